@@ -0,0 +1,610 @@
+//! Array-of-bytes signature scanning.
+//!
+//! The scanner core only ever looks at byte slices and the address they are
+//! mapped at, so it works the same on the live game module, on an executable
+//! read from disk, and on synthetic buffers.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::prelude::base_addresses::BaseAddresses;
+
+/// A byte pattern with wildcards, e.g. `48 8B 05 ?? ?? ?? ?? 48 85 C0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern(Vec<Option<u8>>);
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s
+            .split_whitespace()
+            .map(|b| match b {
+                "?" | "??" => Ok(None),
+                b => u8::from_str_radix(b, 16)
+                    .map(Some)
+                    .map_err(|e| format!("Invalid byte {b:?} in pattern: {e}")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if bytes.is_empty() {
+            return Err("Empty pattern".to_string());
+        }
+
+        Ok(Pattern(bytes))
+    }
+}
+
+impl Pattern {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn matches(&self, window: &[u8]) -> bool {
+        self.0.iter().zip(window).all(|(p, b)| p.map(|p| p == *b).unwrap_or(true))
+    }
+
+    /// Returns the offsets of all the matches of the pattern in `bytes`.
+    pub fn find_iter<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        bytes.windows(self.0.len()).enumerate().filter(|(_, w)| self.matches(w)).map(|(i, _)| i)
+    }
+
+    /// Returns the offset of the first match of the pattern in `bytes`.
+    pub fn find(&self, bytes: &[u8]) -> Option<usize> {
+        self.find_iter(bytes).next()
+    }
+}
+
+/// How to turn the position of a match into a value.
+#[derive(Debug, Clone, Copy)]
+pub enum AobKind {
+    /// The address of the match itself.
+    Direct,
+    /// The 32-bit value at `offset` from the match.
+    Indirect { offset: usize },
+    /// A RIP-relative address: the 32-bit displacement at `offset_read` from
+    /// the match, added to the address of the next instruction, which starts
+    /// at `offset_instruction` from the match.
+    IndirectTwice { offset_read: usize, offset_instruction: usize },
+}
+
+/// A named signature. Each of the `patterns` is tried in order until one
+/// matches.
+#[derive(Debug, Clone, Copy)]
+pub struct Aob {
+    pub name: &'static str,
+    pub patterns: &'static [&'static str],
+    pub kind: AobKind,
+    /// Whether the result is an address within the module, and should be
+    /// stored relative to the module base.
+    pub relative: bool,
+}
+
+impl Aob {
+    pub const fn direct(
+        name: &'static str,
+        patterns: &'static [&'static str],
+        relative: bool,
+    ) -> Aob {
+        Aob { name, patterns, kind: AobKind::Direct, relative }
+    }
+
+    pub const fn indirect(
+        name: &'static str,
+        patterns: &'static [&'static str],
+        offset: usize,
+        relative: bool,
+    ) -> Aob {
+        Aob { name, patterns, kind: AobKind::Indirect { offset }, relative }
+    }
+
+    pub const fn indirect_twice(
+        name: &'static str,
+        patterns: &'static [&'static str],
+        offset_read: usize,
+        offset_instruction: usize,
+        relative: bool,
+    ) -> Aob {
+        Aob {
+            name,
+            patterns,
+            kind: AobKind::IndirectTwice { offset_read, offset_instruction },
+            relative,
+        }
+    }
+
+    /// Returns the matches of the first pattern that matches anything in
+    /// `bytes`, which are mapped at `address`.
+    pub fn find_all(&self, bytes: &[u8], address: usize) -> Result<Vec<usize>, String> {
+        for pattern in self.patterns {
+            let pattern: Pattern = pattern.parse()?;
+            let values: Vec<usize> = pattern
+                .find_iter(bytes)
+                .filter_map(|pos| self.resolve(bytes, address, pos))
+                .collect();
+
+            if !values.is_empty() {
+                return Ok(values);
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Returns the value of the first match in `bytes`, which are mapped at
+    /// `address`. Addresses are absolute; use [`scan`] to get them relative
+    /// to the module base.
+    pub fn find(&self, bytes: &[u8], address: usize) -> Option<usize> {
+        self.find_all(bytes, address).ok()?.into_iter().next()
+    }
+
+    fn adjust(&self, value: usize, image_base: usize) -> usize {
+        if self.relative {
+            value.wrapping_sub(image_base)
+        } else {
+            value
+        }
+    }
+
+    fn resolve(&self, bytes: &[u8], address: usize, pos: usize) -> Option<usize> {
+        let read_u32 = |offset: usize| {
+            bytes
+                .get(pos + offset..pos + offset + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        };
+
+        match self.kind {
+            AobKind::Direct => Some(address + pos),
+            AobKind::Indirect { offset } => read_u32(offset).map(|v| v as usize),
+            AobKind::IndirectTwice { offset_read, offset_instruction } => read_u32(offset_read)
+                .map(|disp| {
+                    (address + pos + offset_instruction).wrapping_add_signed(disp as i32 as isize)
+                }),
+        }
+    }
+}
+
+/// A section of an image: its bytes and the address they are mapped at.
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'a> {
+    pub name: &'a str,
+    pub address: usize,
+    pub bytes: &'a [u8],
+}
+
+/// Header of a PE section, as found in the section table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionHeader {
+    pub name: String,
+    pub virtual_address: usize,
+    pub virtual_size: usize,
+}
+
+/// Parses the section table out of the headers of a PE image.
+pub fn parse_section_headers(headers: &[u8]) -> Result<Vec<SectionHeader>, String> {
+    let u16_at = |offset: usize| {
+        headers
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes(b.try_into().unwrap()) as usize)
+            .ok_or_else(|| format!("PE headers truncated at {offset:#x}"))
+    };
+    let u32_at = |offset: usize| {
+        headers
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+            .ok_or_else(|| format!("PE headers truncated at {offset:#x}"))
+    };
+
+    if headers.get(0..2) != Some(b"MZ") {
+        return Err("Missing DOS signature".to_string());
+    }
+
+    let nt_offset = u32_at(0x3c)?;
+    if headers.get(nt_offset..nt_offset + 4) != Some(b"PE\0\0") {
+        return Err("Missing PE signature".to_string());
+    }
+
+    let file_header = nt_offset + 4;
+    let section_count = u16_at(file_header + 2)?;
+    let optional_header_size = u16_at(file_header + 16)?;
+    let section_table = file_header + 20 + optional_header_size;
+
+    (0..section_count)
+        .map(|i| {
+            let offset = section_table + i * 40;
+            let name = headers
+                .get(offset..offset + 8)
+                .ok_or_else(|| format!("PE headers truncated at {offset:#x}"))?;
+            let name = String::from_utf8_lossy(name).trim_end_matches('\0').to_string();
+
+            Ok(SectionHeader {
+                name,
+                virtual_size: u32_at(offset + 8)?,
+                virtual_address: u32_at(offset + 12)?,
+            })
+        })
+        .collect()
+}
+
+/// The sections of an image that signatures are looked for in: code,
+/// read-only data such as the `FormatString` text, and data.
+const SCANNED_SECTIONS: &[&str] = &[".text", ".rdata", ".data"];
+
+fn scanned<'a, 'b>(sections: &'a [Section<'b>]) -> impl Iterator<Item = &'a Section<'b>> {
+    sections.iter().filter(|section| SCANNED_SECTIONS.contains(&section.name))
+}
+
+/// Resolves `aob` against the `.text`, `.rdata` and `.data` sections of an
+/// image loaded at `image_base`. Relative results are returned as offsets from
+/// `image_base`.
+pub fn scan(aob: &Aob, sections: &[Section], image_base: usize) -> Option<usize> {
+    scanned(sections)
+        .find_map(|section| aob.find(section.bytes, section.address))
+        .map(|value| aob.adjust(value, image_base))
+}
+
+/// Resolves every match of `aob` like [`scan`] does, in scan order and without
+/// duplicates, so the first value is the one [`scan`] returns. More than one
+/// value means the signature is ambiguous.
+pub fn scan_matches(aob: &Aob, sections: &[Section], image_base: usize) -> Vec<usize> {
+    let mut values = Vec::new();
+    for section in scanned(sections) {
+        for value in aob.find_all(section.bytes, section.address).unwrap_or_default() {
+            let value = aob.adjust(value, image_base);
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+    values
+}
+
+/// Resolves all the `aobs`. Returns the names of the signatures that did not
+/// match as an error.
+pub fn scan_all(
+    aobs: &[Aob],
+    sections: &[Section],
+    image_base: usize,
+) -> Result<HashMap<&'static str, usize>, Vec<&'static str>> {
+    let mut found = HashMap::new();
+    let mut missing = Vec::new();
+
+    for aob in aobs {
+        match scan(aob, sections, image_base) {
+            Some(value) => {
+                found.insert(aob.name, value);
+            },
+            None => missing.push(aob.name),
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(found)
+    } else {
+        Err(missing)
+    }
+}
+
+/// The signatures of all the base addresses. Keep in sync with the names of
+/// the `BaseAddresses` fields.
+pub const BASE_ADDRESSES_AOBS: &[Aob] = &[
+    Aob::indirect_twice(
+        "WorldChrMan",
+        &["48 8B 1D ?? ?? ?? 04 48 8B F9 48 85 DB ?? ?? 8B 11 85 D2 ?? ?? 8D"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "WorldChrManDbg",
+        &["48 8B 05 ?? ?? ?? ?? 66 0F 7F 44 24 40 48 85 C0"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "MenuMan",
+        &["48 89 15 ?? ?? ?? ?? 44 8b 82 ?? ?? ?? ?? 44 8b 8a ?? ?? ?? ?? 48 8b c3"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "BaseA",
+        &["48 8B 05 ?? ?? ?? ?? 48 85 C0 ?? ?? 48 8b 40 ?? C3"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice("BaseD", &["48 8B 0D ?? ?? ?? ?? 48 85 C9 74 26 44 8B"], 3, 7, true),
+    Aob::indirect_twice("SprjDebugEvent", &["48 8B 05 ?? ?? ?? ?? 41 0F B6 D8 8B EA"], 3, 7, true),
+    Aob::indirect_twice(
+        "Debug",
+        &["C6 05 ?? ?? ?? ?? 01 48 8B 8C 24 ?? ?? ?? ?? 48 33 CC E8 ?? ?? ?? ?? 4C 8D 9C 24"],
+        2,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "Grend",
+        &["C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? 00 \
+           4C 8B 05 ?? ?? ?? ?? 4C 89 44 24 58"],
+        2,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "BaseHBD",
+        &["48 8B 0D ?? ?? ?? ?? 41 B0 01 E8 ?? ?? ?? ?? 48 8B D3 48 8B CF"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "MapItemMan",
+        &["48 8B 0D ?? ?? ?? ?? 48 8B 89 ?? ?? ?? ?? E8 ?? ?? ?? ?? E9"],
+        3,
+        7,
+        true,
+    ),
+    Aob::indirect_twice(
+        "SpawnItemFuncPtr",
+        &["E8 ?? ?? ?? ?? C7 44 24 20 00 01 00 00 4C 8D 4C 24 40 41 B8"],
+        1,
+        5,
+        true,
+    ),
+    Aob::indirect_twice(
+        "Param",
+        &["48 8B 0D ?? ?? ?? ?? 48 85 C9 74 0B 4C 8B C0 48 8B D7"],
+        3,
+        7,
+        true,
+    ),
+    Aob::direct(
+        "FormatString",
+        &["3C 00 54 00 45 00 58 00 54 00 46 00 4F 00 52 00 4D 00 41 00 54 00"],
+        true,
+    ),
+    Aob::direct("NoLogo", &["E8 ?? ?? ?? FF 90 4D 8B C7 49 8B D4 48 8B C8 E8 ?? ?? ?? FF"], true),
+    Aob::direct("CurrentTarget", &["48 8B 80 ?? ?? ?? ?? 48 8B 08 48 8B ?? 58"], true),
+    Aob::direct(
+        "MenuTravel",
+        &["40 55 53 56 57 41 56 48 8D 6C 24 C9 48 81 EC 00 01 00 00 48 C7 45 97 FE FF FF FF"],
+        true,
+    ),
+    Aob::direct(
+        "MenuAttune",
+        &["48 8D 45 0F 48 89 45 EF 48 8D 45 0F 48 89 45 F7 48 8D ?? ?? ?? ?? ?? 48 89 45 0F 48 \
+           8D ?? ?? ?? ?? ?? 48 89 45 0F 48 8D ?? ?? ?? ?? ?? 48 89 45 17"],
+        true,
+    ),
+    Aob::indirect("XA", &["48 8B 83 ?? ?? ?? ?? 48 8B 10 48 85 D2 ?? ?? 8B"], 3, false),
+    Aob::indirect_twice(
+        "BaseFPS",
+        &["48 8B 0D ?? ?? ?? ?? 84 C0 74 32 48 85 C9 75 26 4C 8D 0D ?? ?? ?? ?? 4C 8D 05 ?? ?? \
+           ?? ?? 48 8D 0D ?? ?? ?? ??"],
+        3,
+        7,
+        true,
+    ),
+];
+
+/// Builds the base addresses out of the results of [`scan_all`] on
+/// [`BASE_ADDRESSES_AOBS`].
+pub fn base_addresses_from_scan(
+    found: &HashMap<&'static str, usize>,
+) -> Result<BaseAddresses, String> {
+    let get = |name: &str| {
+        found.get(name).copied().ok_or_else(|| format!("Base address {name} not found"))
+    };
+
+    Ok(BaseAddresses {
+        world_chr_man: get("WorldChrMan")?,
+        world_chr_man_dbg: get("WorldChrManDbg")?,
+        menu_man: get("MenuMan")?,
+        base_a: get("BaseA")?,
+        base_d: get("BaseD")?,
+        sprj_debug_event: get("SprjDebugEvent")?,
+        debug: get("Debug")?,
+        grend: get("Grend")?,
+        base_hbd: get("BaseHBD")?,
+        map_item_man: get("MapItemMan")?,
+        spawn_item_func_ptr: get("SpawnItemFuncPtr")?,
+        param: get("Param")?,
+        format_string: get("FormatString")?,
+        no_logo: get("NoLogo")?,
+        current_target: get("CurrentTarget")?,
+        menu_travel: get("MenuTravel")?,
+        menu_attune: get("MenuAttune")?,
+        xa: get("XA")?,
+        base_fps: get("BaseFPS")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_parse() {
+        let pattern: Pattern = "48 8b ?? ? C3".parse().unwrap();
+        assert_eq!(pattern, Pattern(vec![Some(0x48), Some(0x8b), None, None, Some(0xc3)]));
+
+        assert!("48 ZZ".parse::<Pattern>().is_err());
+        assert!("".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn test_pattern_find() {
+        let bytes = [0x00, 0x48, 0x8b, 0x01, 0xc3, 0x48, 0x8b, 0x02, 0xc3];
+        let pattern: Pattern = "48 8B ?? C3".parse().unwrap();
+
+        assert_eq!(pattern.find(&bytes), Some(1));
+        assert_eq!(pattern.find_iter(&bytes).collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!("48 8B ?? C4".parse::<Pattern>().unwrap().find(&bytes), None);
+    }
+
+    #[test]
+    fn test_aob_direct() {
+        let mut bytes = vec![0xccu8; 0x100];
+        bytes[0x40..0x44].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let aob = Aob::direct("Test", &["DE AD BE EF"], true);
+        assert_eq!(aob.find(&bytes, 0x140001000), Some(0x140001040));
+
+        let sections = [Section { name: ".text", address: 0x140001000, bytes: &bytes }];
+        assert_eq!(scan(&aob, &sections, 0x140000000), Some(0x1040));
+    }
+
+    #[test]
+    fn test_aob_indirect() {
+        let mut bytes = vec![0xccu8; 0x100];
+        // mov rax, [rbx + 0x1f70]
+        bytes[0x10..0x17].copy_from_slice(&[0x48, 0x8b, 0x83, 0x70, 0x1f, 0x00, 0x00]);
+
+        let aob = Aob::indirect("XA", &["48 8B 83 ?? ?? ?? ??"], 3, false);
+        let sections = [Section { name: ".text", address: 0x140001000, bytes: &bytes }];
+        assert_eq!(scan(&aob, &sections, 0x140000000), Some(0x1f70));
+    }
+
+    #[test]
+    fn test_aob_indirect_twice() {
+        let mut bytes = vec![0xccu8; 0x1000];
+        // mov rbx, [rip + 0x200] at 0x140001100: points to 0x140001307
+        bytes[0x100..0x107].copy_from_slice(&[0x48, 0x8b, 0x1d, 0x00, 0x02, 0x00, 0x00]);
+        // mov rcx, [rip - 0x100] at 0x140001800: points to 0x140001707
+        bytes[0x800..0x807].copy_from_slice(&[0x48, 0x8b, 0x0d, 0x00, 0xff, 0xff, 0xff]);
+
+        let sections = [Section { name: ".text", address: 0x140001000, bytes: &bytes }];
+
+        let aob = Aob::indirect_twice("Fwd", &["48 8B 1D ?? ?? ?? ??"], 3, 7, true);
+        assert_eq!(scan(&aob, &sections, 0x140000000), Some(0x1307));
+
+        let aob = Aob::indirect_twice("Back", &["FF FE", "48 8B 0D ?? ?? ?? ??"], 3, 7, true);
+        assert_eq!(scan(&aob, &sections, 0x140000000), Some(0x1707));
+    }
+
+    #[test]
+    fn test_scan_all_missing() {
+        let bytes = [0x90u8; 0x40];
+        let sections = [Section { name: ".text", address: 0x1000, bytes: &bytes }];
+        let aobs = [Aob::direct("Nop", &["90 90"], true), Aob::direct("Ret", &["C3"], true)];
+
+        assert_eq!(scan_all(&aobs, &sections, 0), Err(vec!["Ret"]));
+    }
+
+    #[test]
+    fn test_scan_matches() {
+        let text = [0x90, 0xc3, 0x90, 0x90, 0xc3];
+        let data = [0x00, 0x90, 0xc3];
+        let sections = [Section { name: ".text", address: 0x1000, bytes: &text }, Section {
+            name: ".data",
+            address: 0x2000,
+            bytes: &data,
+        }];
+
+        let aob = Aob::direct("NopRet", &["90 C3"], true);
+        assert_eq!(scan_matches(&aob, &sections, 0x1000), vec![0x0, 0x3, 0x1001]);
+        assert_eq!(scan(&aob, &sections, 0x1000), Some(0x0));
+
+        // Matches are in scan order, not sorted, with duplicates dropped.
+        let aob = Aob::indirect("Ptr", &["90 C3 ?? ?? ?? ??"], 2, false);
+        let text = [0x90, 0xc3, 0x00, 0x30, 0x00, 0x00, 0x90, 0xc3, 0x00, 0x20, 0x00, 0x00];
+        let data = [0x90, 0xc3, 0x00, 0x30, 0x00, 0x00];
+        let sections = [Section { name: ".text", address: 0x1000, bytes: &text }, Section {
+            name: ".data",
+            address: 0x2000,
+            bytes: &data,
+        }];
+        assert_eq!(scan_matches(&aob, &sections, 0x1000), vec![0x3000, 0x2000]);
+        assert_eq!(scan(&aob, &sections, 0x1000), Some(0x3000));
+
+        let aob = Aob::direct("Int3", &["CC"], true);
+        assert!(scan_matches(&aob, &sections, 0x1000).is_empty());
+    }
+
+    #[test]
+    fn test_scan_sections() {
+        let rsrc = [0x90, 0xc3];
+        let rdata = [0x00, 0x00, 0x90, 0xc3];
+        let text = [0x00, 0x90, 0xc3];
+        let sections = [
+            Section { name: ".rsrc", address: 0x1000, bytes: &rsrc },
+            Section { name: ".rdata", address: 0x2000, bytes: &rdata },
+            Section { name: ".text", address: 0x3000, bytes: &text },
+        ];
+
+        let aob = Aob::direct("NopRet", &["90 C3"], true);
+        assert_eq!(scan(&aob, &sections, 0), Some(0x2002));
+        assert_eq!(scan_matches(&aob, &sections, 0), vec![0x2002, 0x3001]);
+
+        // Data signatures such as the format string live in `.rdata`.
+        let mut rdata = vec![0u8; 0x40];
+        let format_string: Vec<u8> =
+            "<TEXTFORMAT".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        rdata[0x10..0x10 + format_string.len()].copy_from_slice(&format_string);
+        let sections = [Section { name: ".rdata", address: 0x140005000, bytes: &rdata }];
+
+        let aob = BASE_ADDRESSES_AOBS.iter().find(|aob| aob.name == "FormatString").unwrap();
+        assert_eq!(scan(aob, &sections, 0x140000000), Some(0x5010));
+    }
+
+    #[test]
+    fn test_base_addresses_aobs() {
+        for aob in BASE_ADDRESSES_AOBS {
+            for pattern in aob.patterns {
+                assert!(pattern.parse::<Pattern>().is_ok(), "{}: {pattern}", aob.name);
+            }
+        }
+
+        let found = BASE_ADDRESSES_AOBS.iter().map(|aob| (aob.name, 0)).collect();
+        assert!(base_addresses_from_scan(&found).is_ok());
+    }
+
+    #[test]
+    fn test_parse_section_headers() {
+        let mut headers = vec![0u8; 0x400];
+        headers[0..2].copy_from_slice(b"MZ");
+        headers[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        headers[0x80..0x84].copy_from_slice(b"PE\0\0");
+        // NumberOfSections, SizeOfOptionalHeader
+        headers[0x86..0x88].copy_from_slice(&2u16.to_le_bytes());
+        headers[0x94..0x96].copy_from_slice(&0xf0u16.to_le_bytes());
+
+        let section_table = 0x80 + 4 + 20 + 0xf0;
+        for (i, (name, va, size)) in
+            [(b".text\0\0\0", 0x1000u32, 0x2345u32), (b".data\0\0\0", 0x4000, 0x100)]
+                .into_iter()
+                .enumerate()
+        {
+            let offset = section_table + i * 40;
+            headers[offset..offset + 8].copy_from_slice(name);
+            headers[offset + 8..offset + 12].copy_from_slice(&size.to_le_bytes());
+            headers[offset + 12..offset + 16].copy_from_slice(&va.to_le_bytes());
+        }
+
+        assert_eq!(
+            parse_section_headers(&headers),
+            Ok(vec![
+                SectionHeader {
+                    name: ".text".to_string(),
+                    virtual_address: 0x1000,
+                    virtual_size: 0x2345
+                },
+                SectionHeader {
+                    name: ".data".to_string(),
+                    virtual_address: 0x4000,
+                    virtual_size: 0x100
+                },
+            ])
+        );
+
+        headers[0x80] = 0;
+        assert!(parse_section_headers(&headers).is_err());
+    }
+}
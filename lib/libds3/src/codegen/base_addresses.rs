@@ -25,6 +25,27 @@ pub struct BaseAddresses {
 }
 
 impl BaseAddresses {
+    pub const NAMES: &'static [&'static str] = &[
+        "world_chr_man",
+        "world_chr_man_dbg",
+        "menu_man",
+        "base_a",
+        "base_d",
+        "sprj_debug_event",
+        "debug",
+        "grend",
+        "base_hbd",
+        "map_item_man",
+        "spawn_item_func_ptr",
+        "param",
+        "format_string",
+        "no_logo",
+        "current_target",
+        "menu_travel",
+        "menu_attune",
+        "base_fps",
+    ];
+
     pub fn with_module_base_addr(self, base: usize) -> BaseAddresses {
         BaseAddresses {
             world_chr_man: self.world_chr_man + base,
@@ -48,6 +69,30 @@ impl BaseAddresses {
             base_fps: self.base_fps + base,
         }
     }
+
+    pub fn get(&self, name: &str) -> Option<usize> {
+        match name {
+            "world_chr_man" => Some(self.world_chr_man),
+            "world_chr_man_dbg" => Some(self.world_chr_man_dbg),
+            "menu_man" => Some(self.menu_man),
+            "base_a" => Some(self.base_a),
+            "base_d" => Some(self.base_d),
+            "sprj_debug_event" => Some(self.sprj_debug_event),
+            "debug" => Some(self.debug),
+            "grend" => Some(self.grend),
+            "base_hbd" => Some(self.base_hbd),
+            "map_item_man" => Some(self.map_item_man),
+            "spawn_item_func_ptr" => Some(self.spawn_item_func_ptr),
+            "param" => Some(self.param),
+            "format_string" => Some(self.format_string),
+            "no_logo" => Some(self.no_logo),
+            "current_target" => Some(self.current_target),
+            "menu_travel" => Some(self.menu_travel),
+            "menu_attune" => Some(self.menu_attune),
+            "base_fps" => Some(self.base_fps),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -74,6 +119,29 @@ pub enum Version {
     V1_15_2,
 }
 
+pub const VERSIONS: &[Version] = &[
+    Version::V1_01_1,
+    Version::V1_03_1,
+    Version::V1_03_2,
+    Version::V1_04_1,
+    Version::V1_04_2,
+    Version::V1_04_3,
+    Version::V1_05_0,
+    Version::V1_05_1,
+    Version::V1_06_0,
+    Version::V1_07_0,
+    Version::V1_08_0,
+    Version::V1_09_0,
+    Version::V1_10_0,
+    Version::V1_11_0,
+    Version::V1_12_0,
+    Version::V1_13_0,
+    Version::V1_14_0,
+    Version::V1_15_0,
+    Version::V1_15_1,
+    Version::V1_15_2,
+];
+
 impl From<(u32, u32, u32)> for Version {
     fn from(v: (u32, u32, u32)) -> Self {
         match v {
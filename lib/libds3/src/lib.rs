@@ -1,5 +1,7 @@
+pub mod aob;
 pub mod codegen;
 pub mod memedit;
+pub mod offsets;
 pub mod params;
 pub mod pointers;
 pub mod version;
@@ -33,9 +35,18 @@ pub trait ParamVisitor {
     fn visit_i32(&mut self, name: &str, v: &mut i32);
     fn visit_f32(&mut self, name: &str, v: &mut f32);
     fn visit_bool(&mut self, name: &str, v: &mut bool);
+
+    /// Whether fields marked as padding are visited. Array elements are
+    /// visited one by one, named `field[index]`.
+    fn visit_padding(&self) -> bool {
+        false
+    }
 }
 
 pub trait ParamStruct {
+    /// The visited fields, in visit order.
+    const FIELDS: &'static [params::FieldInfo];
+
     fn visit<T: ParamVisitor + ?Sized>(&mut self, t: &mut T);
 }
 
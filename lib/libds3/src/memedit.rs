@@ -1,9 +1,168 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::mem::size_of;
 use std::ops::{BitAnd, BitOr, BitXor, Not};
+use std::slice;
+use std::sync::Arc;
 
+use parking_lot::Mutex;
 use windows::Win32::Foundation::HANDLE;
 use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
 use windows::Win32::System::Threading::GetCurrentProcess;
 
+/// Raw access to an address space. Pointer chains and bitflags go through a
+/// backend instead of touching memory directly, so that they can be pointed
+/// at something other than the live game process.
+pub trait MemoryBackend: Clone {
+    /// Fills `buf` with the bytes at `addr`. Returns `None` if any of the
+    /// bytes could not be read.
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> Option<()>;
+
+    /// Writes `buf` at `addr`. Returns `None` if any of the bytes could not
+    /// be written.
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> Option<()>;
+}
+
+/// Memory of the current process, accessed via `ReadProcessMemory` and
+/// `WriteProcessMemory`.
+#[derive(Clone, Copy, Debug)]
+pub struct ProcessMemory(HANDLE);
+
+impl ProcessMemory {
+    pub fn current() -> Self {
+        ProcessMemory(unsafe { GetCurrentProcess() })
+    }
+}
+
+impl Default for ProcessMemory {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+impl MemoryBackend for ProcessMemory {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> Option<()> {
+        unsafe { ReadProcessMemory(self.0, addr as _, buf.as_mut_ptr() as _, buf.len(), None).ok() }
+    }
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> Option<()> {
+        unsafe { WriteProcessMemory(self.0, addr as _, buf.as_ptr() as _, buf.len(), None).ok() }
+    }
+}
+
+const FAKE_PAGE_SIZE: usize = 0x1000;
+
+/// Sparse, page-granular fake address space. Reads and writes touching a
+/// page that was never mapped fail, just like they would on an invalid
+/// address in the game.
+///
+/// Clones share the same pages, so a test can hand clones to several pointer
+/// chains and inspect the results afterwards.
+#[derive(Clone, Default)]
+pub struct FakeMemory(Arc<Mutex<BTreeMap<usize, Box<[u8; FAKE_PAGE_SIZE]>>>>);
+
+impl FakeMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps zeroed pages covering `addr..addr + len`. Pages already mapped
+    /// keep their contents. Ranges past the end of the address space are
+    /// not mapped.
+    pub fn map(&self, addr: usize, len: usize) {
+        let Some(end) = addr.checked_add(len.max(1) - 1) else {
+            return;
+        };
+        let mut pages = self.0.lock();
+        let first = addr / FAKE_PAGE_SIZE;
+        let last = end / FAKE_PAGE_SIZE;
+        for page in first..=last {
+            pages.entry(page).or_insert_with(|| Box::new([0; FAKE_PAGE_SIZE]));
+        }
+    }
+
+    /// Maps the pages covering the value and writes it.
+    pub fn write_value<T: Copy>(&self, addr: usize, value: T) {
+        self.map(addr, size_of::<T>());
+        self.write_bytes(addr, as_bytes(&value)).unwrap();
+    }
+
+    /// Stores a pointer to `target` at `addr`. Convenience for building
+    /// pointer graphs.
+    pub fn write_pointer(&self, addr: usize, target: usize) {
+        self.write_value(addr, target);
+    }
+
+    pub fn read_value<T: Copy>(&self, addr: usize) -> Option<T> {
+        let mut value: T = unsafe { std::mem::zeroed() };
+        self.read_bytes(addr, as_bytes_mut(&mut value))?;
+        Some(value)
+    }
+
+    /// Creates a pointer chain evaluated against this address space.
+    pub fn pointer_chain<T>(&self, chain: &[usize]) -> PointerChain<T, FakeMemory> {
+        PointerChain::with_backend(self.clone(), chain)
+    }
+
+    fn for_each_chunk<F: FnMut(&mut [u8; FAKE_PAGE_SIZE], usize, usize, usize)>(
+        &self,
+        addr: usize,
+        len: usize,
+        mut f: F,
+    ) -> Option<()> {
+        let end = addr.checked_add(len)?;
+        let mut pages = self.0.lock();
+
+        // Check everything up front so that a failed write leaves no trace.
+        if len > 0 {
+            for page in addr / FAKE_PAGE_SIZE..=(end - 1) / FAKE_PAGE_SIZE {
+                pages.get(&page)?;
+            }
+        }
+
+        let mut cursor = addr;
+        while cursor < end {
+            let page = pages.get_mut(&(cursor / FAKE_PAGE_SIZE))?;
+            let page_offset = cursor % FAKE_PAGE_SIZE;
+            let count = (FAKE_PAGE_SIZE - page_offset).min(end - cursor);
+            f(page, page_offset, cursor - addr, count);
+            cursor += count;
+        }
+
+        Some(())
+    }
+}
+
+impl Debug for FakeMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FakeMemory {{ pages: {} }}", self.0.lock().len())
+    }
+}
+
+impl MemoryBackend for FakeMemory {
+    fn read_bytes(&self, addr: usize, buf: &mut [u8]) -> Option<()> {
+        self.for_each_chunk(addr, buf.len(), |page, page_offset, buf_offset, count| {
+            buf[buf_offset..buf_offset + count]
+                .copy_from_slice(&page[page_offset..page_offset + count]);
+        })
+    }
+
+    fn write_bytes(&self, addr: usize, buf: &[u8]) -> Option<()> {
+        self.for_each_chunk(addr, buf.len(), |page, page_offset, buf_offset, count| {
+            page[page_offset..page_offset + count]
+                .copy_from_slice(&buf[buf_offset..buf_offset + count]);
+        })
+    }
+}
+
+fn as_bytes<T>(value: &T) -> &[u8] {
+    unsafe { slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+fn as_bytes_mut<T>(value: &mut T) -> &mut [u8] {
+    unsafe { slice::from_raw_parts_mut(value as *mut T as *mut u8, size_of::<T>()) }
+}
+
 /// Wraps CheatEngine's concept of pointer with nested offsets. Evaluates,
 /// if the evaluation does not fail, to a mutable pointer of type `T`.
 ///
@@ -11,34 +170,45 @@ use windows::Win32::System::Threading::GetCurrentProcess;
 /// base pointer, then recursively reading the next memory address in the
 /// chain at an offset from there. For example,
 ///
-/// ```
+/// ```text
 /// PointerChain::<T>::new(&[a, b, c, d, e])
 /// ```
 ///
 /// evaluates to
 ///
-/// ```
+/// ```text
 /// *(*(*(*(*a + b) + c) + d) + e)
 /// ```
 ///
 /// This is useful for managing reverse engineered structures which are not
 /// fully known.
+///
+/// All the memory accesses go through `M`, which is the current process'
+/// memory by default.
 #[derive(Clone, Debug)]
-pub struct PointerChain<T> {
-    proc: HANDLE,
+pub struct PointerChain<T, M = ProcessMemory> {
+    mem: M,
     base: *mut T,
     offsets: Vec<usize>,
 }
-unsafe impl<T> Send for PointerChain<T> {}
-unsafe impl<T> Sync for PointerChain<T> {}
+unsafe impl<T, M: Send> Send for PointerChain<T, M> {}
+unsafe impl<T, M: Sync> Sync for PointerChain<T, M> {}
 
 impl<T> PointerChain<T> {
     /// Creates a new pointer chain given an array of addresses.
     pub fn new(chain: &[usize]) -> PointerChain<T> {
+        PointerChain::with_backend(ProcessMemory::current(), chain)
+    }
+}
+
+impl<T, M: MemoryBackend> PointerChain<T, M> {
+    /// Creates a new pointer chain given an array of addresses, evaluated
+    /// against the `mem` backend.
+    pub fn with_backend(mem: M, chain: &[usize]) -> PointerChain<T, M> {
         let mut it = chain.iter();
         let base = *it.next().unwrap() as *mut T;
         PointerChain {
-            proc: unsafe { GetCurrentProcess() },
+            mem,
             base,
             offsets: it.copied().collect(), // it.map(|x| *x).collect(),
         }
@@ -46,22 +216,12 @@ impl<T> PointerChain<T> {
 
     fn safe_read(&self, addr: usize, offs: usize) -> Option<usize> {
         let mut value = 0usize;
-        unsafe {
-            ReadProcessMemory(
-                self.proc,
-                addr as _,
-                &mut value as *mut usize as _,
-                std::mem::size_of::<usize>(),
-                None,
-            )
-            .ok()
-            .map(|_| value + offs)
-        }
+        self.mem.read_bytes(addr, as_bytes_mut(&mut value)).map(|_| value + offs)
     }
 
     /// Safely evaluates the pointer chain.
-    /// Relies on `ReadProcessMemory` instead of pointer dereferencing for crash
-    /// safety.  Returns `None` if the evaluation failed.
+    /// Relies on the memory backend instead of pointer dereferencing for
+    /// crash safety.  Returns `None` if the evaluation failed.
     pub fn eval(&self) -> Option<*mut T> {
         self.offsets
             .iter()
@@ -74,45 +234,29 @@ impl<T> PointerChain<T> {
     pub fn read(&self) -> Option<T> {
         let ptr = self.eval()?;
         let mut value: T = unsafe { std::mem::zeroed() };
-        unsafe {
-            ReadProcessMemory(
-                self.proc,
-                ptr as _,
-                &mut value as *mut _ as _,
-                std::mem::size_of::<T>(),
-                None,
-            )
-            .ok()
-            .map(|_| value)
-        }
+        self.mem.read_bytes(ptr as usize, as_bytes_mut(&mut value)).map(|_| value)
     }
 
     /// Evaluates the pointer chain and attempts to write the datum.
     /// Returns `None` if either the evaluation or the write failed.
-    pub fn write(&self, mut value: T) -> Option<()> {
+    pub fn write(&self, value: T) -> Option<()> {
         let ptr = self.eval()?;
-        unsafe {
-            WriteProcessMemory(
-                self.proc,
-                ptr as _,
-                &mut value as *mut _ as _,
-                std::mem::size_of::<T>(),
-                None,
-            )
-            .ok()
-            .map(|_| ())
-        }
+        self.mem.write_bytes(ptr as usize, as_bytes(&value))
     }
 
-    pub fn cast<S>(&self) -> PointerChain<S> {
-        PointerChain { proc: self.proc, base: self.base as *mut S, offsets: self.offsets.clone() }
+    pub fn cast<S>(&self) -> PointerChain<S, M> {
+        PointerChain {
+            mem: self.mem.clone(),
+            base: self.base as *mut S,
+            offsets: self.offsets.clone(),
+        }
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct Bitflag<T>(PointerChain<T>, T);
+pub struct Bitflag<T, M = ProcessMemory>(PointerChain<T, M>, T);
 
-impl<T> Bitflag<T>
+impl<T, M> Bitflag<T, M>
 where
     T: BitXor<Output = T>
         + BitAnd<Output = T>
@@ -120,8 +264,9 @@ where
         + Not<Output = T>
         + PartialEq
         + Copy,
+    M: MemoryBackend,
 {
-    pub fn new(c: PointerChain<T>, mask: T) -> Self {
+    pub fn new(c: PointerChain<T, M>, mask: T) -> Self {
         Bitflag(c, mask)
     }
 
@@ -153,3 +298,81 @@ macro_rules! bitflag {
 }
 
 pub use {bitflag, pointer_chain};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_memory_pointer_chain() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x20000);
+        mem.write_pointer(0x20080, 0x300000);
+        mem.write_value(0x300010, 42u32);
+
+        let chain = mem.pointer_chain::<u32>(&[0x1000, 0x80, 0x10]);
+        assert_eq!(chain.eval(), Some(0x300010 as *mut u32));
+        assert_eq!(chain.read(), Some(42));
+
+        chain.write(1337).unwrap();
+        assert_eq!(mem.read_value::<u32>(0x300010), Some(1337));
+    }
+
+    #[test]
+    fn test_fake_memory_unmapped() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0xdead0000);
+
+        let chain = mem.pointer_chain::<u32>(&[0x1000, 0x10, 0x0]);
+        assert_eq!(chain.eval(), None);
+        assert_eq!(chain.read(), None);
+        assert_eq!(chain.write(1), None);
+    }
+
+    #[test]
+    fn test_fake_memory_page_boundary() {
+        let mem = FakeMemory::new();
+        mem.write_value(FAKE_PAGE_SIZE - 2, [1u8, 2, 3, 4]);
+        assert_eq!(mem.read_value::<[u8; 4]>(FAKE_PAGE_SIZE - 2), Some([1, 2, 3, 4]));
+
+        // The second page is not mapped: the write must fail without touching
+        // the first one.
+        mem.map(FAKE_PAGE_SIZE * 4, 1);
+        assert_eq!(mem.write_bytes(FAKE_PAGE_SIZE * 5 - 1, &[0xff, 0xff]), None);
+        assert_eq!(mem.read_value::<u8>(FAKE_PAGE_SIZE * 5 - 1), Some(0));
+    }
+
+    #[test]
+    fn test_fake_memory_end_of_address_space() {
+        let mem = FakeMemory::new();
+        mem.write_value(usize::MAX - 4, [1u8, 2, 3, 4]);
+        assert_eq!(mem.read_value::<[u8; 4]>(usize::MAX - 4), Some([1, 2, 3, 4]));
+
+        // Ranges that wrap around are neither mapped nor accessed.
+        assert_eq!(mem.read_value::<u32>(usize::MAX - 1), None);
+        assert_eq!(mem.write_bytes(usize::MAX - 1, &[0xff, 0xff]), None);
+        assert_eq!(mem.read_value::<u8>(usize::MAX - 1), Some(4));
+
+        let mem = FakeMemory::new();
+        mem.map(usize::MAX - 1, 4);
+        assert_eq!(mem.read_value::<u8>(usize::MAX - 1), None);
+        assert_eq!(mem.read_value::<u8>(0), None);
+    }
+
+    #[test]
+    fn test_bitflag() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_value(0x2008, 0b0101u8);
+
+        let flag = Bitflag::new(mem.pointer_chain::<u8>(&[0x1000, 0x8]), 0b0010u8);
+        assert_eq!(flag.get(), Some(false));
+
+        flag.toggle();
+        assert_eq!(flag.get(), Some(true));
+        assert_eq!(mem.read_value::<u8>(0x2008), Some(0b0111));
+
+        flag.set(false);
+        assert_eq!(mem.read_value::<u8>(0x2008), Some(0b0101));
+    }
+}
@@ -0,0 +1,178 @@
+//! Version-specific struct offsets, loaded from the embedded `offsets.toml`.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+
+use crate::prelude::Version;
+
+pub static OFFSET_TABLE: Lazy<OffsetTable> = Lazy::new(|| {
+    include_str!("offsets.toml").parse().unwrap_or_else(|e| panic!("Invalid offsets.toml: {e}"))
+});
+
+type VersionTuple = (u32, u32, u32);
+
+/// Offsets by name, each a list of `(valid from version, value)` pairs
+/// sorted by version.
+#[derive(Debug)]
+pub struct OffsetTable(BTreeMap<String, Vec<(VersionTuple, usize)>>);
+
+fn parse_version(s: &str) -> Result<VersionTuple, String> {
+    let parts = s
+        .split('.')
+        .map(|p| p.parse::<u32>().map_err(|e| format!("Invalid version {s:?}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match parts[..] {
+        [maj, min, patch] => Ok((maj, min, patch)),
+        _ => Err(format!("Invalid version {s:?}: expected major.minor.patch")),
+    }
+}
+
+impl FromStr for OffsetTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: toml::value::Table = toml::from_str(s).map_err(|e| e.to_string())?;
+
+        table
+            .into_iter()
+            .map(|(name, ranges)| {
+                let ranges = ranges
+                    .as_table()
+                    .ok_or_else(|| format!("{name}: expected a table of versions"))?;
+
+                let mut ranges = ranges
+                    .iter()
+                    .map(|(version, value)| {
+                        let version = parse_version(version).map_err(|e| format!("{name}: {e}"))?;
+                        let value = value
+                            .as_integer()
+                            .and_then(|v| usize::try_from(v).ok())
+                            .ok_or_else(|| format!("{name}: invalid offset {value}"))?;
+                        Ok((version, value))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                ranges.sort_by_key(|&(version, _)| version);
+
+                Ok((name, ranges))
+            })
+            .collect::<Result<BTreeMap<_, _>, String>>()
+            .map(OffsetTable)
+    }
+}
+
+impl OffsetTable {
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Returns the value of the offset `name` for the latest range starting
+    /// at or before `version`.
+    pub fn get(&self, name: &str, version: impl Into<VersionTuple>) -> Result<usize, String> {
+        let version = version.into();
+        let ranges = self.0.get(name).ok_or_else(|| format!("Unknown offset {name}"))?;
+
+        let (maj, min, patch) = version;
+
+        ranges
+            .iter()
+            .rev()
+            .find(|&&(from, _)| from <= version)
+            .map(|&(_, value)| value)
+            .ok_or_else(|| format!("Offset {name} not defined for version {maj}.{min:02}.{patch}"))
+    }
+}
+
+macro_rules! struct_offsets {
+    ($($name:ident),* $(,)?) => {
+        /// Struct offsets resolved for a single version.
+        #[derive(Debug, Clone, Copy)]
+        pub struct StructOffsets {
+            $(pub $name: usize,)*
+        }
+
+        impl StructOffsets {
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            pub fn resolve(table: &OffsetTable, version: Version) -> Result<Self, String> {
+                Ok(StructOffsets { $($name: table.get(stringify!($name), version)?,)* })
+            }
+        }
+    };
+}
+
+struct_offsets! {
+    no_goods_consume,
+    deathcam,
+    bloodstain_draw,
+    speed,
+    igt,
+    debug_draw,
+    ik_foot_ray,
+    anim,
+}
+
+impl StructOffsets {
+    /// Offsets for the running version.
+    ///
+    /// # Panics
+    ///
+    /// If the embedded table is invalid or incomplete, which the tests
+    /// guard against.
+    pub fn current() -> Self {
+        StructOffsets::resolve(&OFFSET_TABLE, *crate::version::VERSION)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::KNOWN_VERSIONS;
+
+    #[test]
+    fn test_every_version_resolves_every_offset() {
+        for &version in KNOWN_VERSIONS {
+            if let Err(e) = StructOffsets::resolve(&OFFSET_TABLE, version) {
+                panic!("{e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_unused_offsets() {
+        for name in OFFSET_TABLE.names() {
+            assert!(StructOffsets::NAMES.contains(&name), "Unused offset {name}");
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        let table: OffsetTable = r#"
+            [foo]
+            "1.08.0" = 0x20
+            "1.01.1" = 0x10
+            "1.15.0" = 0x30
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(table.get("foo", Version::V1_01_1), Ok(0x10));
+        assert_eq!(table.get("foo", Version::V1_07_0), Ok(0x10));
+        assert_eq!(table.get("foo", Version::V1_08_0), Ok(0x20));
+        assert_eq!(table.get("foo", Version::V1_14_0), Ok(0x20));
+        assert_eq!(table.get("foo", Version::V1_15_2), Ok(0x30));
+        assert_eq!(table.get("foo", (1, 16, 0)), Ok(0x30));
+        assert!(table.get("foo", (1, 0, 0)).is_err());
+        assert!(table.get("bar", Version::V1_15_2).is_err());
+    }
+
+    #[test]
+    fn test_invalid_table() {
+        assert!("[foo]\n\"1.01\" = 0x10".parse::<OffsetTable>().is_err());
+        assert!("[foo]\n\"1.01.1\" = -1".parse::<OffsetTable>().is_err());
+        assert!("foo = 1".parse::<OffsetTable>().is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Rows copied in param-tinkerer, to be pasted over other rows of the same
+//! param or written out as `param-mod.toml` snippets.
+//!
+//! Rows are read and written with the same visitors as exports, so any
+//! [`ParamStruct`] can be copied.
+//!
+//! [`ParamStruct`]: crate::ParamStruct
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Write;
+
+use super::{ParamRow, ParamTable, Params, PARAM_NAMES};
+
+/// A row of a param, as it was when copied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopiedRow {
+    pub param: String,
+    pub row: ParamRow,
+}
+
+/// The last copied rows, most recent first.
+#[derive(Debug, Clone)]
+pub struct RowClipboard {
+    rows: VecDeque<CopiedRow>,
+    capacity: usize,
+}
+
+fn toml_key(key: &str) -> String {
+    if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_string()
+    } else {
+        format!("{key:?}")
+    }
+}
+
+impl CopiedRow {
+    fn fields<'a>(
+        &'a self,
+        only: Option<&'a HashSet<String>>,
+    ) -> impl Iterator<Item = &'a (String, serde_json::Value)> {
+        self.row
+            .fields
+            .iter()
+            .filter(move |(field, _)| only.is_none_or(|only| only.contains(field)))
+    }
+
+    /// A table writing the copied fields, or only those in `only`, to row
+    /// `id` of `param`.
+    pub fn paste_table(
+        &self,
+        param: &str,
+        id: u64,
+        only: Option<&HashSet<String>>,
+    ) -> Result<ParamTable, String> {
+        if param != self.param {
+            return Err(format!("Can't paste a row of {} into {param}", self.param));
+        }
+
+        let fields = self.fields(only).cloned().collect();
+        Ok(ParamTable { param: param.to_string(), rows: vec![ParamRow { id, name: None, fields }] })
+    }
+
+    /// The copied fields, or only those in `only`, as a `param-mod.toml`
+    /// table.
+    pub fn to_toml(&self, only: Option<&HashSet<String>>) -> String {
+        let mut out = String::new();
+        if let Some(name) = &self.row.name {
+            writeln!(out, "# {name}").ok();
+        }
+        writeln!(out, "[{}.{}]", self.param, self.row.id).ok();
+        for (field, value) in self.fields(only) {
+            writeln!(out, "{} = {value}", toml_key(field)).ok();
+        }
+        out
+    }
+}
+
+impl RowClipboard {
+    pub fn new(capacity: usize) -> Self {
+        RowClipboard { rows: VecDeque::new(), capacity }
+    }
+
+    /// Adds a row at the front, dropping the oldest rows past the capacity.
+    /// Copying the same row again moves it to the front.
+    pub fn push(&mut self, row: CopiedRow) {
+        self.rows.retain(|r| r.param != row.param || r.row.id != row.row.id);
+        self.rows.push_front(row);
+        self.rows.truncate(self.capacity);
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&CopiedRow> {
+        self.rows.get(idx)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &CopiedRow> {
+        self.rows.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl Params {
+    /// Copies a row by index.
+    pub fn copy_row(&self, param: &str, param_idx: usize) -> Option<CopiedRow> {
+        let mut row = self.export_row(param, param_idx)?;
+        row.name = PARAM_NAMES.get(param).and_then(|names| names.get(&(row.id as usize))).cloned();
+
+        Some(CopiedRow { param: param.to_string(), row })
+    }
+
+    /// Writes a copied row, or only the fields in `only`, over row `id` of
+    /// `param`. Returns the fields that couldn't be written.
+    pub fn paste_row(
+        &self,
+        copied: &CopiedRow,
+        param: &str,
+        id: u64,
+        only: Option<&HashSet<String>>,
+    ) -> Result<Vec<String>, String> {
+        self.import_param(&copied.paste_table(param, id, only)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::test_param::{TestParam, TEST_PARAM};
+    use crate::prelude::*;
+
+    fn copy(id: u64, row: &mut TestParam) -> CopiedRow {
+        CopiedRow {
+            param: TEST_PARAM.to_string(),
+            row: ParamRow::read(id, Some("Boss".to_string()), row),
+        }
+    }
+
+    #[test]
+    fn test_paste() {
+        let mut from =
+            TestParam { hp: 500, flags: 1, speed: 1.5, ids: [3, 4], ..Default::default() };
+        let mut to = TestParam { hp: 10, ids: [1, 2], ..Default::default() };
+        let copied = copy(10, &mut from);
+
+        let only = HashSet::from(["hp".to_string(), "ids[1]".to_string()]);
+        let table = copied.paste_table(TEST_PARAM, 20, Some(&only)).unwrap();
+        assert_eq!(table.rows[0].id, 20);
+        assert!(table.apply_to_rows([(20, &mut to)]).is_empty());
+        assert_eq!(to, TestParam { hp: 500, ids: [1, 4], ..Default::default() });
+
+        let table = copied.paste_table(TEST_PARAM, 20, None).unwrap();
+        assert!(table.apply_to_rows([(20, &mut to)]).is_empty());
+        assert_eq!(to, from);
+
+        assert_eq!(
+            copied.paste_table("OtherParam", 20, None),
+            Err("Can't paste a row of TestParam into OtherParam".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let mut row =
+            TestParam { hp: 500, flags: 1, speed: 0.1, ids: [3, 4], ..Default::default() };
+        let copied = copy(10, &mut row);
+
+        assert_eq!(
+            copied.to_toml(None),
+            [
+                "# Boss\n",
+                "[TestParam.10]\n",
+                "hp = 500\n",
+                "isBoss = true\n",
+                "isGhost = false\n",
+                "level = 0\n",
+                "speed = 0.1\n",
+                "\"ids[0]\" = 3\n",
+                "\"ids[1]\" = 4\n",
+            ]
+            .concat()
+        );
+
+        let only = HashSet::from(["speed".to_string()]);
+        let config = copied.to_toml(Some(&only)).parse::<PatchConfig>().unwrap();
+        let (resolved, errors) = config.resolve(&ParamSnapshot::new());
+        assert!(errors.is_empty());
+        assert_eq!(resolved.get(TEST_PARAM, 10, "speed"), Some(&0.1.into()));
+        assert_eq!(resolved.get(TEST_PARAM, 10, "hp"), None);
+    }
+
+    #[test]
+    fn test_clipboard_history() {
+        let mut clipboard = RowClipboard::new(2);
+        let mut row = TestParam::default();
+        assert!(clipboard.is_empty());
+
+        clipboard.push(copy(1, &mut row));
+        clipboard.push(copy(2, &mut row));
+        clipboard.push(copy(1, &mut row));
+        assert_eq!(clipboard.rows().map(|r| r.row.id).collect::<Vec<_>>(), [1, 2]);
+
+        clipboard.push(copy(3, &mut row));
+        assert_eq!(clipboard.rows().map(|r| r.row.id).collect::<Vec<_>>(), [3, 1]);
+        assert_eq!(clipboard.len(), 2);
+        assert_eq!(clipboard.get(1).map(|r| r.row.id), Some(1));
+    }
+}
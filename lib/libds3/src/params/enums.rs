@@ -0,0 +1,108 @@
+//! Names of the values of enum fields, like `goods_type = "Weapon"`, from
+//! the param metadata of Paramdex. Generated along with the param structs
+//! by `cargo xtask codegen`.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+pub static PARAM_ENUMS: Lazy<ParamEnums> =
+    Lazy::new(|| ParamEnums::from_json(include_str!("param_enums.json")).unwrap());
+
+/// Named values by param name and field name, sorted by value.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParamEnums(HashMap<String, HashMap<String, Vec<(i64, String)>>>);
+
+impl ParamEnums {
+    /// Reads `{ "Param": { "field": { "0": "Name", ... } } }`.
+    pub fn from_json(json: &str) -> Result<ParamEnums, String> {
+        let params: HashMap<String, HashMap<String, HashMap<String, String>>> =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut enums = HashMap::new();
+        for (param, fields) in params {
+            let mut param_enums = HashMap::new();
+            for (field, names) in fields {
+                let mut values = names
+                    .into_iter()
+                    .map(|(value, name)| match value.parse::<i64>() {
+                        Ok(value) => Ok((value, name)),
+                        Err(e) => Err(format!("{param}.{field}: value {value:?}: {e}")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                values.sort();
+                param_enums.insert(field, values);
+            }
+            enums.insert(param, param_enums);
+        }
+
+        Ok(ParamEnums(enums))
+    }
+
+    /// The named values of a field, if it is an enum.
+    pub fn values(&self, param: &str, field: &str) -> Option<&[(i64, String)]> {
+        self.0.get(param)?.get(field).map(Vec::as_slice)
+    }
+
+    /// The name of a value of a field.
+    pub fn name(&self, param: &str, field: &str, value: i64) -> Option<&str> {
+        self.values(param, field)?.iter().find(|(v, _)| *v == value).map(|(_, name)| name.as_str())
+    }
+
+    /// The value of a field with the given name.
+    pub fn value(&self, param: &str, field: &str, name: &str) -> Result<i64, String> {
+        let Some(values) = self.values(param, field) else {
+            return Err(format!("{name:?} is not an expression, and {field} has no named values"));
+        };
+
+        match values.iter().find(|(_, n)| n == name) {
+            Some((value, _)) => Ok(*value),
+            None => {
+                let names = values.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>();
+                Err(format!(
+                    "unknown value {name:?} for {field}, expected one of {}",
+                    names.join(", ")
+                ))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enums() {
+        let enums = ParamEnums::from_json(
+            r#"{
+                "EquipParamGoods": {
+                    "goods_type": { "1": "Weapon", "0": "Goods", "-1": "None" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(enums.values("EquipParamGoods", "goods_type").unwrap(), &[
+            (-1, "None".to_string()),
+            (0, "Goods".to_string()),
+            (1, "Weapon".to_string())
+        ]);
+        assert_eq!(enums.values("EquipParamGoods", "sort_id"), None);
+        assert_eq!(enums.name("EquipParamGoods", "goods_type", 1), Some("Weapon"));
+        assert_eq!(enums.name("EquipParamGoods", "goods_type", 2), None);
+        assert_eq!(enums.value("EquipParamGoods", "goods_type", "Weapon"), Ok(1));
+        assert_eq!(
+            enums.value("EquipParamGoods", "goods_type", "Armor"),
+            Err("unknown value \"Armor\" for goods_type, expected one of None, Goods, Weapon"
+                .to_string())
+        );
+        assert_eq!(
+            enums.value("EquipParamGoods", "sort_id", "1.5"),
+            Err("\"1.5\" is not an expression, and sort_id has no named values".to_string())
+        );
+
+        assert!(ParamEnums::from_json(r#"{ "A": { "b": { "x": "X" } } }"#).is_err());
+        assert!(PARAM_ENUMS.values("EquipParamGoods", "sort_id").is_none());
+    }
+}
@@ -0,0 +1,567 @@
+//! Export of whole param tables to JSON and CSV, and import back.
+//!
+//! Rows are walked with [`ParamVisitor`]s, so the same code works on the
+//! live game params and on any [`ParamStruct`] in memory.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+use super::{Params, PARAM_NAMES, PARAM_VTABLE};
+use crate::{ParamStruct, ParamVisitor};
+
+/// A param row: its ID, its name if known, and its fields in visit order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamRow {
+    pub id: u64,
+    pub name: Option<String>,
+    pub fields: Vec<(String, Value)>,
+}
+
+/// All the rows of a param.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamTable {
+    pub param: String,
+    pub rows: Vec<ParamRow>,
+}
+
+/// Collects the fields of a row as JSON values.
+#[derive(Default)]
+pub struct RowSerializer(pub Vec<(String, Value)>);
+
+impl RowSerializer {
+    fn push(&mut self, name: &str, value: impl Into<Value>) {
+        self.0.push((name.to_string(), value.into()));
+    }
+}
+
+/// Goes through the shortest representation that round-trips, so that e.g.
+/// 0.1 doesn't become 0.10000000149011612.
+pub(super) fn f32_value(v: f32) -> Value {
+    Number::from_str(&v.to_string())
+        .map(Value::Number)
+        .unwrap_or_else(|_| Value::String(v.to_string()))
+}
+
+impl ParamVisitor for RowSerializer {
+    fn visit_u8(&mut self, name: &str, v: &mut u8) {
+        self.push(name, *v);
+    }
+
+    fn visit_u16(&mut self, name: &str, v: &mut u16) {
+        self.push(name, *v);
+    }
+
+    fn visit_u32(&mut self, name: &str, v: &mut u32) {
+        self.push(name, *v);
+    }
+
+    fn visit_i8(&mut self, name: &str, v: &mut i8) {
+        self.push(name, *v);
+    }
+
+    fn visit_i16(&mut self, name: &str, v: &mut i16) {
+        self.push(name, *v);
+    }
+
+    fn visit_i32(&mut self, name: &str, v: &mut i32) {
+        self.push(name, *v);
+    }
+
+    fn visit_f32(&mut self, name: &str, v: &mut f32) {
+        self.push(name, f32_value(*v));
+    }
+
+    fn visit_bool(&mut self, name: &str, v: &mut bool) {
+        self.push(name, *v);
+    }
+}
+
+/// Writes the fields of a row from JSON values. Values can be either of
+/// the right JSON type or strings, as read from CSV. Fields that are
+/// missing are left untouched.
+pub struct RowDeserializer<'a> {
+    fields: HashMap<&'a str, &'a Value>,
+    visited: HashSet<String>,
+    pub errors: Vec<String>,
+}
+
+impl<'a> RowDeserializer<'a> {
+    pub fn new(fields: &'a [(String, Value)]) -> Self {
+        RowDeserializer {
+            fields: fields.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+            visited: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Errors, plus an error for each field that didn't match any field of
+    /// the row.
+    pub fn finish(mut self) -> Vec<String> {
+        let mut unknown = self
+            .fields
+            .keys()
+            .filter(|k| !self.visited.contains(**k))
+            .map(|k| format!("Unknown field {k}"))
+            .collect::<Vec<_>>();
+        unknown.sort();
+        self.errors.extend(unknown);
+        self.errors
+    }
+
+    fn take(&mut self, name: &str) -> Option<&'a Value> {
+        let value = self.fields.get(name).copied()?;
+        self.visited.insert(name.to_string());
+        Some(value)
+    }
+
+    fn set<T>(&mut self, name: &str, v: &mut T, parse: impl FnOnce(&Value) -> Option<T>) {
+        if let Some(value) = self.take(name) {
+            match parse(value) {
+                Some(value) => *v = value,
+                None => self.errors.push(format!("{name}: invalid value {value}")),
+            }
+        }
+    }
+
+    fn set_int<T: TryFrom<i64>>(&mut self, name: &str, v: &mut T) {
+        self.set(name, v, |value| {
+            value
+                .as_i64()
+                .or_else(|| value.as_str()?.trim().parse().ok())
+                .and_then(|i| T::try_from(i).ok())
+        })
+    }
+}
+
+impl ParamVisitor for RowDeserializer<'_> {
+    fn visit_u8(&mut self, name: &str, v: &mut u8) {
+        self.set_int(name, v);
+    }
+
+    fn visit_u16(&mut self, name: &str, v: &mut u16) {
+        self.set_int(name, v);
+    }
+
+    fn visit_u32(&mut self, name: &str, v: &mut u32) {
+        self.set_int(name, v);
+    }
+
+    fn visit_i8(&mut self, name: &str, v: &mut i8) {
+        self.set_int(name, v);
+    }
+
+    fn visit_i16(&mut self, name: &str, v: &mut i16) {
+        self.set_int(name, v);
+    }
+
+    fn visit_i32(&mut self, name: &str, v: &mut i32) {
+        self.set_int(name, v);
+    }
+
+    fn visit_f32(&mut self, name: &str, v: &mut f32) {
+        self.set(name, v, |value| {
+            value.as_f64().map(|f| f as f32).or_else(|| value.as_str()?.trim().parse().ok())
+        })
+    }
+
+    fn visit_bool(&mut self, name: &str, v: &mut bool) {
+        self.set(name, v, |value| match value {
+            Value::Bool(b) => Some(*b),
+            Value::Number(n) => match n.as_u64()? {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            },
+            Value::String(s) => match s.trim() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+}
+
+impl ParamRow {
+    /// Reads a row out of a param struct.
+    pub fn read<T: ParamStruct>(id: u64, name: Option<String>, row: &mut T) -> Self {
+        let mut serializer = RowSerializer::default();
+        row.visit(&mut serializer);
+        ParamRow { id, name, fields: serializer.0 }
+    }
+
+    /// Writes the fields of this row to a param struct. Returns the fields
+    /// that couldn't be written.
+    pub fn apply<T: ParamStruct>(&self, row: &mut T) -> Vec<String> {
+        let mut deserializer = RowDeserializer::new(&self.fields);
+        row.visit(&mut deserializer);
+        deserializer.finish()
+    }
+}
+
+impl ParamTable {
+    /// Builds a table out of in-memory rows, naming them after
+    /// `PARAM_NAMES`.
+    pub fn from_rows<'a, T: ParamStruct + 'a>(
+        param: &str,
+        rows: impl IntoIterator<Item = (u64, &'a mut T)>,
+    ) -> Self {
+        let names = PARAM_NAMES.get(param);
+        let rows = rows
+            .into_iter()
+            .map(|(id, row)| {
+                let name = names.and_then(|names| names.get(&(id as usize))).cloned();
+                ParamRow::read(id, name, row)
+            })
+            .collect();
+
+        ParamTable { param: param.to_string(), rows }
+    }
+
+    /// Writes the rows of this table to in-memory rows with matching IDs.
+    /// Returns the rows and fields that couldn't be written.
+    pub fn apply_to_rows<'a, T: ParamStruct + 'a>(
+        &self,
+        rows: impl IntoIterator<Item = (u64, &'a mut T)>,
+    ) -> Vec<String> {
+        let mut rows: HashMap<u64, &mut T> = rows.into_iter().collect();
+
+        self.apply_by_id(|id, row| rows.get_mut(&id).map(|target| row.apply(*target)))
+    }
+
+    fn apply_by_id(
+        &self,
+        mut apply: impl FnMut(u64, &ParamRow) -> Option<Vec<String>>,
+    ) -> Vec<String> {
+        self.rows
+            .iter()
+            .flat_map(|row| match apply(row.id, row) {
+                Some(errors) => {
+                    errors.into_iter().map(|e| format!("{}[{}]: {e}", self.param, row.id)).collect()
+                },
+                None => vec![format!("{}[{}]: row not found", self.param, row.id)],
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut obj = Map::new();
+                obj.insert("id".to_string(), row.id.into());
+                if let Some(name) = &row.name {
+                    obj.insert("name".to_string(), name.clone().into());
+                }
+                obj.insert(
+                    "fields".to_string(),
+                    Value::Object(row.fields.iter().cloned().collect()),
+                );
+                Value::Object(obj)
+            })
+            .collect::<Vec<_>>();
+
+        let mut obj = Map::new();
+        obj.insert("param".to_string(), self.param.clone().into());
+        obj.insert("rows".to_string(), Value::Array(rows));
+
+        serde_json::to_string_pretty(&Value::Object(obj)).unwrap()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(s).map_err(|e| e.to_string())?;
+
+        let param = value
+            .get("param")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Missing \"param\"".to_string())?
+            .to_string();
+
+        let rows = value
+            .get("rows")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "Missing \"rows\"".to_string())?
+            .iter()
+            .enumerate()
+            .map(|(idx, row)| {
+                let id = row
+                    .get("id")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| format!("Row {idx}: missing \"id\""))?;
+                let name = row.get("name").and_then(Value::as_str).map(String::from);
+                let fields = row
+                    .get("fields")
+                    .and_then(Value::as_object)
+                    .ok_or_else(|| format!("Row {idx}: missing \"fields\""))?
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+
+                Ok(ParamRow { id, name, fields })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ParamTable { param, rows })
+    }
+
+    /// One line per row, with `id` and `name` columns followed by the
+    /// fields of the first row.
+    pub fn to_csv(&self) -> String {
+        let field_names =
+            self.rows.first().map(|row| row.fields.iter().map(|(k, _)| k.as_str()).collect());
+        let field_names: Vec<&str> = field_names.unwrap_or_default();
+
+        let mut out = String::new();
+        let header = ["id", "name"].into_iter().chain(field_names.iter().copied());
+        write_csv_line(&mut out, header.map(String::from));
+
+        for row in &self.rows {
+            let fields: HashMap<_, _> = row.fields.iter().map(|(k, v)| (k.as_str(), v)).collect();
+            let cells = [row.id.to_string(), row.name.clone().unwrap_or_default()]
+                .into_iter()
+                .chain(field_names.iter().map(|k| match fields.get(k) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(v) => v.to_string(),
+                    None => String::new(),
+                }));
+            write_csv_line(&mut out, cells);
+        }
+
+        out
+    }
+
+    /// Reads a table written by [`ParamTable::to_csv`]. The `name` column
+    /// is optional, and empty cells leave the field untouched on import.
+    pub fn from_csv(param: &str, s: &str) -> Result<Self, String> {
+        let mut lines = parse_csv(s)?.into_iter();
+        let header = lines.next().ok_or_else(|| "Empty CSV".to_string())?;
+
+        let id_col = header
+            .iter()
+            .position(|h| h == "id")
+            .ok_or_else(|| "Missing \"id\" column".to_string())?;
+        let name_col = header.iter().position(|h| h == "name");
+
+        let rows = lines
+            .enumerate()
+            .filter(|(_, cells)| !cells.iter().all(String::is_empty))
+            .map(|(idx, cells)| {
+                let line = idx + 2;
+                let id = cells
+                    .get(id_col)
+                    .and_then(|id| id.trim().parse().ok())
+                    .ok_or_else(|| format!("Line {line}: invalid id"))?;
+                let name = name_col.and_then(|c| cells.get(c)).filter(|n| !n.is_empty()).cloned();
+                let fields = header
+                    .iter()
+                    .zip(cells)
+                    .enumerate()
+                    .filter(|&(col, (_, ref cell))| {
+                        col != id_col && Some(col) != name_col && !cell.is_empty()
+                    })
+                    .map(|(_, (k, v))| (k.clone(), Value::String(v)))
+                    .collect();
+
+                Ok(ParamRow { id, name, fields })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ParamTable { param: param.to_string(), rows })
+    }
+}
+
+impl Params {
+    /// Reads all the rows of a param.
+    pub fn export_param(&self, param: &str) -> Option<ParamTable> {
+        PARAM_VTABLE.get(param)?;
+        let ids = unsafe { self.iter_param_ids(param) }?.collect::<Vec<_>>();
+        let names = PARAM_NAMES.get(param);
+
+        let rows = ids
+            .into_iter()
+            .enumerate()
+            .map(|(idx, id)| {
+                let mut serializer = RowSerializer::default();
+                self.visit_param_item(param, idx, &mut serializer);
+                let name = names.and_then(|names| names.get(&(id as usize))).cloned();
+                ParamRow { id, name, fields: serializer.0 }
+            })
+            .collect();
+
+        Some(ParamTable { param: param.to_string(), rows })
+    }
+
+    /// Writes the rows of a table to the param with the same name. Returns
+    /// the rows and fields that couldn't be written.
+    pub fn import_param(&self, table: &ParamTable) -> Result<Vec<String>, String> {
+        if !PARAM_VTABLE.contains_key(&table.param) {
+            return Err(format!("Unknown param {}", table.param));
+        }
+        let indices: HashMap<u64, usize> = unsafe { self.iter_param_ids(&table.param) }
+            .ok_or_else(|| format!("Param {} not loaded", table.param))?
+            .enumerate()
+            .map(|(idx, id)| (id, idx))
+            .collect();
+
+        Ok(table.apply_by_id(|id, row| {
+            let idx = *indices.get(&id)?;
+            let mut deserializer = RowDeserializer::new(&row.fields);
+            self.visit_param_item(&table.param, idx, &mut deserializer);
+            Some(deserializer.finish())
+        }))
+    }
+}
+
+fn write_csv_line(out: &mut String, cells: impl Iterator<Item = String>) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if cell.contains([',', '"', '\n', '\r']) {
+            write!(out, "\"{}\"", cell.replace('"', "\"\"")).unwrap();
+        } else {
+            out.push_str(&cell);
+        }
+    }
+    out.push('\n');
+}
+
+fn parse_csv(s: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut cell = String::new();
+    let mut chars = s.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            },
+            (true, '"') => quoted = false,
+            (true, c) => cell.push(c),
+            (false, '"') if cell.is_empty() => quoted = true,
+            (false, ',') => line.push(std::mem::take(&mut cell)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {},
+            (false, '\n') => {
+                line.push(std::mem::take(&mut cell));
+                lines.push(std::mem::take(&mut line));
+            },
+            (false, c) => cell.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("Unterminated quoted cell".to_string());
+    }
+    if !cell.is_empty() || !line.is_empty() {
+        line.push(cell);
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::test_param::{table, vanilla, TestParam, IDS, TEST_PARAM};
+
+    #[test]
+    fn test_serialize() {
+        let table = table(&mut vanilla());
+        assert_eq!(table.rows[1].fields, vec![
+            ("hp".to_string(), Value::from(200)),
+            ("isBoss".to_string(), Value::from(true)),
+            ("isGhost".to_string(), Value::from(false)),
+            ("level".to_string(), Value::from(300)),
+            ("speed".to_string(), serde_json::from_str("0.1").unwrap()),
+            ("ids[0]".to_string(), Value::from(0)),
+            ("ids[1]".to_string(), Value::from(0)),
+        ]);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let json = table(&mut vanilla()).to_json();
+        assert!(json.contains("\"speed\": 0.1\n"));
+
+        let mut rows = vec![TestParam::default(); 2];
+        let imported = ParamTable::from_json(&json).unwrap();
+        assert_eq!(imported.param, TEST_PARAM);
+
+        let errors = imported.apply_to_rows(IDS.into_iter().zip(rows.iter_mut()));
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(rows, vanilla());
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let csv = table(&mut vanilla()).to_csv();
+        assert_eq!(
+            csv,
+            [
+                "id,name,hp,isBoss,isGhost,level,speed,ids[0],ids[1]\n",
+                "10,,100,false,false,-3,1,1,2\n",
+                "20,,200,true,false,300,0.1,0,0\n",
+            ]
+            .concat()
+        );
+
+        let mut rows = vec![TestParam::default(); 2];
+        let errors = ParamTable::from_csv(TEST_PARAM, &csv)
+            .unwrap()
+            .apply_to_rows(IDS.into_iter().zip(rows.iter_mut()));
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(rows, vanilla());
+    }
+
+    #[test]
+    fn test_csv_partial_import() {
+        let csv = "id,speed,isBoss\r\n20,,0\r\n30,1.0,1\r\n";
+        let table = ParamTable::from_csv(TEST_PARAM, csv).unwrap();
+
+        let mut rows = vanilla();
+        let errors = table.apply_to_rows(IDS.into_iter().zip(rows.iter_mut()));
+        assert_eq!(errors, vec!["TestParam[30]: row not found".to_string()]);
+        assert_eq!(rows[1].speed, 0.1);
+        assert!(!rows[1].is_boss());
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let row = ParamRow {
+            id: 0,
+            name: None,
+            fields: vec![
+                ("level".to_string(), Value::from(40000)),
+                ("hp".to_string(), Value::String("abc".to_string())),
+                ("speed".to_string(), Value::String(" 4 ".to_string())),
+                ("nope".to_string(), Value::from(1)),
+            ],
+        };
+
+        let mut param = TestParam::default();
+        assert_eq!(row.apply(&mut param), vec![
+            "hp: invalid value \"abc\"".to_string(),
+            "level: invalid value 40000".to_string(),
+            "Unknown field nope".to_string(),
+        ]);
+        assert_eq!(param.speed, 4.0);
+        assert_eq!(param.level, 0);
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        let mut out = String::new();
+        write_csv_line(&mut out, ["a", "b,c", "d\"e", "f\ng"].into_iter().map(String::from));
+        assert_eq!(out, "a,\"b,c\",\"d\"\"e\",\"f\ng\"\n");
+        assert_eq!(parse_csv(&out).unwrap(), vec![vec!["a", "b,c", "d\"e", "f\ng"]]);
+        assert!(parse_csv("\"abc").is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! Field values computed from the row they are written to, as in
+//! `param-mod.toml`: `"*1.2"`, `"+10"`, `"=original"` or
+//! `"=atk_base_physics / 2"`.
+//!
+//! A value starting with `=` is an expression, where `original` is the
+//! field's original value and other names are the original values of the
+//! other fields of the row. A value starting with `+`, `-`, `*` or `/`
+//! applies that operation to the original value.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// The original value of the field being written.
+    Original,
+    /// The original value of another field of the same row.
+    Field(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+impl BinOp {
+    fn from_char(c: char) -> Option<BinOp> {
+        match c {
+            '+' => Some(BinOp::Add),
+            '-' => Some(BinOp::Sub),
+            '*' => Some(BinOp::Mul),
+            '/' => Some(BinOp::Div),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Original => write!(f, "original"),
+            Expr::Field(name) => write!(f, "{name}"),
+            Expr::Neg(e) => write!(f, "-{e}"),
+            Expr::Binary(op, a, b) => write!(f, "({a} {op} {b})"),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+
+        match chars.next() {
+            Some('=') => Parser::parse(chars.as_str()),
+            Some(c) => match BinOp::from_char(c) {
+                Some(op) => Ok(Expr::Binary(
+                    op,
+                    Box::new(Expr::Original),
+                    Box::new(Parser::parse(chars.as_str())?),
+                )),
+                None => Err(format!("expected {s:?} to start with =, +, -, * or /")),
+            },
+            None => Err("empty expression".to_string()),
+        }
+    }
+}
+
+impl Expr {
+    /// Computes the value of the expression, given the original value of
+    /// the field and a way to read the other fields of the row.
+    pub fn eval(&self, original: f64, field: &dyn Fn(&str) -> Option<f64>) -> Result<f64, String> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Original => Ok(original),
+            Expr::Field(name) => field(name).ok_or_else(|| format!("unknown field {name}")),
+            Expr::Neg(e) => Ok(-e.eval(original, field)?),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(original, field)?, b.eval(original, field)?);
+                match op {
+                    BinOp::Add => Ok(a + b),
+                    BinOp::Sub => Ok(a - b),
+                    BinOp::Mul => Ok(a * b),
+                    BinOp::Div if b == 0. => Err("division by zero".to_string()),
+                    BinOp::Div => Ok(a / b),
+                }
+            },
+        }
+    }
+
+    /// The other fields the expression reads.
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Expr::Number(_) | Expr::Original => Vec::new(),
+            Expr::Field(name) => vec![name.as_str()],
+            Expr::Neg(e) => e.fields(),
+            Expr::Binary(_, a, b) => a.fields().into_iter().chain(b.fields()).collect(),
+        }
+    }
+}
+
+/// Recursive descent over `expr := term (('+' | '-') term)*`,
+/// `term := factor (('*' | '/') factor)*` and
+/// `factor := number | name | '-' factor | '(' expr ')'`.
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(s: &'a str) -> Result<Expr, String> {
+        let mut parser = Parser { s, pos: 0 };
+        let expr = parser.expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(c) => Err(parser.unexpected(c)),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.s[self.pos..].chars().next()
+    }
+
+    fn unexpected(&self, c: char) -> String {
+        format!("unexpected {c:?} at {} in {:?}", self.pos + 1, self.s)
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.s[self.pos..];
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut expr = self.term()?;
+        while let Some(op @ (BinOp::Add | BinOp::Sub)) = self.peek().and_then(BinOp::from_char) {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.term()?));
+        }
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut expr = self.factor()?;
+        while let Some(op @ (BinOp::Mul | BinOp::Div)) = self.peek().and_then(BinOp::from_char) {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.factor()?));
+        }
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.factor()?)))
+            },
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.expr()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.pos += 1;
+                        Ok(expr)
+                    },
+                    Some(c) => Err(self.unexpected(c)),
+                    None => Err(format!("missing ) in {:?}", self.s)),
+                }
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                number.parse().map(Expr::Number).map_err(|_| format!("invalid number {number}"))
+            },
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name =
+                    self.take_while(|c| c.is_ascii_alphanumeric() || c == '_').to_string();
+                // Array elements, as in `ids[1]`
+                if self.s[self.pos..].starts_with('[') {
+                    let start = self.pos;
+                    self.pos += 1;
+                    self.take_while(|c| c.is_ascii_digit());
+                    if !self.s[self.pos..].starts_with(']') {
+                        return Err(format!("invalid array index in {:?}", self.s));
+                    }
+                    self.pos += 1;
+                    name.push_str(&self.s[start..self.pos]);
+                }
+                Ok(match name.as_str() {
+                    "original" => Expr::Original,
+                    _ => Expr::Field(name),
+                })
+            },
+            Some(c) => Err(self.unexpected(c)),
+            None => Err(format!("unexpected end of {:?}", self.s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str) -> Result<f64, String> {
+        let expr: Expr = s.parse()?;
+        expr.eval(100., &|name| match name {
+            "atk" => Some(50.),
+            "ids[1]" => Some(3.),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("*1.2".parse::<Expr>().unwrap().to_string(), "(original * 1.2)");
+        assert_eq!("-5".parse::<Expr>().unwrap().to_string(), "(original - 5)");
+        assert_eq!("=original".parse::<Expr>(), Ok(Expr::Original));
+        assert_eq!(
+            "= atk + 2 * (ids[1] - -1)".parse::<Expr>().unwrap().to_string(),
+            "(atk + (2 * (ids[1] - -1)))"
+        );
+        assert_eq!("=atk / ids[1] + original".parse::<Expr>().unwrap().fields(), ["atk", "ids[1]"]);
+
+        assert!("1.2".parse::<Expr>().is_err());
+        assert!("".parse::<Expr>().is_err());
+        assert!("=".parse::<Expr>().is_err());
+        assert!("=(atk".parse::<Expr>().is_err());
+        assert!("=atk atk".parse::<Expr>().is_err());
+        assert!("=1..2".parse::<Expr>().is_err());
+        assert!("=ids[a]".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval("*1.5"), Ok(150.));
+        assert_eq!(eval("+10"), Ok(110.));
+        assert_eq!(eval("-10"), Ok(90.));
+        assert_eq!(eval("/4"), Ok(25.));
+        assert_eq!(eval("=original"), Ok(100.));
+        assert_eq!(eval("=atk"), Ok(50.));
+        assert_eq!(eval("=(atk + original) / ids[1]"), Ok(50.));
+        assert_eq!(eval("*-atk"), Ok(-5000.));
+        assert_eq!(eval("=nope"), Err("unknown field nope".to_string()));
+        assert_eq!(eval("/(atk - 50)"), Err("division by zero".to_string()));
+    }
+}
@@ -1,19 +1,42 @@
+mod clipboard;
+mod enums;
+mod export;
+mod expr;
 mod param_data;
+mod patch;
+mod refs;
+mod regulation;
+mod schema;
+mod search;
+mod snapshot;
+#[cfg(test)]
+mod test_param;
+mod validate;
 use std::collections::{BTreeMap, HashMap};
 use std::ffi::c_void;
 use std::time::Duration;
 use std::{mem, thread};
 
+pub use clipboard::*;
+pub use enums::*;
+pub use export::*;
+pub use expr::*;
 use log::{error, info};
 use once_cell::sync::Lazy;
 pub use param_data::*;
 use parking_lot::RwLock;
+pub use patch::*;
+pub use refs::*;
+pub use regulation::*;
+pub use schema::*;
+pub use search::*;
+pub use snapshot::*;
+pub use validate::*;
 use widestring::U16CStr;
 use windows::Win32::System::LibraryLoader::GetModuleHandleA;
 use windows::Win32::System::Memory::{VirtualQuery, MEMORY_BASIC_INFORMATION, PAGE_READWRITE};
 
-use crate::prelude::base_addresses::*;
-use crate::version::VERSION;
+use crate::version::base_addresses;
 use crate::{wait_option, ParamVisitor};
 
 pub static PARAMS: Lazy<RwLock<Params>> = Lazy::new(|| unsafe {
@@ -64,13 +87,18 @@ pub struct Param<T: 'static> {
     pub param: Option<&'static mut T>,
 }
 
-pub struct Params(BTreeMap<String, (*const c_void, isize)>);
+pub struct Params {
+    params: BTreeMap<String, (*const c_void, isize)>,
+    /// Param files read from disk, that `params` points into. Empty when
+    /// reading the game's memory.
+    files: Vec<Box<[u64]>>,
+}
 unsafe impl Send for Params {}
 unsafe impl Sync for Params {}
 
 impl Params {
     unsafe fn new() -> Result<Params, String> {
-        let mut p = Params(BTreeMap::new());
+        let mut p = Params { params: BTreeMap::new(), files: Vec::new() };
         p.refresh()?;
 
         Ok(p)
@@ -81,7 +109,7 @@ impl Params {
     /// Accesses raw pointers. Should never crash as the param pointers are
     /// static.
     pub unsafe fn refresh(&mut self) -> Result<(), String> {
-        let addresses: BaseAddresses = (*VERSION).into();
+        let addresses = base_addresses();
         let module_base_addr = GetModuleHandleA(None).map_err(|e| e.to_string())?.0 as usize;
         let base_ptr = addresses.param + module_base_addr;
         let base_ptr = loop {
@@ -105,7 +133,7 @@ impl Params {
             .ok_or_else(|| "Invalid param base address".to_string())?;
 
         let m = Params::param_entries_from_master(base)?;
-        self.0 = m;
+        self.params = m;
         Ok(())
     }
 
@@ -154,11 +182,11 @@ impl Params {
     }
 
     pub fn keys(&self) -> impl Iterator<Item = &String> {
-        self.0.keys()
+        self.params.keys()
     }
 
     fn get_param_ptr(&self, s: &str) -> Option<(*const c_void, isize)> {
-        self.0.get(s).cloned()
+        self.params.get(s).cloned()
     }
 
     pub fn visit_param_item<T: ParamVisitor>(
@@ -167,10 +195,10 @@ impl Params {
         param_idx: usize,
         visitor: &mut T,
     ) {
-        if let Some((lambda, ptr)) = PARAM_VTABLE.get(param).and_then(|lambda| {
-            unsafe { self.get_param_idx_ptr(param, param_idx) }.map(|v| (lambda, v))
+        if let Some((entry, ptr)) = PARAM_VTABLE.get(param).and_then(|entry| {
+            unsafe { self.get_param_idx_ptr(param, param_idx) }.map(|v| (entry, v))
         }) {
-            lambda(ptr, visitor);
+            (entry.visit)(ptr, visitor);
         }
     }
 
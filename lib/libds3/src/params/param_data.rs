@@ -9,138 +9,148 @@ use once_cell::sync::Lazy;
 
 use crate::prelude::*;
 
-unsafe fn get_lambda<T: ParamStruct>() -> BoxedVisitorLambda {
-    Box::new(|ptr, v| {
-        if let Some(r) = (ptr as *mut T).as_mut() {
-            r.visit(&mut *v);
-        }
-    })
+unsafe fn get_vtable<T: ParamStruct>() -> ParamVTableEntry {
+    ParamVTableEntry {
+        visit: Box::new(|ptr, v| {
+            if let Some(r) = (ptr as *mut T).as_mut() {
+                r.visit(&mut *v);
+            }
+        }),
+        fields: T::FIELDS,
+        size: std::mem::size_of::<T>(),
+    }
 }
 
 type BoxedVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamVisitor) + Send + Sync>;
 
-pub static PARAM_VTABLE: Lazy<HashMap<String, BoxedVisitorLambda>> = Lazy::new(|| {
+pub struct ParamVTableEntry {
+    pub visit: BoxedVisitorLambda,
+    pub fields: &'static [FieldInfo],
+    pub size: usize,
+}
+
+pub static PARAM_VTABLE: Lazy<HashMap<String, ParamVTableEntry>> = Lazy::new(|| {
     [
-        ("ActionButtonParam".to_string(), unsafe { get_lambda::<ActionButtonParam>() }),
-        ("AiSoundParam".to_string(), unsafe { get_lambda::<AiSoundParam>() }),
-        ("AtkParam_Npc".to_string(), unsafe { get_lambda::<AtkParam_Npc>() }),
-        ("AtkParam_Pc".to_string(), unsafe { get_lambda::<AtkParam_Pc>() }),
+        ("ActionButtonParam".to_string(), unsafe { get_vtable::<ActionButtonParam>() }),
+        ("AiSoundParam".to_string(), unsafe { get_vtable::<AiSoundParam>() }),
+        ("AtkParam_Npc".to_string(), unsafe { get_vtable::<AtkParam_Npc>() }),
+        ("AtkParam_Pc".to_string(), unsafe { get_vtable::<AtkParam_Pc>() }),
         ("AttackElementCorrectParam".to_string(), unsafe {
-            get_lambda::<AttackElementCorrectParam>()
+            get_vtable::<AttackElementCorrectParam>()
         }),
-        ("BehaviorParam".to_string(), unsafe { get_lambda::<BehaviorParam>() }),
-        ("BehaviorParam_PC".to_string(), unsafe { get_lambda::<BehaviorParam_PC>() }),
-        ("BonfireWarpParam".to_string(), unsafe { get_lambda::<BonfireWarpParam>() }),
-        ("BudgetParam".to_string(), unsafe { get_lambda::<BudgetParam>() }),
-        ("Bullet".to_string(), unsafe { get_lambda::<Bullet>() }),
-        ("BulletCreateLimitParam".to_string(), unsafe { get_lambda::<BulletCreateLimitParam>() }),
-        ("CalcCorrectGraph".to_string(), unsafe { get_lambda::<CalcCorrectGraph>() }),
-        ("Ceremony".to_string(), unsafe { get_lambda::<Ceremony>() }),
-        ("CharacterLoadParam".to_string(), unsafe { get_lambda::<CharacterLoadParam>() }),
-        ("CharaInitParam".to_string(), unsafe { get_lambda::<CharaInitParam>() }),
+        ("BehaviorParam".to_string(), unsafe { get_vtable::<BehaviorParam>() }),
+        ("BehaviorParam_PC".to_string(), unsafe { get_vtable::<BehaviorParam_PC>() }),
+        ("BonfireWarpParam".to_string(), unsafe { get_vtable::<BonfireWarpParam>() }),
+        ("BudgetParam".to_string(), unsafe { get_vtable::<BudgetParam>() }),
+        ("Bullet".to_string(), unsafe { get_vtable::<Bullet>() }),
+        ("BulletCreateLimitParam".to_string(), unsafe { get_vtable::<BulletCreateLimitParam>() }),
+        ("CalcCorrectGraph".to_string(), unsafe { get_vtable::<CalcCorrectGraph>() }),
+        ("Ceremony".to_string(), unsafe { get_vtable::<Ceremony>() }),
+        ("CharacterLoadParam".to_string(), unsafe { get_vtable::<CharacterLoadParam>() }),
+        ("CharaInitParam".to_string(), unsafe { get_vtable::<CharaInitParam>() }),
         ("CharMakeMenuListItemParam".to_string(), unsafe {
-            get_lambda::<CharMakeMenuListItemParam>()
+            get_vtable::<CharMakeMenuListItemParam>()
         }),
-        ("CharMakeMenuTopParam".to_string(), unsafe { get_lambda::<CharMakeMenuTopParam>() }),
-        ("ClearCountCorrectParam".to_string(), unsafe { get_lambda::<ClearCountCorrectParam>() }),
-        ("CoolTimeParam".to_string(), unsafe { get_lambda::<CoolTimeParam>() }),
-        ("CultSettingParam".to_string(), unsafe { get_lambda::<CultSettingParam>() }),
-        ("DecalParam".to_string(), unsafe { get_lambda::<DecalParam>() }),
-        ("DirectionCameraParam".to_string(), unsafe { get_lambda::<DirectionCameraParam>() }),
-        ("EquipMtrlSetParam".to_string(), unsafe { get_lambda::<EquipMtrlSetParam>() }),
-        ("EquipParamAccessory".to_string(), unsafe { get_lambda::<EquipParamAccessory>() }),
-        ("EquipParamGoods".to_string(), unsafe { get_lambda::<EquipParamGoods>() }),
-        ("EquipParamProtector".to_string(), unsafe { get_lambda::<EquipParamProtector>() }),
-        ("EquipParamWeapon".to_string(), unsafe { get_lambda::<EquipParamWeapon>() }),
-        ("FaceGenParam".to_string(), unsafe { get_lambda::<FaceGenParam>() }),
-        ("FaceParam".to_string(), unsafe { get_lambda::<FaceParam>() }),
-        ("FaceRangeParam".to_string(), unsafe { get_lambda::<FaceRangeParam>() }),
-        ("FootSfxParam".to_string(), unsafe { get_lambda::<FootSfxParam>() }),
-        ("GameAreaParam".to_string(), unsafe { get_lambda::<GameAreaParam>() }),
-        ("GameProgressParam".to_string(), unsafe { get_lambda::<GameProgressParam>() }),
-        ("GemCategoryParam".to_string(), unsafe { get_lambda::<GemCategoryParam>() }),
-        ("GemDropDopingParam".to_string(), unsafe { get_lambda::<GemDropDopingParam>() }),
-        ("GemDropModifyParam".to_string(), unsafe { get_lambda::<GemDropModifyParam>() }),
-        ("GemeffectParam".to_string(), unsafe { get_lambda::<GemeffectParam>() }),
-        ("GemGenParam".to_string(), unsafe { get_lambda::<GemGenParam>() }),
-        ("HitEffectSeParam".to_string(), unsafe { get_lambda::<HitEffectSeParam>() }),
+        ("CharMakeMenuTopParam".to_string(), unsafe { get_vtable::<CharMakeMenuTopParam>() }),
+        ("ClearCountCorrectParam".to_string(), unsafe { get_vtable::<ClearCountCorrectParam>() }),
+        ("CoolTimeParam".to_string(), unsafe { get_vtable::<CoolTimeParam>() }),
+        ("CultSettingParam".to_string(), unsafe { get_vtable::<CultSettingParam>() }),
+        ("DecalParam".to_string(), unsafe { get_vtable::<DecalParam>() }),
+        ("DirectionCameraParam".to_string(), unsafe { get_vtable::<DirectionCameraParam>() }),
+        ("EquipMtrlSetParam".to_string(), unsafe { get_vtable::<EquipMtrlSetParam>() }),
+        ("EquipParamAccessory".to_string(), unsafe { get_vtable::<EquipParamAccessory>() }),
+        ("EquipParamGoods".to_string(), unsafe { get_vtable::<EquipParamGoods>() }),
+        ("EquipParamProtector".to_string(), unsafe { get_vtable::<EquipParamProtector>() }),
+        ("EquipParamWeapon".to_string(), unsafe { get_vtable::<EquipParamWeapon>() }),
+        ("FaceGenParam".to_string(), unsafe { get_vtable::<FaceGenParam>() }),
+        ("FaceParam".to_string(), unsafe { get_vtable::<FaceParam>() }),
+        ("FaceRangeParam".to_string(), unsafe { get_vtable::<FaceRangeParam>() }),
+        ("FootSfxParam".to_string(), unsafe { get_vtable::<FootSfxParam>() }),
+        ("GameAreaParam".to_string(), unsafe { get_vtable::<GameAreaParam>() }),
+        ("GameProgressParam".to_string(), unsafe { get_vtable::<GameProgressParam>() }),
+        ("GemCategoryParam".to_string(), unsafe { get_vtable::<GemCategoryParam>() }),
+        ("GemDropDopingParam".to_string(), unsafe { get_vtable::<GemDropDopingParam>() }),
+        ("GemDropModifyParam".to_string(), unsafe { get_vtable::<GemDropModifyParam>() }),
+        ("GemeffectParam".to_string(), unsafe { get_vtable::<GemeffectParam>() }),
+        ("GemGenParam".to_string(), unsafe { get_vtable::<GemGenParam>() }),
+        ("HitEffectSeParam".to_string(), unsafe { get_vtable::<HitEffectSeParam>() }),
         ("HitEffectSfxConceptParam".to_string(), unsafe {
-            get_lambda::<HitEffectSfxConceptParam>()
+            get_vtable::<HitEffectSfxConceptParam>()
         }),
-        ("HitEffectSfxParam".to_string(), unsafe { get_lambda::<HitEffectSfxParam>() }),
-        ("HitMtrlParam".to_string(), unsafe { get_lambda::<HitMtrlParam>() }),
+        ("HitEffectSfxParam".to_string(), unsafe { get_vtable::<HitEffectSfxParam>() }),
+        ("HitMtrlParam".to_string(), unsafe { get_vtable::<HitMtrlParam>() }),
         ("HPEstusFlaskRecoveryParam".to_string(), unsafe {
-            get_lambda::<HPEstusFlaskRecoveryParam>()
+            get_vtable::<HPEstusFlaskRecoveryParam>()
         }),
-        ("ItemLotParam".to_string(), unsafe { get_lambda::<ItemLotParam>() }),
-        ("KnockBackParam".to_string(), unsafe { get_lambda::<KnockBackParam>() }),
+        ("ItemLotParam".to_string(), unsafe { get_vtable::<ItemLotParam>() }),
+        ("KnockBackParam".to_string(), unsafe { get_vtable::<KnockBackParam>() }),
         ("KnowledgeLoadScreenItemParam".to_string(), unsafe {
-            get_lambda::<KnowledgeLoadScreenItemParam>()
+            get_vtable::<KnowledgeLoadScreenItemParam>()
         }),
         ("LoadBalancerDrawDistScaleParam".to_string(), unsafe {
-            get_lambda::<LoadBalancerDrawDistScaleParam>()
+            get_vtable::<LoadBalancerDrawDistScaleParam>()
         }),
-        ("LoadBalancerParam".to_string(), unsafe { get_lambda::<LoadBalancerParam>() }),
-        ("LockCamParam".to_string(), unsafe { get_lambda::<LockCamParam>() }),
-        ("LodParam".to_string(), unsafe { get_lambda::<LodParam>() }),
-        ("LodParam_ps4".to_string(), unsafe { get_lambda::<LodParam_ps4>() }),
-        ("LodParam_xb1".to_string(), unsafe { get_lambda::<LodParam_xb1>() }),
-        ("Magic".to_string(), unsafe { get_lambda::<Magic>() }),
+        ("LoadBalancerParam".to_string(), unsafe { get_vtable::<LoadBalancerParam>() }),
+        ("LockCamParam".to_string(), unsafe { get_vtable::<LockCamParam>() }),
+        ("LodParam".to_string(), unsafe { get_vtable::<LodParam>() }),
+        ("LodParam_ps4".to_string(), unsafe { get_vtable::<LodParam_ps4>() }),
+        ("LodParam_xb1".to_string(), unsafe { get_vtable::<LodParam_xb1>() }),
+        ("Magic".to_string(), unsafe { get_vtable::<Magic>() }),
         ("MapMimicryEstablishmentParam".to_string(), unsafe {
-            get_lambda::<MapMimicryEstablishmentParam>()
+            get_vtable::<MapMimicryEstablishmentParam>()
         }),
-        ("MenuOffscrRendParam".to_string(), unsafe { get_lambda::<MenuOffscrRendParam>() }),
-        ("MenuPropertyLayoutParam".to_string(), unsafe { get_lambda::<MenuPropertyLayoutParam>() }),
-        ("MenuPropertySpecParam".to_string(), unsafe { get_lambda::<MenuPropertySpecParam>() }),
-        ("MenuValueTableParam".to_string(), unsafe { get_lambda::<MenuValueTableParam>() }),
-        ("ModelSfxParam".to_string(), unsafe { get_lambda::<ModelSfxParam>() }),
-        ("MoveParam".to_string(), unsafe { get_lambda::<MoveParam>() }),
+        ("MenuOffscrRendParam".to_string(), unsafe { get_vtable::<MenuOffscrRendParam>() }),
+        ("MenuPropertyLayoutParam".to_string(), unsafe { get_vtable::<MenuPropertyLayoutParam>() }),
+        ("MenuPropertySpecParam".to_string(), unsafe { get_vtable::<MenuPropertySpecParam>() }),
+        ("MenuValueTableParam".to_string(), unsafe { get_vtable::<MenuValueTableParam>() }),
+        ("ModelSfxParam".to_string(), unsafe { get_vtable::<ModelSfxParam>() }),
+        ("MoveParam".to_string(), unsafe { get_vtable::<MoveParam>() }),
         ("MPEstusFlaskRecoveryParam".to_string(), unsafe {
-            get_lambda::<MPEstusFlaskRecoveryParam>()
+            get_vtable::<MPEstusFlaskRecoveryParam>()
         }),
         ("MultiHPEstusFlaskBonusParam".to_string(), unsafe {
-            get_lambda::<MultiHPEstusFlaskBonusParam>()
+            get_vtable::<MultiHPEstusFlaskBonusParam>()
         }),
         ("MultiMPEstusFlaskBonusParam".to_string(), unsafe {
-            get_lambda::<MultiMPEstusFlaskBonusParam>()
+            get_vtable::<MultiMPEstusFlaskBonusParam>()
         }),
         ("MultiPlayCorrectionParam".to_string(), unsafe {
-            get_lambda::<MultiPlayCorrectionParam>()
+            get_vtable::<MultiPlayCorrectionParam>()
         }),
-        ("MultiSoulBonusRateParam".to_string(), unsafe { get_lambda::<MultiSoulBonusRateParam>() }),
-        ("NetworkAreaParam".to_string(), unsafe { get_lambda::<NetworkAreaParam>() }),
-        ("NetworkMsgParam".to_string(), unsafe { get_lambda::<NetworkMsgParam>() }),
-        ("NetworkParam".to_string(), unsafe { get_lambda::<NetworkParam>() }),
-        ("NewMenuColorTableParam".to_string(), unsafe { get_lambda::<NewMenuColorTableParam>() }),
-        ("NpcAiActionParam".to_string(), unsafe { get_lambda::<NpcAiActionParam>() }),
-        ("NpcParam".to_string(), unsafe { get_lambda::<NpcParam>() }),
-        ("NpcThinkParam".to_string(), unsafe { get_lambda::<NpcThinkParam>() }),
-        ("ObjActParam".to_string(), unsafe { get_lambda::<ObjActParam>() }),
-        ("ObjectMaterialSfxParam".to_string(), unsafe { get_lambda::<ObjectMaterialSfxParam>() }),
-        ("ObjectParam".to_string(), unsafe { get_lambda::<ObjectParam>() }),
-        ("PhantomParam".to_string(), unsafe { get_lambda::<PhantomParam>() }),
-        ("PlayRegionParam".to_string(), unsafe { get_lambda::<PlayRegionParam>() }),
-        ("ProtectorGenParam".to_string(), unsafe { get_lambda::<ProtectorGenParam>() }),
-        ("RagdollParam".to_string(), unsafe { get_lambda::<RagdollParam>() }),
-        ("ReinforceParamProtector".to_string(), unsafe { get_lambda::<ReinforceParamProtector>() }),
-        ("ReinforceParamWeapon".to_string(), unsafe { get_lambda::<ReinforceParamWeapon>() }),
-        ("RoleParam".to_string(), unsafe { get_lambda::<RoleParam>() }),
-        ("SeMaterialConvertParam".to_string(), unsafe { get_lambda::<SeMaterialConvertParam>() }),
-        ("ShopLineupParam".to_string(), unsafe { get_lambda::<ShopLineupParam>() }),
-        ("SkeletonParam".to_string(), unsafe { get_lambda::<SkeletonParam>() }),
-        ("SpEffectParam".to_string(), unsafe { get_lambda::<SpEffectParam>() }),
-        ("SpEffectVfxParam".to_string(), unsafe { get_lambda::<SpEffectVfxParam>() }),
-        ("SwordArtsParam".to_string(), unsafe { get_lambda::<SwordArtsParam>() }),
-        ("TalkParam".to_string(), unsafe { get_lambda::<TalkParam>() }),
-        ("ThrowDirectionSfxParam".to_string(), unsafe { get_lambda::<ThrowDirectionSfxParam>() }),
-        ("ThrowParam".to_string(), unsafe { get_lambda::<ThrowParam>() }),
-        ("ToughnessParam".to_string(), unsafe { get_lambda::<ToughnessParam>() }),
-        ("UpperArmParam".to_string(), unsafe { get_lambda::<UpperArmParam>() }),
-        ("WeaponGenParam".to_string(), unsafe { get_lambda::<WeaponGenParam>() }),
-        ("WepAbsorpPosParam".to_string(), unsafe { get_lambda::<WepAbsorpPosParam>() }),
-        ("WetAspectParam".to_string(), unsafe { get_lambda::<WetAspectParam>() }),
-        ("WhiteSignCoolTimeParam".to_string(), unsafe { get_lambda::<WhiteSignCoolTimeParam>() }),
-        ("Wind".to_string(), unsafe { get_lambda::<Wind>() }),
+        ("MultiSoulBonusRateParam".to_string(), unsafe { get_vtable::<MultiSoulBonusRateParam>() }),
+        ("NetworkAreaParam".to_string(), unsafe { get_vtable::<NetworkAreaParam>() }),
+        ("NetworkMsgParam".to_string(), unsafe { get_vtable::<NetworkMsgParam>() }),
+        ("NetworkParam".to_string(), unsafe { get_vtable::<NetworkParam>() }),
+        ("NewMenuColorTableParam".to_string(), unsafe { get_vtable::<NewMenuColorTableParam>() }),
+        ("NpcAiActionParam".to_string(), unsafe { get_vtable::<NpcAiActionParam>() }),
+        ("NpcParam".to_string(), unsafe { get_vtable::<NpcParam>() }),
+        ("NpcThinkParam".to_string(), unsafe { get_vtable::<NpcThinkParam>() }),
+        ("ObjActParam".to_string(), unsafe { get_vtable::<ObjActParam>() }),
+        ("ObjectMaterialSfxParam".to_string(), unsafe { get_vtable::<ObjectMaterialSfxParam>() }),
+        ("ObjectParam".to_string(), unsafe { get_vtable::<ObjectParam>() }),
+        ("PhantomParam".to_string(), unsafe { get_vtable::<PhantomParam>() }),
+        ("PlayRegionParam".to_string(), unsafe { get_vtable::<PlayRegionParam>() }),
+        ("ProtectorGenParam".to_string(), unsafe { get_vtable::<ProtectorGenParam>() }),
+        ("RagdollParam".to_string(), unsafe { get_vtable::<RagdollParam>() }),
+        ("ReinforceParamProtector".to_string(), unsafe { get_vtable::<ReinforceParamProtector>() }),
+        ("ReinforceParamWeapon".to_string(), unsafe { get_vtable::<ReinforceParamWeapon>() }),
+        ("RoleParam".to_string(), unsafe { get_vtable::<RoleParam>() }),
+        ("SeMaterialConvertParam".to_string(), unsafe { get_vtable::<SeMaterialConvertParam>() }),
+        ("ShopLineupParam".to_string(), unsafe { get_vtable::<ShopLineupParam>() }),
+        ("SkeletonParam".to_string(), unsafe { get_vtable::<SkeletonParam>() }),
+        ("SpEffectParam".to_string(), unsafe { get_vtable::<SpEffectParam>() }),
+        ("SpEffectVfxParam".to_string(), unsafe { get_vtable::<SpEffectVfxParam>() }),
+        ("SwordArtsParam".to_string(), unsafe { get_vtable::<SwordArtsParam>() }),
+        ("TalkParam".to_string(), unsafe { get_vtable::<TalkParam>() }),
+        ("ThrowDirectionSfxParam".to_string(), unsafe { get_vtable::<ThrowDirectionSfxParam>() }),
+        ("ThrowParam".to_string(), unsafe { get_vtable::<ThrowParam>() }),
+        ("ToughnessParam".to_string(), unsafe { get_vtable::<ToughnessParam>() }),
+        ("UpperArmParam".to_string(), unsafe { get_vtable::<UpperArmParam>() }),
+        ("WeaponGenParam".to_string(), unsafe { get_vtable::<WeaponGenParam>() }),
+        ("WepAbsorpPosParam".to_string(), unsafe { get_vtable::<WepAbsorpPosParam>() }),
+        ("WetAspectParam".to_string(), unsafe { get_vtable::<WetAspectParam>() }),
+        ("WhiteSignCoolTimeParam".to_string(), unsafe { get_vtable::<WhiteSignCoolTimeParam>() }),
+        ("Wind".to_string(), unsafe { get_vtable::<Wind>() }),
     ]
     .into_iter()
     .collect()
@@ -149,6 +159,7 @@ pub static PARAM_VTABLE: Lazy<HashMap<String, BoxedVisitorLambda>> = Lazy::new(|
 #[repr(C)]
 pub struct ActionButtonParam {
     pub region_type: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub dummy_poly1: i32,
     pub dummy_poly2: i32,
@@ -159,9 +170,11 @@ pub struct ActionButtonParam {
     pub height: f32,
     pub base_height_offset: f32,
     pub angle_check_type: u8,
+    #[padding]
     pub pad2: [u8; 3],
     pub allow_angle: i32,
     pub text_box_type: u8,
+    #[padding]
     pub pad3: [u8; 3],
     pub text_id: i32,
     pub invalid_flag: i32,
@@ -169,6 +182,7 @@ pub struct ActionButtonParam {
     pub priority: i32,
     pub exec_invalid_time: f32,
     pub exec_button_circle: u8,
+    #[padding]
     pub pad4: [u8; 3],
 }
 
@@ -182,6 +196,7 @@ pub struct AiSoundParam {
     pub fake_target_type: u8,
     pub interest_category: u8,
     pub use_hit_damage_team: u8,
+    #[padding]
     pub pad: [u8; 19],
 }
 
@@ -270,6 +285,7 @@ pub struct AtkParam_Npc {
     #[bitflag(field0x81, 6)]
     #[bitflag(field0x81_0, 7)]
     pub bitfield1: u8,
+    #[padding]
     pub pad1: u8,
     pub regainable_slot_id: u8,
     pub death_cause_id: i32,
@@ -379,6 +395,7 @@ pub struct AtkParam_Npc {
     pub field0x195: u8,
     pub dark_sp_correction: i16,
     pub atk_element_correct_id: i32,
+    #[padding]
     pub pad2: [u8; 12],
 }
 
@@ -467,6 +484,7 @@ pub struct AtkParam_Pc {
     #[bitflag(field0x81, 6)]
     #[bitflag(field0x81_0, 7)]
     pub bitfield1: u8,
+    #[padding]
     pub pad1: u8,
     pub regainable_slot_id: u8,
     pub death_cause_id: i32,
@@ -576,6 +594,7 @@ pub struct AtkParam_Pc {
     pub field0x195: u8,
     pub dark_sp_correction: i16,
     pub atk_element_correct_id: i32,
+    #[padding]
     pub pad2: [u8; 12],
 }
 
@@ -665,6 +684,7 @@ pub struct AttackElementCorrectParam {
     pub corr_rate22: i16,
     pub corr_rate23: i16,
     pub corr_rate24: i16,
+    #[padding]
     pub pad1: [u8; 24],
 }
 
@@ -675,6 +695,7 @@ pub struct BehaviorParam {
     pub behavior_judge_id: i32,
     pub ez_state_behavior_type_old: u8,
     pub ref_type: u8,
+    #[padding]
     pub pad1: [u8; 2],
     pub ref_id: i32,
     pub sfx_variation_id: i32,
@@ -682,6 +703,7 @@ pub struct BehaviorParam {
     pub mp: i32,
     pub category: u8,
     pub hero_point: u8,
+    #[padding]
     pub pad2: [u8; 2],
 }
 
@@ -692,6 +714,7 @@ pub struct BehaviorParam_PC {
     pub behavior_judge_id: i32,
     pub ez_state_behavior_type_old: u8,
     pub ref_type: u8,
+    #[padding]
     pub pad1: [u8; 2],
     pub ref_id: i32,
     pub sfx_variation_id: i32,
@@ -699,6 +722,7 @@ pub struct BehaviorParam_PC {
     pub mp: i32,
     pub category: u8,
     pub hero_point: u8,
+    #[padding]
     pub pad2: [u8; 2],
 }
 
@@ -714,6 +738,7 @@ pub struct BonfireWarpParam {
     pub is_disable_quickwarp: u8,
     pub ceremony_id: i16,
     pub online_area_id: i32,
+    #[padding]
     pub pad1: [u8; 36],
 }
 
@@ -746,6 +771,7 @@ pub struct BudgetParam {
     pub memory_budget23: f32,
     pub memory_budget24: f32,
     pub memory_budget25: f32,
+    #[padding]
     pub pad1: [u8; 28],
 }
 
@@ -859,8 +885,10 @@ pub struct Bullet {
     pub hit_object_id: i32,
     pub sub_life: f32,
     pub sub_homing_angle: i16,
+    #[padding]
     pub pad1: [u8; 2],
     pub lock_shoot_correction_ang: f32,
+    #[padding]
     pub pad2: [u8; 40],
 }
 
@@ -868,6 +896,7 @@ pub struct Bullet {
 #[repr(C)]
 pub struct BulletCreateLimitParam {
     pub max_ammount: u8,
+    #[padding]
     pub pad1: [u8; 31],
 }
 
@@ -893,6 +922,7 @@ pub struct CalcCorrectGraph {
     pub adjustment_value: f32,
     pub boundry_inclination_soul: f32,
     pub boundry_value: f32,
+    #[padding]
     pub pad1: [u8; 4],
 }
 
@@ -908,6 +938,7 @@ pub struct Ceremony {
     pub light: i32,
     pub is_reload: u8,
     pub is_disable_online: u8,
+    #[padding]
     pub pad1: [u8; 10],
 }
 
@@ -1015,6 +1046,7 @@ pub struct CharaInitParam {
     pub npc_player_sex: u8,
     pub vow_type: u8,
     pub voice_type: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub equip_wep_right_gen_id: i32,
     pub equip_subwep_right_gen_id: i32,
@@ -1041,6 +1073,7 @@ pub struct CharaInitParam {
     pub secondary_item_num_06: u8,
     pub secondary_item_num_07: u8,
     pub secondary_item_num_08: u8,
+    #[padding]
     pub pad2: [u8; 12],
 }
 
@@ -1050,6 +1083,7 @@ pub struct CharMakeMenuListItemParam {
     pub value: i32,
     pub caption_id: i32,
     pub icon_id: u8,
+    #[padding]
     pub pad1: [u8; 7],
 }
 
@@ -1061,8 +1095,10 @@ pub struct CharMakeMenuTopParam {
     pub table_id: i32,
     pub view_condition: i32,
     pub preview_mode: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub menu_type: i8,
+    #[padding]
     pub pad2: [u8; 11],
 }
 
@@ -1096,6 +1132,7 @@ pub struct ClearCountCorrectParam {
     pub hp_recover: f32,
     pub sub_mp_recover: f32,
     pub sub_hp_recover: f32,
+    #[padding]
     pub pad1: [u8; 20],
 }
 
@@ -1121,6 +1158,7 @@ pub struct CultSettingParam {
     pub coefficient: i16,
     pub cult_state1: i8,
     pub cult_state2: i8,
+    #[padding]
     pub pad1: [u8; 16],
 }
 
@@ -1252,6 +1290,7 @@ pub struct DecalParam {
     #[bitflag(field0xB0_28, 31)]
     pub bitfield1: u32,
     pub texture_spawn_delay: f32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -1259,6 +1298,7 @@ pub struct DecalParam {
 #[repr(C)]
 pub struct DirectionCameraParam {
     pub rumble_state: u8,
+    #[padding]
     pub pad1: [u8; 15],
 }
 
@@ -1275,6 +1315,7 @@ pub struct EquipMtrlSetParam {
     pub item_num03: i8,
     pub item_num04: i8,
     pub item_num05: i8,
+    #[padding]
     pub pad1: [u8; 6],
 }
 
@@ -1299,6 +1340,7 @@ pub struct EquipParamAccessory {
     pub accessory_category: u8,
     pub ref_category: u8,
     pub sp_effect_category: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub vagrant_item_lot_id: i32,
     pub vagrant_bonus_ene_drop_item_lot_id: i32,
@@ -1312,6 +1354,7 @@ pub struct EquipParamAccessory {
     #[bitflag(field0x3C_0, 6)]
     #[bitflag(field0x3C_1, 7)]
     pub bitfield0: u8,
+    #[padding]
     pub pad2: [u8; 3],
     pub costvalue: i32,
     pub ring_compatibility_id: i16,
@@ -1321,6 +1364,7 @@ pub struct EquipParamAccessory {
     pub ref_id2: i32,
     pub ref_id3: i32,
     pub ref_id4: i32,
+    #[padding]
     pub pad3: [u8; 8],
 }
 
@@ -1430,10 +1474,12 @@ pub struct EquipParamGoods {
     pub drop: u8,
     pub max_rep_num: i16,
     pub invade_type: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub shop_id: i32,
     pub fp_consume: i16,
     pub use_limit_category2: i16,
+    #[padding]
     pub pad2: [u8; 8],
 }
 
@@ -1602,6 +1648,7 @@ pub struct EquipParamProtector {
     pub material_id7: u16,
     pub protector_category_id: i32,
     pub poise: f32,
+    #[padding]
     pub pad1: [u8; 4],
     pub dark_damage_cut_rate: f32,
     pub defense_dark: i16,
@@ -1959,6 +2006,7 @@ pub struct EquipParamWeapon {
     pub unk65: u8,
     pub unk66: i32,
     pub sp_eff9600: i16,
+    #[padding]
     pub pad1: [u8; 38],
 }
 
@@ -2280,6 +2328,7 @@ pub struct FaceParam {
     pub face_geo_asym_data29: u8,
     pub face_geo_asym_data30: u8,
     pub face_geo_asym_data31: u8,
+    #[padding]
     pub padding: [u8; 18],
 }
 
@@ -2609,6 +2658,7 @@ pub struct GameAreaParam {
     pub humanity_drop_point10: i16,
     pub sub_bonus_soul_single: i32,
     pub subbonus_soul_multi: i32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -2617,6 +2667,7 @@ pub struct GameAreaParam {
 pub struct GameProgressParam {
     pub event_flag_id: i32,
     pub progress_id: u8,
+    #[padding]
     pub pad1: [u8; 11],
 }
 
@@ -2637,6 +2688,7 @@ pub struct GemCategoryParam {
     #[bitflag(enableSlotTypeF, 6)]
     #[bitflag(holygrailTypeGroup, 7)]
     pub bitfield0: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub affinity_cate_id_0: i32,
     pub affinity_modify_rate_0: f32,
@@ -2717,6 +2769,7 @@ pub struct GemeffectParam {
     pub rank_max: i32,
     pub disposal_price: i32,
     pub gem_icon_id_offset: i16,
+    #[padding]
     pub pad1: [u8; 2],
     pub sp_effect_id_for_atk: i32,
 }
@@ -2724,6 +2777,7 @@ pub struct GemeffectParam {
 #[derive(ParamStruct, Debug)]
 #[repr(C)]
 pub struct GemGenParam {
+    #[padding]
     pub pad: [u8; 3],
     pub field0x04: i32,
     pub gem_name_id_offset: i32,
@@ -2735,6 +2789,7 @@ pub struct GemGenParam {
     pub slot_type_rate_e: f32,
     pub slot_type_rate_f: f32,
     pub gem_rank_doping: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub gemeffect_gen_param_type_0: i32,
     pub gemeffect_gen_param_0: i32,
@@ -2960,6 +3015,7 @@ pub struct HitEffectSfxConceptParam {
     pub sfx_concept_id27: i16,
     pub sfx_concept_id28: i16,
     pub sfx_concept_id29: i16,
+    #[padding]
     pub pad1: [u8; 20],
 }
 
@@ -2982,6 +3038,7 @@ pub struct HitEffectSfxParam {
     pub hit_sfx_id13: i32,
     pub hit_sfx_id14: i32,
     pub hit_sfx_id15: i32,
+    #[padding]
     pub pad1: [u8; 16],
 }
 
@@ -3018,6 +3075,7 @@ pub struct HitMtrlParam {
     pub sp_effect_id14: i32,
     pub sp_effect_id15: i32,
     pub sp_effect_id16: i32,
+    #[padding]
     pub pad1: [u8; 20],
 }
 
@@ -3124,6 +3182,7 @@ pub struct ItemLotParam {
     #[bitflag(cumulateReset08, 7)]
     pub bitfield1: u8,
     pub clear_count: i8,
+    #[padding]
     pub pad1: [u8; 3],
 }
 
@@ -3160,6 +3219,7 @@ pub struct KnockBackParam {
     pub guard_l_dec_time: f32,
     pub guard_ll_dec_time: f32,
     pub guard_brake_dec_time: f32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -3168,6 +3228,7 @@ pub struct KnockBackParam {
 pub struct KnowledgeLoadScreenItemParam {
     pub loadscreen_category_id: u32,
     pub knowledge_id: i32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -3195,6 +3256,7 @@ pub struct LoadBalancerDrawDistScaleParam {
     pub lod_dist_draw_scale18: f32,
     pub lod_dist_draw_scale19: f32,
     pub lod_dist_draw_scale20: f32,
+    #[padding]
     pub pad1: [u8; 44],
 }
 
@@ -3225,6 +3287,7 @@ pub struct LoadBalancerParam {
     pub load_balancer_val15: u8,
     pub load_balancer_val16: u8,
     pub load_balancer_val17: u8,
+    #[padding]
     pub pad1: [u8; 38],
 }
 
@@ -3249,6 +3312,7 @@ pub struct LockCamParam {
     pub bullet_auto_capture_dark_character_range_maximum_radius: f32,
     pub bullet_automatic_capture_character_range_ror_pure_darkness_maximum_radius: f32,
     pub bullet_auto_capturing_angle_range_left_and_right: f32,
+    #[padding]
     pub pad1: [u8; 28],
 }
 
@@ -3260,6 +3324,7 @@ pub struct LodParam {
     pub lv12_border_dist: f32,
     pub lv12_play_dist: f32,
     pub texture_lod: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub lv23_border_dist: f32,
     pub lv23_play_dist: f32,
@@ -3268,6 +3333,7 @@ pub struct LodParam {
     pub lv45_border_dist: f32,
     pub lv45_play_dist: f32,
     pub distance_scale_id: u8,
+    #[padding]
     pub pad2: [u8; 19],
 }
 
@@ -3279,6 +3345,7 @@ pub struct LodParam_ps4 {
     pub lv12_border_dist: f32,
     pub lv12_play_dist: f32,
     pub texture_lod: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub lv23_border_dist: f32,
     pub lv23_play_dist: f32,
@@ -3287,6 +3354,7 @@ pub struct LodParam_ps4 {
     pub lv45_border_dist: f32,
     pub lv45_play_dist: f32,
     pub distance_scale_id: u8,
+    #[padding]
     pub pad2: [u8; 19],
 }
 
@@ -3298,6 +3366,7 @@ pub struct LodParam_xb1 {
     pub lv12_border_dist: f32,
     pub lv12_play_dist: f32,
     pub texture_lod: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub lv23_border_dist: f32,
     pub lv23_play_dist: f32,
@@ -3306,6 +3375,7 @@ pub struct LodParam_xb1 {
     pub lv45_border_dist: f32,
     pub lv45_play_dist: f32,
     pub distance_scale_id: u8,
+    #[padding]
     pub pad2: [u8; 19],
 }
 
@@ -3402,6 +3472,7 @@ pub struct Magic {
     pub ref_id2: i32,
     pub ref_id3: i32,
     pub ref_id4: i32,
+    #[padding]
     pub pad1: [u8; 12],
 }
 
@@ -3420,6 +3491,7 @@ pub struct MapMimicryEstablishmentParam {
     pub transform_vfx_id2: i32,
     pub loop_vfx_id2: i32,
     pub destroy_vfx_id2: i32,
+    #[padding]
     pub pad1: [u8; 16],
 }
 
@@ -3433,8 +3505,10 @@ pub struct MenuOffscrRendParam {
     pub menu_content1_2: f32,
     pub menu_content1_3: f32,
     pub menu_content1_4: f32,
+    #[padding]
     pub pad1: [u8; 16],
     pub screen_rend_id: i32,
+    #[padding]
     pub pad2: [u8; 16],
 }
 
@@ -3445,6 +3519,7 @@ pub struct MenuPropertyLayoutParam {
     pub property_id: i32,
     pub caption_text_id: i32,
     pub help_text_id: i32,
+    #[padding]
     pub pad1: [u8; 4],
 }
 
@@ -3465,6 +3540,7 @@ pub struct MenuValueTableParam {
     pub value: i32,
     pub text_id: i32,
     pub compare_type: u8,
+    #[padding]
     pub pad1: [u8; 3],
 }
 
@@ -3473,33 +3549,43 @@ pub struct MenuValueTableParam {
 pub struct ModelSfxParam {
     pub vfx_id1: i32,
     pub dummy_poly_id1: i32,
+    #[padding]
     pub pad1: [u8; 8],
     pub vfx_id2: i32,
     pub dummy_poly_id2: i32,
+    #[padding]
     pub pad2: [u8; 8],
     pub vfx_id3: i32,
     pub dummy_poly_id3: i32,
+    #[padding]
     pub pad3: [u8; 0],
     pub vfx_id4: i32,
     pub dummy_poly_id4: i32,
+    #[padding]
     pub pad4: [u8; 0],
     pub vfx_id5: i32,
     pub dummy_poly_id5: i32,
+    #[padding]
     pub pad5: [u8; 0],
     pub vfx_id6: i32,
     pub dummy_poly_id6: i32,
+    #[padding]
     pub pad6: [u8; 0],
     pub vfx_id7: i32,
     pub dummy_poly_id7: i32,
+    #[padding]
     pub pad7: [u8; 8],
     pub vfx_id8: i32,
     pub dummy_poly_id8: i32,
+    #[padding]
     pub pad8: [u8; 8],
     pub vfx_id9: i32,
     pub dummy_poly_id9: i32,
+    #[padding]
     pub pad9: [u8; 8],
     pub vfx_id10: i32,
     pub dummy_poly_id10: i32,
+    #[padding]
     pub pad10: [u8; 8],
 }
 
@@ -3633,6 +3719,7 @@ pub struct MultiPlayCorrectionParam {
     pub correction_val1: i32,
     pub correction_val2: i32,
     pub correction_val3: i32,
+    #[padding]
     pub pad1: [u8; 16],
 }
 
@@ -3655,6 +3742,7 @@ pub struct MultiSoulBonusRateParam {
     pub soul_multiplier_rate13: f32,
     pub soul_multiplier_rate14: f32,
     pub soul_multiplier_rate15: f32,
+    #[padding]
     pub pad1: [u8; 4],
 }
 
@@ -3664,6 +3752,7 @@ pub struct NetworkAreaParam {
     pub limitation_time0: f32,
     pub limitation_time1: f32,
     pub limitation_time2: f32,
+    #[padding]
     pub pad1: [u8; 12],
     #[bitflag(isEnable00, 0)]
     #[bitflag(isEnable01, 1)]
@@ -3674,6 +3763,7 @@ pub struct NetworkAreaParam {
     #[bitflag(unkb4, 6)]
     #[bitflag(unkb5, 7)]
     pub bitfield0: u8,
+    #[padding]
     pub pad2: [u8; 3],
 }
 
@@ -3707,6 +3797,7 @@ pub struct NetworkMsgParam {
     pub msg_id20: i32,
     pub msg_id21: i32,
     pub msg_id22: i32,
+    #[padding]
     pub pad1: [u8; 48],
 }
 
@@ -3738,6 +3829,7 @@ pub struct NpcAiActionParam {
     pub is_disable_act2: u8,
     pub act_type: i32,
     pub is_disable_ai_check: u8,
+    #[padding]
     pub pad1: [u8; 3],
 }
 
@@ -4000,6 +4092,7 @@ pub struct NpcParam {
     pub sp_effect_id31: i32,
     pub lock_correction: f32,
     pub sub_cloth_update_offset: i8,
+    #[padding]
     pub pad1: [u8; 1],
     pub estus_flask_param_id: i16,
     pub text_id: i32,
@@ -4012,6 +4105,7 @@ pub struct NpcParam {
     pub sub_phantom_param_id: i32,
     pub activate_distance: i16,
     pub deactivate_distance: i16,
+    #[padding]
     pub pad2: [u8; 4],
 }
 
@@ -4101,6 +4195,7 @@ pub struct NpcThinkParam {
     pub unk5: u8,
     pub unk6: u8,
     pub unk7: f32,
+    #[padding]
     pub pad: [u8; 12],
 }
 
@@ -4116,6 +4211,7 @@ pub struct ObjActParam {
     pub sp_qualified_id: i16,
     pub sp_qualified_id2: i16,
     pub obj_dummy_id: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub obj_anim_id: i32,
     pub valid_player_angle: u8,
@@ -4124,6 +4220,7 @@ pub struct ObjActParam {
     pub valid_obj_angle: u8,
     pub chr_sorb_type: u8,
     pub event_kick_timing: u8,
+    #[padding]
     pub pad2: [u8; 2],
     pub action_button_param_id: i32,
     pub action_success_msg_id: i32,
@@ -4243,6 +4340,7 @@ pub struct ObjectParam {
     pub unk44: f32,
     pub sound_id: i32,
     pub object_material_sfx_index: i32,
+    #[padding]
     pub pad1: [u8; 68],
 }
 
@@ -4273,6 +4371,7 @@ pub struct PhantomParam {
     pub ghost_alpha1: f32,
     pub ghost_alpha2: f32,
     pub ghost_type: u8,
+    #[padding]
     pub pad1: [u8; 3],
 }
 
@@ -4314,6 +4413,7 @@ pub struct PlayRegionParam {
     #[bitflag(Unk14, 6)]
     #[bitflag(Unk15, 7)]
     pub bitfield1: u8,
+    #[padding]
     pub pad1: [u8; 31],
 }
 
@@ -4346,6 +4446,7 @@ pub struct RagdollParam {
     pub snap_gain: f32,
     pub enable: u8,
     pub parts_hit_mask_no: i8,
+    #[padding]
     pub pad1: [u8; 14],
 }
 
@@ -4369,6 +4470,7 @@ pub struct ReinforceParamProtector {
     pub material_set_id: u8,
     pub dark_def_rate: f32,
     pub resist_frost: f32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -4402,6 +4504,7 @@ pub struct ReinforceParamWeapon {
     pub resident_sp_effect_id2: u8,
     pub resident_sp_effect_id3: u8,
     pub material_set_id: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub dark_atk_rate: f32,
     pub dark_cut_rate: f32,
@@ -4409,6 +4512,7 @@ pub struct ReinforceParamWeapon {
     pub stability_cut_rate: f32,
     pub frost_guard_resist_rate: f32,
     pub unk1: f32,
+    #[padding]
     pub pad2: [u8; 16],
 }
 
@@ -4416,6 +4520,7 @@ pub struct ReinforceParamWeapon {
 #[repr(C)]
 pub struct RoleParam {
     pub team_type: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub phantom_param_id0: i32,
     pub sp_effect_id0: i32,
@@ -4434,6 +4539,7 @@ pub struct RoleParam {
     pub item_lot_id: i32,
     pub sp_effect_condition: u8,
     pub is_display_team_name: u8,
+    #[padding]
     pub pad2: [u8; 2],
     pub text_id: i32,
     pub sub_team_type: i32,
@@ -4445,6 +4551,7 @@ pub struct RoleParam {
     pub sp_effect12: i32,
     pub sp_effect13: i32,
     pub phantom_param_id_for_debug: i32,
+    #[padding]
     pub pad3: [u8; 20],
 }
 
@@ -4466,6 +4573,7 @@ pub struct ShopLineupParam {
     pub shop_type: u8,
     pub equip_type: u8,
     pub value_san: i16,
+    #[padding]
     pub pad1: [u8; 6],
     pub price_rate: f32,
 }
@@ -4498,6 +4606,7 @@ pub struct SkeletonParam {
     pub twist_knee_axis_type: u8,
     pub neck_turn_priority: u8,
     pub neck_turn_max_angle: u8,
+    #[padding]
     pub pad1: [u8; 2],
 }
 
@@ -4834,6 +4943,7 @@ pub struct SpEffectParam {
     pub faith: i8,
     pub luck: i8,
     pub human_point: i8,
+    #[padding]
     pub pad: [u8; 14],
 }
 
@@ -4906,6 +5016,7 @@ pub struct SpEffectVfxParam {
     pub body_protector_val: i16,
     pub unk14: i16,
     pub cinder_intensity_scale: f32,
+    #[padding]
     pub pad1: [u8; 8],
 }
 
@@ -4926,6 +5037,7 @@ pub struct SwordArtsParam {
     pub f_pcost_light: i16,
     pub fp_cost_strong: i16,
     pub shield_category: u8,
+    #[padding]
     pub pad1: [u8; 11],
 }
 
@@ -4979,6 +5091,7 @@ pub struct ThrowDirectionSfxParam {
     pub sfx_id28: i32,
     pub sfx_id29: i32,
     pub sfx_id30: i32,
+    #[padding]
     pub pad1: [u8; 24],
 }
 
@@ -5026,6 +5139,7 @@ pub struct ThrowParam {
     pub diff_ang_my_to_def2: f32,
     pub perform_dmy_id0: i32,
     pub perform_dmy_id1: i32,
+    #[padding]
     pub pad1: [u8; 32],
 }
 
@@ -5035,8 +5149,10 @@ pub struct ToughnessParam {
     pub toughness: f32,
     pub damage_lvl_threshold: i16,
     pub is_toughness_effective: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub sp_effect_id: i32,
+    #[padding]
     pub pad2: [u8; 20],
 }
 
@@ -5063,6 +5179,7 @@ pub struct UpperArmParam {
     pub arm_xy8: f32,
     pub arm_z9: f32,
     pub arm_xy9: f32,
+    #[padding]
     pub pad1: [u8; 48],
 }
 
@@ -5086,6 +5203,7 @@ pub struct WeaponGenParam {
 #[repr(C)]
 pub struct WepAbsorpPosParam {
     pub shealth_time: u8,
+    #[padding]
     pub pad1: [u8; 3],
     pub one_hand_damipoly_id0: u16,
     pub one_hand_damipoly_id1: u16,
@@ -5139,6 +5257,7 @@ pub struct WepAbsorpPosParam {
     pub unk51: u8,
     pub unk52: u8,
     pub unk53: u8,
+    #[padding]
     pub pad2: [u8; 16],
 }
 
@@ -5148,15 +5267,18 @@ pub struct WetAspectParam {
     pub r1: u8,
     pub g1: u8,
     pub b1: u8,
+    #[padding]
     pub pad1: [u8; 1],
     pub alpha1: f32,
     pub r2: u8,
     pub g2: u8,
     pub b2: u8,
+    #[padding]
     pub pad2: [u8; 1],
     pub alpha2: f32,
     pub wet_rate: f32,
     pub wet_correction: u8,
+    #[padding]
     pub pad3: [u8; 11],
 }
 
@@ -5175,7 +5297,9 @@ pub struct Wind {
     pub common_capsule_begin_dmy_id: i16,
     pub common_capsule_end_dmy_id: i16,
     pub common_capsule_radius: f32,
+    #[padding]
     pub pad1: [u8; 120],
+    #[padding]
     pub pad2: [u8; 3],
     pub sfx_dir_pitch_min: f32,
     pub sfx_dir_pitch_max: f32,
@@ -5186,7 +5310,9 @@ pub struct Wind {
     pub sfx_speed_min: f32,
     pub sfx_speed_max: f32,
     pub sfx_maximum_drag: f32,
+    #[padding]
     pub pad3: [u8; 88],
+    #[padding]
     pub pad4: [u8; 3],
     pub cloth_dir_pitch_min: f32,
     pub cloth_dir_pitch_max: f32,
@@ -5197,5 +5323,6 @@ pub struct Wind {
     pub cloth_speed_min: f32,
     pub cloth_speed_max: f32,
     pub cloth_maximum_drag: f32,
+    #[padding]
     pub pad5: [u8; 88],
 }
@@ -0,0 +1,921 @@
+//! Patches as written in `param-mod.toml`, and the bookkeeping needed to
+//! swap the applied patch for a new one.
+//!
+//! Nothing here touches the game directly: reading and writing params goes
+//! through closures, so that the same logic can run on in-memory rows.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Number, Value};
+
+use super::{
+    f32_value, Expr, FieldType, ParamRow, ParamSnapshot, ParamTable, Params, PARAM_ENUMS,
+    PARAM_VTABLE,
+};
+
+type PatchFields = BTreeMap<String, Value>;
+type PatchRows = Vec<(RowSelector, BTreeMap<String, PatchValue>)>;
+
+/// The rows a table of `param-mod.toml` applies to: a row ID, an inclusive
+/// range of IDs like `"2000000..2999999"`, or `"*"` for every row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowSelector {
+    Id(u64),
+    Range(u64, u64),
+    All,
+}
+
+/// A field value: either written as is, computed from the original
+/// values of the row, or the name of a value of an enum field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchValue {
+    Value(Value),
+    Expr(Expr),
+    Name(String),
+}
+
+/// `param-mod.toml` as written: fields by param name and row selector.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PatchConfig(BTreeMap<String, PatchRows>);
+
+/// Field values by param name, row ID and field name, once the selectors
+/// and expressions of a [`PatchConfig`] are resolved.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResolvedPatch(BTreeMap<String, BTreeMap<u64, PatchFields>>);
+
+impl fmt::Display for RowSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowSelector::Id(id) => write!(f, "{id}"),
+            RowSelector::Range(start, end) => write!(f, "{start}..{end}"),
+            RowSelector::All => write!(f, "*"),
+        }
+    }
+}
+
+impl FromStr for RowSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = |s: &str| s.trim().parse::<u64>().ok();
+
+        let selector = match s.trim() {
+            "*" => Some(RowSelector::All),
+            s => match s.split_once("..") {
+                Some((start, end)) => match (id(start), id(end)) {
+                    (Some(start), Some(end)) if start <= end => {
+                        Some(RowSelector::Range(start, end))
+                    },
+                    _ => None,
+                },
+                None => id(s).map(RowSelector::Id),
+            },
+        };
+
+        selector.ok_or_else(|| {
+            format!("row ID {s} is not a non-negative integer, a range like 10..20 or *")
+        })
+    }
+}
+
+impl RowSelector {
+    pub fn contains(&self, id: u64) -> bool {
+        match *self {
+            RowSelector::Id(i) => i == id,
+            RowSelector::Range(start, end) => (start..=end).contains(&id),
+            RowSelector::All => true,
+        }
+    }
+
+    /// Order in which selectors are applied, so that the more specific
+    /// ones override the others: `*`, then ranges from the widest, then
+    /// row IDs.
+    fn precedence(&self) -> (u8, Reverse<u64>) {
+        match *self {
+            RowSelector::All => (0, Reverse(0)),
+            RowSelector::Range(start, end) => (1, Reverse(end - start)),
+            RowSelector::Id(_) => (2, Reverse(0)),
+        }
+    }
+}
+
+impl FromStr for PatchValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_start().chars().next() {
+            Some('=' | '+' | '-' | '*' | '/') => s.parse().map(PatchValue::Expr),
+            _ => Ok(PatchValue::Name(s.to_string())),
+        }
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Result<Value, String> {
+    match value {
+        toml::Value::Integer(i) => Ok(i.into()),
+        toml::Value::Float(f) => {
+            Number::from_f64(f).map(Value::Number).ok_or_else(|| format!("invalid float {f}"))
+        },
+        toml::Value::Boolean(b) => Ok(b.into()),
+        toml::Value::String(s) => Ok(s.into()),
+        value => Err(format!("unsupported value {value}")),
+    }
+}
+
+fn toml_to_patch_value(value: toml::Value) -> Result<PatchValue, String> {
+    match value {
+        toml::Value::String(s) => s.parse(),
+        value => toml_to_json(value).map(PatchValue::Value),
+    }
+}
+
+/// Splits `field = [a, b]` into `field[0] = a` and `field[1] = b`, which
+/// is how array elements are visited.
+pub(super) fn array_elements(field: String, value: toml::Value) -> Vec<(String, toml::Value)> {
+    match value {
+        toml::Value::Array(values) => values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| (format!("{field}[{i}]"), value))
+            .collect(),
+        value => vec![(field, value)],
+    }
+}
+
+impl FromStr for PatchConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: toml::value::Table = toml::from_str(s).map_err(|e| e.to_string())?;
+        let mut config = BTreeMap::new();
+
+        for (param, rows) in table {
+            let toml::Value::Table(rows) = rows else {
+                return Err(format!("{param}: expected a table of row IDs"));
+            };
+
+            let mut param_rows: Vec<(RowSelector, _)> = Vec::new();
+            for (id_str, fields) in rows {
+                let selector =
+                    id_str.parse::<RowSelector>().map_err(|e| format!("{param}: {e}"))?;
+                let toml::Value::Table(fields) = fields else {
+                    return Err(format!("{param}.{id_str}: expected a table of fields"));
+                };
+
+                let fields = fields
+                    .into_iter()
+                    .flat_map(|(field, value)| array_elements(field, value))
+                    .map(|(field, value)| {
+                        toml_to_patch_value(value)
+                            .map(|value| (field.clone(), value))
+                            .map_err(|e| format!("{param}.{id_str}.{field}: {e}"))
+                    })
+                    .collect::<Result<_, String>>()?;
+
+                // multiple strings could parse to the same int, e.g "0" and "00"
+                if param_rows.iter().any(|(other, _)| *other == selector) {
+                    return Err(format!("{param}.{id_str}: duplicate row ID {selector}"));
+                }
+                param_rows.push((selector, fields));
+            }
+
+            param_rows.sort_by_key(|(selector, _)| selector.precedence());
+            config.insert(param, param_rows);
+        }
+
+        Ok(PatchConfig(config))
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_bool().map(|b| b as u8 as f64))
+}
+
+/// The value to write to a field of type `ty` for the result of an
+/// expression. Integers are rounded to the nearest.
+fn number_value(ty: Option<FieldType>, n: f64) -> Result<Value, String> {
+    if !n.is_finite() {
+        return Err(format!("result {n} is not a number"));
+    }
+
+    match ty {
+        Some(FieldType::F32) => Ok(f32_value(n as f32)),
+        Some(FieldType::Bool) => match n {
+            0. => Ok(false.into()),
+            1. => Ok(true.into()),
+            n => Err(format!("result {n} out of range for bool (0 or 1)")),
+        },
+        Some(ty) => {
+            let (min, max) = ty.int_range().unwrap_or((i64::MIN, i64::MAX));
+            let i = n.round() as i64;
+            if (min..=max).contains(&i) {
+                Ok(i.into())
+            } else {
+                Err(format!("result {i} out of range for {ty} ({min}..={max})"))
+            }
+        },
+        None if n.fract() == 0. => Ok((n as i64).into()),
+        None => Number::from_f64(n).map(Value::Number).ok_or_else(|| format!("invalid result {n}")),
+    }
+}
+
+fn resolve_value(
+    param: &str,
+    field: &str,
+    value: &PatchValue,
+    original: Option<&[(String, Value)]>,
+) -> Result<Value, String> {
+    let expr = match value {
+        PatchValue::Value(value) => return Ok(value.clone()),
+        PatchValue::Name(name) => return PARAM_ENUMS.value(param, field, name).map(Value::from),
+        PatchValue::Expr(expr) => expr,
+    };
+
+    let row = original.ok_or_else(|| "original value unknown".to_string())?;
+    let get = |name: &str| row.iter().find(|(k, _)| k == name).and_then(|(_, v)| as_number(v));
+    let current = get(field).ok_or_else(|| "unknown field".to_string())?;
+
+    let ty = PARAM_VTABLE
+        .get(param)
+        .and_then(|entry| entry.fields.iter().find(|f| f.name == field))
+        .map(|f| f.ty);
+
+    number_value(ty, expr.eval(current, &get)?)
+}
+
+impl PatchConfig {
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Expands row selectors to the rows of `snapshot`, and computes
+    /// expressions from the original values in it. Later selectors
+    /// override earlier ones, so a row ID wins over a range, and a range
+    /// over `*`.
+    pub fn resolve(&self, snapshot: &ParamSnapshot) -> (ResolvedPatch, Vec<String>) {
+        let mut resolved = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        for (param, rules) in &self.0 {
+            let originals = snapshot.rows(param);
+            let mut rows: BTreeMap<u64, PatchFields> = BTreeMap::new();
+
+            for (selector, fields) in rules {
+                let ids = match (selector, originals) {
+                    (RowSelector::Id(id), _) => vec![*id],
+                    (_, Some(originals)) => {
+                        originals.keys().copied().filter(|&id| selector.contains(id)).collect()
+                    },
+                    (_, None) => {
+                        errors.push(format!("Unknown param {param}"));
+                        continue;
+                    },
+                };
+                if ids.is_empty() {
+                    errors.push(format!("{param}[{selector}]: no rows match"));
+                }
+
+                for id in ids {
+                    let original = originals.and_then(|rows| rows.get(&id)).map(Vec::as_slice);
+                    for (field, value) in fields {
+                        match resolve_value(param, field, value, original) {
+                            Ok(value) => {
+                                rows.entry(id).or_default().insert(field.clone(), value);
+                            },
+                            // Reported once per selector rather than for every row
+                            Err(e) => {
+                                let error = format!("{param}[{selector}].{field}: {e}");
+                                if !errors.contains(&error) {
+                                    errors.push(error);
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+
+            if !rows.is_empty() {
+                resolved.insert(param.clone(), rows);
+            }
+        }
+
+        (ResolvedPatch(resolved), errors)
+    }
+
+    /// What applying this patch over the tables returned by `read` would
+    /// change, without writing anything. Unknown params, missing rows and
+    /// unknown fields are reported as errors, as writing them would.
+    pub fn dry_run(&self, mut read: impl FnMut(&str) -> Option<ParamTable>) -> PatchReport {
+        let mut snapshot = ParamSnapshot::new();
+        for param in self.params() {
+            snapshot.capture_with(param, || read(param));
+        }
+
+        let (resolved, errors) = self.resolve(&snapshot);
+        let mut report = PatchReport { changes: Vec::new(), errors };
+
+        for (param, id, field, value) in resolved.fields() {
+            match original_field(&snapshot, param, id, field) {
+                Ok(from) if same_value(from, value) => {},
+                Ok(from) => report.changes.push(FieldChange {
+                    param: param.to_string(),
+                    id,
+                    field: field.to_string(),
+                    from: Some(from.clone()),
+                    to: value.clone(),
+                    reverted: false,
+                }),
+                Err(e) => push_error(&mut report.errors, e),
+            }
+        }
+
+        report
+    }
+}
+
+impl ResolvedPatch {
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, param: &str, id: u64, field: &str) -> Option<&Value> {
+        self.0.get(param)?.get(&id)?.get(field)
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (&str, u64, &str, &Value)> {
+        self.0.iter().flat_map(|(param, rows)| {
+            rows.iter().flat_map(move |(&id, fields)| {
+                fields.iter().map(move |(field, value)| (param.as_str(), id, field.as_str(), value))
+            })
+        })
+    }
+
+    /// Sets the value of a field, or removes it along with the rows and
+    /// params left empty.
+    fn set(&mut self, param: &str, id: u64, field: &str, value: Option<Value>) {
+        if let Some(value) = value {
+            let fields = self.0.entry(param.to_string()).or_default().entry(id).or_default();
+            fields.insert(field.to_string(), value);
+            return;
+        }
+
+        let Some(rows) = self.0.get_mut(param) else {
+            return;
+        };
+        if let Some(fields) = rows.get_mut(&id) {
+            fields.remove(field);
+            if fields.is_empty() {
+                rows.remove(&id);
+            }
+        }
+        if rows.is_empty() {
+            self.0.remove(param);
+        }
+    }
+}
+
+/// A field written when going from one patch to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub param: String,
+    pub id: u64,
+    pub field: String,
+    /// The previously patched value, or the original one if the field
+    /// wasn't patched. `None` if the original value is unknown.
+    pub from: Option<Value>,
+    pub to: Value,
+    /// Whether the field was dropped from the patch and `to` is its
+    /// original value.
+    pub reverted: bool,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}].{}: ", self.param, self.id, self.field)?;
+        match &self.from {
+            Some(from) => write!(f, "{from} -> {}", self.to)?,
+            None => write!(f, "? -> {}", self.to)?,
+        }
+        if self.reverted {
+            write!(f, " (reverted)")?;
+        }
+        Ok(())
+    }
+}
+
+impl FieldChange {
+    /// Whether one of the errors returned when writing the table of the
+    /// change is about its row or field.
+    fn failed_in(&self, errors: &[String]) -> bool {
+        let row = format!("{}[{}]: ", self.param, self.id);
+        errors.iter().filter_map(|e| e.strip_prefix(&row)).any(|e| {
+            e == "row not found"
+                || e.strip_prefix("Unknown field ") == Some(&self.field)
+                || e.strip_prefix(&self.field).is_some_and(|e| e.starts_with(": "))
+        })
+    }
+}
+
+/// The original value of a field in `snapshot`, or the error writing to it
+/// would give.
+fn original_field<'a>(
+    snapshot: &'a ParamSnapshot,
+    param: &str,
+    id: u64,
+    field: &str,
+) -> Result<&'a Value, String> {
+    let rows = snapshot.rows(param).ok_or_else(|| format!("Unknown param {param}"))?;
+    let row = rows.get(&id).ok_or_else(|| format!("{param}[{id}]: row not found"))?;
+    row.iter()
+        .find(|(k, _)| k == field)
+        .map(|(_, v)| v)
+        .ok_or_else(|| format!("{param}[{id}]: Unknown field {field}"))
+}
+
+fn push_error(errors: &mut Vec<String>, error: String) {
+    if !errors.contains(&error) {
+        errors.push(error);
+    }
+}
+
+/// Numbers are compared by value, as the snapshot stores whole floats as
+/// integers.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Outcome of applying a patch.
+#[derive(Debug, Default)]
+pub struct PatchReport {
+    pub changes: Vec<FieldChange>,
+    pub errors: Vec<String>,
+}
+
+/// The currently applied patch, and the original values of the params it
+/// touches.
+#[derive(Debug, Default)]
+pub struct PatchState {
+    applied: ResolvedPatch,
+    snapshot: ParamSnapshot,
+}
+
+impl PatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn applied(&self) -> &ResolvedPatch {
+        &self.applied
+    }
+
+    fn original(&self, param: &str, id: u64, field: &str) -> Option<&Value> {
+        self.snapshot.original(param, id)?.iter().find(|(k, _)| k == field).map(|(_, v)| v)
+    }
+
+    /// The fields to write to go from the applied patch to `next`: those
+    /// whose value changed, and those no longer patched, which go back to
+    /// their original value. Fields with an unknown original value can't
+    /// be reverted and are returned as errors.
+    pub fn changes(&self, next: &ResolvedPatch) -> (Vec<FieldChange>, Vec<String>) {
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+
+        for (param, id, field, value) in self.applied.fields() {
+            if next.get(param, id, field).is_some() {
+                continue;
+            }
+            match self.original(param, id, field) {
+                Some(original) if !same_value(original, value) => changes.push(FieldChange {
+                    param: param.to_string(),
+                    id,
+                    field: field.to_string(),
+                    from: Some(value.clone()),
+                    to: original.clone(),
+                    reverted: true,
+                }),
+                Some(_) => {},
+                None => errors.push(format!("{param}[{id}].{field}: original value unknown")),
+            }
+        }
+
+        for (param, id, field, value) in next.fields() {
+            let from = self
+                .applied
+                .get(param, id, field)
+                .or_else(|| self.original(param, id, field))
+                .cloned();
+            if from.as_ref().is_some_and(|from| same_value(from, value)) {
+                continue;
+            }
+            changes.push(FieldChange {
+                param: param.to_string(),
+                id,
+                field: field.to_string(),
+                from,
+                to: value.clone(),
+                reverted: false,
+            });
+        }
+
+        (changes, errors)
+    }
+
+    /// Replaces the applied patch with `next`, resolved against the
+    /// original values of the params.
+    ///
+    /// `capture` reads a whole param the first time `next` touches it, so
+    /// that its original values can be restored later on. `write` applies
+    /// a table and returns the rows and fields that couldn't be written.
+    ///
+    /// Only the fields that were written are reported and recorded as
+    /// applied. Fields of params that couldn't be captured are not written,
+    /// like in a dry run.
+    pub fn apply(
+        &mut self,
+        next: PatchConfig,
+        mut capture: impl FnMut(&str) -> Option<ParamTable>,
+        mut write: impl FnMut(&ParamTable) -> Result<Vec<String>, String>,
+    ) -> PatchReport {
+        for param in next.params() {
+            self.snapshot.capture_with(param, || capture(param));
+        }
+
+        let (next, mut errors) = next.resolve(&self.snapshot);
+        let (changes, change_errors) = self.changes(&next);
+        errors.extend(change_errors);
+
+        let mut pending = Vec::new();
+        let mut failed = Vec::new();
+        for change in changes {
+            match original_field(&self.snapshot, &change.param, change.id, &change.field) {
+                Ok(_) => pending.push(change),
+                Err(e) => {
+                    push_error(&mut errors, e);
+                    failed.push(change);
+                },
+            }
+        }
+
+        let mut tables: BTreeMap<&str, BTreeMap<u64, PatchFields>> = BTreeMap::new();
+        for change in &pending {
+            tables
+                .entry(&change.param)
+                .or_default()
+                .entry(change.id)
+                .or_default()
+                .insert(change.field.clone(), change.to.clone());
+        }
+
+        let mut write_errors = Vec::new();
+        let mut unwritten_params = Vec::new();
+        for (param, rows) in tables {
+            let table = ParamTable {
+                param: param.to_string(),
+                rows: rows
+                    .into_iter()
+                    .map(|(id, fields)| ParamRow {
+                        id,
+                        name: None,
+                        fields: fields.into_iter().collect(),
+                    })
+                    .collect(),
+            };
+            match write(&table) {
+                Ok(e) => write_errors.extend(e),
+                Err(e) => {
+                    push_error(&mut errors, e);
+                    unwritten_params.push(table.param);
+                },
+            }
+        }
+
+        let (written, unwritten): (Vec<_>, Vec<_>) = pending.into_iter().partition(|change| {
+            !unwritten_params.contains(&change.param) && !change.failed_in(&write_errors)
+        });
+        errors.extend(write_errors);
+        failed.extend(unwritten);
+
+        // Fields that weren't written keep the value they had before
+        let mut applied = next;
+        for change in &failed {
+            let previous = self.applied.get(&change.param, change.id, &change.field).cloned();
+            applied.set(&change.param, change.id, &change.field, previous);
+        }
+        self.applied = applied;
+
+        PatchReport { changes: written, errors }
+    }
+}
+
+impl Params {
+    /// Replaces the patch applied to the game params with `next`.
+    pub fn apply_patch(&self, state: &mut PatchState, next: PatchConfig) -> PatchReport {
+        state.apply(next, |param| self.export_param(param), |table| self.import_param(table))
+    }
+
+    /// What applying `patch` would change, without writing to the params.
+    pub fn dry_run_patch(&self, patch: &PatchConfig) -> PatchReport {
+        patch.dry_run(|param| self.export_param(param))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::test_param::{table, vanilla, TestParam, IDS, TEST_PARAM};
+    use crate::prelude::*;
+
+    fn apply(state: &mut PatchState, rows: &mut [TestParam], config: &str) -> PatchReport {
+        let config: PatchConfig = config.parse().unwrap();
+        let rows = std::cell::RefCell::new(rows);
+
+        state.apply(
+            config,
+            |param| (param == TEST_PARAM).then(|| table(&mut rows.borrow_mut())),
+            |table| {
+                if table.param != TEST_PARAM {
+                    return Err(format!("Unknown param {}", table.param));
+                }
+                Ok(table.apply_to_rows(IDS.into_iter().zip(rows.borrow_mut().iter_mut())))
+            },
+        )
+    }
+
+    fn report(report: &PatchReport) -> Vec<String> {
+        report.changes.iter().map(|c| c.to_string()).chain(report.errors.clone()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = r#"
+            [TestParam.10]
+            hp = 150
+            speed = 0.5
+            isBoss = true
+            ids = [1, 2]
+            "ids[3]" = 4
+        "#
+        .parse::<PatchConfig>()
+        .unwrap()
+        .resolve(&ParamSnapshot::new())
+        .0;
+
+        assert_eq!(config.params().collect::<Vec<_>>(), vec!["TestParam"]);
+        assert_eq!(config.get("TestParam", 10, "hp"), Some(&Value::from(150)));
+        assert_eq!(config.get("TestParam", 10, "isBoss"), Some(&Value::from(true)));
+        assert_eq!(config.get("TestParam", 20, "hp"), None);
+        assert_eq!(config.get("TestParam", 10, "ids[1]"), Some(&Value::from(2)));
+        assert_eq!(config.get("TestParam", 10, "ids[3]"), Some(&Value::from(4)));
+
+        assert!("[Foo.bar]\nhp = 1".parse::<PatchConfig>().is_err());
+        assert!("[Foo.0]\nhp = 1\n[Foo.00]\nhp = 2".parse::<PatchConfig>().is_err());
+        assert!("[Foo.0]\nhp = [[1], 2]".parse::<PatchConfig>().is_err());
+        assert!("Foo = 1".parse::<PatchConfig>().is_err());
+        assert!("[Foo.\"1..5\"]\nhp = 1\n[Foo.\"1..05\"]\nhp = 2".parse::<PatchConfig>().is_err());
+        assert!("[Foo.0]\nhp = \"=1.2.\"".parse::<PatchConfig>().is_err());
+    }
+
+    #[test]
+    fn test_row_selectors() {
+        assert_eq!("12".parse(), Ok(RowSelector::Id(12)));
+        assert_eq!("10..20".parse(), Ok(RowSelector::Range(10, 20)));
+        assert_eq!("*".parse(), Ok(RowSelector::All));
+        assert!("20..10".parse::<RowSelector>().is_err());
+        assert!("10..".parse::<RowSelector>().is_err());
+        assert!("-1".parse::<RowSelector>().is_err());
+
+        assert!(RowSelector::Range(10, 20).contains(20));
+        assert!(!RowSelector::Range(10, 20).contains(21));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut rows = vanilla();
+        let mut snapshot = ParamSnapshot::new();
+        snapshot.capture(&table(&mut rows));
+
+        let config: PatchConfig = r#"
+            [TestParam."*"]
+            hp = "*1.5"
+            [TestParam."15..25"]
+            speed = "=hp / 100"
+            [TestParam.20]
+            hp = 1
+            isBoss = "=original - 1"
+            [TestParam.30]
+            hp = "+1"
+            [TestParam."40..50"]
+            hp = 1
+            [Other."*"]
+            hp = 1
+        "#
+        .parse()
+        .unwrap();
+
+        let (resolved, errors) = config.resolve(&snapshot);
+        let fields = resolved.fields().map(|(_, id, field, v)| format!("{id}.{field} = {v}"));
+        assert_eq!(fields.collect::<Vec<_>>(), vec![
+            "10.hp = 150",
+            "20.hp = 1",
+            "20.isBoss = 0",
+            "20.speed = 2",
+        ]);
+        assert_eq!(errors, vec![
+            "Unknown param Other",
+            "TestParam[40..50]: no rows match",
+            "TestParam[30].hp: original value unknown",
+        ]);
+    }
+
+    #[test]
+    fn test_resolve_types() {
+        let mut snapshot = ParamSnapshot::new();
+        snapshot.capture(&ParamTable {
+            param: "ItemLotParam".to_string(),
+            rows: vec![ParamRow {
+                id: 1,
+                name: None,
+                fields: vec![
+                    ("lot_item_base_point01".to_string(), 30000.into()),
+                    ("lot_item_base_point02".to_string(), 7.into()),
+                ],
+            }],
+        });
+
+        let resolve = |value: &str| {
+            let config = format!("[ItemLotParam.1]\nlot_item_base_point02 = \"{value}\"");
+            let (resolved, errors) = config.parse::<PatchConfig>().unwrap().resolve(&snapshot);
+            match errors.first() {
+                Some(e) => e.clone(),
+                None => {
+                    resolved.get("ItemLotParam", 1, "lot_item_base_point02").unwrap().to_string()
+                },
+            }
+        };
+
+        assert_eq!(resolve("*1.3"), "9");
+        assert_eq!(resolve("/2"), "4");
+        assert_eq!(resolve("=lot_item_base_point01 + original"), "30007");
+        assert_eq!(
+            resolve("=lot_item_base_point01 * 2"),
+            "ItemLotParam[1].lot_item_base_point02: result 60000 out of range for i16 \
+             (-32768..=32767)"
+        );
+        assert_eq!(resolve("=nope"), "ItemLotParam[1].lot_item_base_point02: unknown field nope");
+        assert_eq!(
+            resolve("1.2"),
+            "ItemLotParam[1].lot_item_base_point02: \"1.2\" is not an expression, and \
+             lot_item_base_point02 has no named values"
+        );
+    }
+
+    #[test]
+    fn test_reload_expressions() {
+        let mut state = PatchState::new();
+        let mut rows = vanilla();
+
+        // Expressions are computed from the original values, so reloading
+        // doesn't compound them
+        let config = "[TestParam.\"*\"]\nhp = \"*2\"";
+        assert_eq!(report(&apply(&mut state, &mut rows, config)), vec![
+            "TestParam[10].hp: 100 -> 200",
+            "TestParam[20].hp: 200 -> 400",
+        ]);
+        assert!(apply(&mut state, &mut rows, config).changes.is_empty());
+        assert_eq!((rows[0].hp, rows[1].hp), (200, 400));
+
+        apply(&mut state, &mut rows, "");
+        assert_eq!(rows, vanilla());
+    }
+
+    #[test]
+    fn test_apply_and_reload() {
+        let mut state = PatchState::new();
+        let mut rows = vanilla();
+
+        let first = apply(&mut state, &mut rows, "[TestParam.10]\nhp = 150\nspeed = 0.5");
+        assert_eq!(report(&first), vec![
+            "TestParam[10].hp: 100 -> 150",
+            "TestParam[10].speed: 1 -> 0.5",
+        ]);
+        assert_eq!(rows[0], TestParam { hp: 150, speed: 0.5, ..vanilla()[0].clone() });
+
+        let second = apply(
+            &mut state,
+            &mut rows,
+            "[TestParam.10]\nhp = 150\n[TestParam.20]\nisBoss = false",
+        );
+        assert_eq!(report(&second), vec![
+            "TestParam[10].speed: 0.5 -> 1 (reverted)",
+            "TestParam[20].isBoss: true -> false",
+        ]);
+        assert_eq!(rows, vec![TestParam { hp: 150, ..vanilla()[0].clone() }, TestParam {
+            flags: 0,
+            ..vanilla()[1].clone()
+        }]);
+
+        let third = apply(&mut state, &mut rows, "");
+        assert_eq!(report(&third).len(), 2);
+        assert_eq!(rows, vanilla());
+        assert_eq!(state.applied(), &ResolvedPatch::default());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut state = PatchState::new();
+        let mut rows = vanilla();
+
+        let config = "[TestParam.30]\nhp = 1\n[TestParam.10]\nnope = 1\n[Other.1]\nhp = 1";
+        let report = apply(&mut state, &mut rows, config);
+        assert!(report.changes.is_empty());
+        assert_eq!(report.errors, vec![
+            "Unknown param Other",
+            "TestParam[10]: Unknown field nope",
+            "TestParam[30]: row not found",
+        ]);
+        assert_eq!(rows, vanilla());
+        assert_eq!(state.applied(), &ResolvedPatch::default());
+
+        let dry_run = config
+            .parse::<PatchConfig>()
+            .unwrap()
+            .dry_run(|param| (param == TEST_PARAM).then(|| table(&mut rows)));
+        assert_eq!(report.errors, dry_run.errors);
+
+        let report = apply(&mut state, &mut rows, "");
+        assert!(report.changes.is_empty());
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn test_write_errors() {
+        let mut state = PatchState::new();
+        let mut rows = vanilla();
+        let config = |config: &str| config.parse::<PatchConfig>().unwrap();
+
+        // Nothing is recorded when the whole table can't be written
+        let report = state.apply(
+            config("[TestParam.10]\nhp = 150"),
+            |_| Some(table(&mut rows)),
+            |_| Err("Param TestParam not loaded".to_string()),
+        );
+        assert!(report.changes.is_empty());
+        assert_eq!(report.errors, vec!["Param TestParam not loaded"]);
+        assert_eq!(state.applied(), &ResolvedPatch::default());
+
+        // Only the field that failed is left out
+        let report = state.apply(
+            config("[TestParam.10]\nhp = 150\nspeed = 0.5"),
+            |_| unreachable!(),
+            |_| Ok(vec!["TestParam[10]: speed: invalid value 0.5".to_string()]),
+        );
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].to_string(), "TestParam[10].hp: 100 -> 150");
+        assert_eq!(report.errors, vec!["TestParam[10]: speed: invalid value 0.5"]);
+        assert_eq!(state.applied().get(TEST_PARAM, 10, "hp"), Some(&Value::from(150)));
+        assert_eq!(state.applied().get(TEST_PARAM, 10, "speed"), None);
+
+        // A revert that fails keeps the field applied, so the next reload
+        // tries again
+        let report = state.apply(
+            config(""),
+            |_| unreachable!(),
+            |_| Err("Param TestParam not loaded".to_string()),
+        );
+        assert!(report.changes.is_empty());
+        assert_eq!(state.applied().get(TEST_PARAM, 10, "hp"), Some(&Value::from(150)));
+
+        let report = state.apply(config(""), |_| unreachable!(), |_| Ok(Vec::new()));
+        assert_eq!(report.changes[0].to_string(), "TestParam[10].hp: 150 -> 100 (reverted)");
+        assert_eq!(state.applied(), &ResolvedPatch::default());
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let mut rows = vanilla();
+        let config: PatchConfig = "[TestParam.10]\nhp = 150\nspeed = 1.0\nnope = \
+                                   1\n[TestParam.20]\nisBoss = false\n[TestParam.30]\nhp = \
+                                   1\n[Other.1]\nhp = 1"
+            .parse()
+            .unwrap();
+
+        let report = config.dry_run(|param| (param == TEST_PARAM).then(|| table(&mut rows)));
+
+        assert_eq!(report.changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(), vec![
+            "TestParam[10].hp: 100 -> 150",
+            "TestParam[20].isBoss: true -> false",
+        ]);
+        assert_eq!(report.errors, vec![
+            "Unknown param Other",
+            "TestParam[10]: Unknown field nope",
+            "TestParam[30]: row not found",
+        ]);
+        assert_eq!(rows, vanilla());
+    }
+}
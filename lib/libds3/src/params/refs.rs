@@ -0,0 +1,209 @@
+//! Fields holding the ID of a row of another param, like the special
+//! effects of a weapon, from the param metadata of Paramdex. Generated
+//! along with the param structs by `cargo xtask codegen`.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use super::{ParamTable, Params};
+
+pub static PARAM_REFS: Lazy<ParamRefs> =
+    Lazy::new(|| ParamRefs::from_json(include_str!("param_refs.json")).unwrap());
+
+/// Target params by param name and field name.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParamRefs(HashMap<String, HashMap<String, Vec<String>>>);
+
+/// A field of a row that refers to another row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Reference {
+    pub param: String,
+    pub id: u64,
+    pub field: String,
+}
+
+impl ParamRefs {
+    /// Reads `{ "Param": { "field": ["TargetParam", ...] } }`.
+    pub fn from_json(json: &str) -> Result<ParamRefs, String> {
+        serde_json::from_str(json).map(ParamRefs).map_err(|e| e.to_string())
+    }
+
+    /// The params a field can refer to, empty if it is not a reference.
+    pub fn targets(&self, param: &str, field: &str) -> &[String] {
+        self.0.get(param).and_then(|fields| fields.get(field)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The rows a field value refers to, among the targets of the field
+    /// that have a row with that ID. Negative values refer to nothing.
+    pub fn resolve(
+        &self,
+        param: &str,
+        field: &str,
+        value: i64,
+        has_row: impl Fn(&str, u64) -> bool,
+    ) -> Vec<(String, u64)> {
+        let Ok(id) = u64::try_from(value) else {
+            return Vec::new();
+        };
+
+        self.targets(param, field)
+            .iter()
+            .filter(|target| has_row(target, id))
+            .map(|target| (target.clone(), id))
+            .collect()
+    }
+
+    /// The params with fields referring to `target`.
+    pub fn referring_params(&self, target: &str) -> Vec<&str> {
+        let mut params = self
+            .0
+            .iter()
+            .filter(|(_, fields)| {
+                fields.values().any(|targets| targets.iter().any(|t| t == target))
+            })
+            .map(|(param, _)| param.as_str())
+            .collect::<Vec<_>>();
+        params.sort();
+        params
+    }
+
+    /// The fields of every row of `table` referring to row `id` of
+    /// `target`.
+    pub fn references_in(&self, table: &ParamTable, target: &str, id: u64) -> Vec<Reference> {
+        let Some(fields) = self.0.get(&table.param) else {
+            return Vec::new();
+        };
+
+        table
+            .rows
+            .iter()
+            .flat_map(|row| {
+                row.fields
+                    .iter()
+                    .filter(|(field, value)| {
+                        value.as_u64() == Some(id)
+                            && fields.get(field).is_some_and(|t| t.iter().any(|t| t == target))
+                    })
+                    .map(|(field, _)| Reference {
+                        param: table.param.clone(),
+                        id: row.id,
+                        field: field.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Every field referring to row `id` of `target`, reading the tables of
+    /// the params that can refer to it with `read`.
+    pub fn references_to(
+        &self,
+        target: &str,
+        id: u64,
+        mut read: impl FnMut(&str) -> Option<ParamTable>,
+    ) -> Vec<Reference> {
+        self.referring_params(target)
+            .into_iter()
+            .filter_map(&mut read)
+            .flat_map(|table| self.references_in(&table, target, id))
+            .collect()
+    }
+}
+
+impl Params {
+    /// Whether a param has a row with the given ID.
+    pub fn has_row(&self, param: &str, id: u64) -> bool {
+        unsafe { self.iter_param_ids(param) }.is_some_and(|mut ids| ids.any(|i| i == id))
+    }
+
+    /// The rows a field value refers to, see [`ParamRefs::resolve`].
+    pub fn resolve_ref(&self, param: &str, field: &str, value: i64) -> Vec<(String, u64)> {
+        PARAM_REFS.resolve(param, field, value, |target, id| self.has_row(target, id))
+    }
+
+    /// Every field of the game params referring to row `id` of `target`.
+    pub fn references_to(&self, target: &str, id: u64) -> Vec<Reference> {
+        PARAM_REFS.references_to(target, id, |param| self.export_param(param))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamRow;
+
+    fn refs() -> ParamRefs {
+        ParamRefs::from_json(
+            r#"{
+                "EquipParamWeapon": {
+                    "sp_effect_behavior_id0": ["SpEffectParam"],
+                    "resident_sp_effect_id": ["SpEffectParam", "Magic"]
+                },
+                "SpEffectParam": { "cycle_occurrence_sp_effect_id": ["SpEffectParam"] },
+                "NpcParam": { "think_id": ["NpcThinkParam"] }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn table(param: &str, rows: &[(u64, &[(&str, i64)])]) -> ParamTable {
+        ParamTable {
+            param: param.to_string(),
+            rows: rows
+                .iter()
+                .map(|(id, fields)| ParamRow {
+                    id: *id,
+                    name: None,
+                    fields: fields.iter().map(|(k, v)| (k.to_string(), (*v).into())).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let refs = refs();
+        let has_row = |param: &str, id| param == "Magic" && id == 100;
+
+        assert_eq!(refs.targets("EquipParamWeapon", "sp_effect_behavior_id0"), ["SpEffectParam"]);
+        assert!(refs.targets("EquipParamWeapon", "weight").is_empty());
+        assert_eq!(refs.resolve("EquipParamWeapon", "resident_sp_effect_id", 100, has_row), [(
+            "Magic".to_string(),
+            100
+        )]);
+        assert!(refs
+            .resolve("EquipParamWeapon", "resident_sp_effect_id", -1, |_, _| true)
+            .is_empty());
+        assert!(refs.resolve("EquipParamWeapon", "weight", 100, |_, _| true).is_empty());
+    }
+
+    #[test]
+    fn test_references_to() {
+        let refs = refs();
+        assert_eq!(refs.referring_params("SpEffectParam"), ["EquipParamWeapon", "SpEffectParam"]);
+
+        let read = |param: &str| match param {
+            "EquipParamWeapon" => Some(table(param, &[
+                (1000, &[("sp_effect_behavior_id0", 100), ("resident_sp_effect_id", 100)]),
+                (2000, &[("sp_effect_behavior_id0", 200), ("resident_sp_effect_id", 100)]),
+            ])),
+            "SpEffectParam" => {
+                Some(table(param, &[(100, &[("cycle_occurrence_sp_effect_id", 100)])]))
+            },
+            _ => None,
+        };
+
+        let found = refs
+            .references_to("SpEffectParam", 100, read)
+            .into_iter()
+            .map(|r| format!("{}[{}].{}", r.param, r.id, r.field))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [
+            "EquipParamWeapon[1000].sp_effect_behavior_id0",
+            "EquipParamWeapon[1000].resident_sp_effect_id",
+            "EquipParamWeapon[2000].resident_sp_effect_id",
+            "SpEffectParam[100].cycle_occurrence_sp_effect_id",
+        ]);
+        assert!(refs.references_to("NpcThinkParam", 1, read).is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+//! Params read from the game's files instead of its memory: the encrypted
+//! regulation (`Data0.bdt`), and the DCX and BND4 containers around the
+//! `.param` files.
+
+use std::collections::BTreeMap;
+use std::ffi::c_void;
+use std::io::Read;
+use std::{mem, ptr, slice};
+
+use aes::cipher::block_padding::NoPadding;
+use aes::cipher::{BlockDecryptMut, KeyIvInit};
+use flate2::read::ZlibDecoder;
+use log::error;
+
+use super::{ParamEntryOffset, Params, PARAM_VTABLE};
+
+const REGULATION_KEY: &[u8; 32] = b"ds3#jn/8_7(rsY9pg55GFN7VFL#+3n/)";
+
+type RegulationDecryptor = cbc::Decryptor<aes::Aes256>;
+
+// Layout of a `.param` file, which the game keeps in memory as is.
+const PARAM_ROW_COUNT: usize = 0x0a;
+const PARAM_ROWS: usize = 0x40;
+const PARAM_ROW_SIZE: usize = mem::size_of::<ParamEntryOffset>();
+
+// BND4 format flags, in the bit order of SoulsFormats.
+const BND4_IDS: u8 = 0b0000_0010;
+const BND4_NAMES: u8 = 0b0000_1100;
+const BND4_LONG_OFFSETS: u8 = 0b0001_0000;
+const BND4_COMPRESSION: u8 = 0b0010_0000;
+
+fn bytes(data: &[u8], offset: usize, len: usize) -> Result<&[u8], String> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| format!("{len} bytes at {offset:#x} out of bounds"))
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], String> {
+    bytes(data, offset, N).map(|b| b.try_into().unwrap())
+}
+
+/// Decrypts a regulation file. The first 16 bytes are the IV.
+pub fn decrypt_regulation(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 16 || !data.len().is_multiple_of(16) {
+        return Err(format!("Regulation size {} is not a multiple of 16", data.len()));
+    }
+
+    let (iv, encrypted) = data.split_at(16);
+    let mut decrypted = encrypted.to_vec();
+    RegulationDecryptor::new_from_slices(REGULATION_KEY, iv)
+        .map_err(|e| e.to_string())?
+        .decrypt_padded_mut::<NoPadding>(&mut decrypted)
+        .map_err(|_| "Couldn't decrypt regulation".to_string())?;
+
+    Ok(decrypted)
+}
+
+/// Decompresses a DCX file. Only the DEFLATE format used by DS3 is
+/// supported.
+pub fn decompress_dcx(data: &[u8]) -> Result<Vec<u8>, String> {
+    if !data.starts_with(b"DCX\0") {
+        return Err("Not a DCX file".to_string());
+    }
+
+    let format = read::<4>(data, 0x28)?;
+    if &format != b"DFLT" {
+        return Err(format!("Unsupported DCX format {}", String::from_utf8_lossy(&format)));
+    }
+
+    let data_offset = u32::from_be_bytes(read(data, 0x14)?) as usize;
+    let uncompressed_size = u32::from_be_bytes(read(data, 0x1c)?) as usize;
+    let compressed_size = u32::from_be_bytes(read(data, 0x20)?) as usize;
+
+    let mut decompressed = Vec::with_capacity(uncompressed_size);
+    ZlibDecoder::new(bytes(data, data_offset, compressed_size)?)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("Couldn't decompress DCX: {e}"))?;
+
+    if decompressed.len() != uncompressed_size {
+        return Err(format!(
+            "DCX decompressed to {} bytes instead of {uncompressed_size}",
+            decompressed.len()
+        ));
+    }
+
+    Ok(decompressed)
+}
+
+/// Unpacks the files of a little-endian BND4 container, as names and
+/// contents. Files compressed with DCX are decompressed.
+pub fn unpack_bnd4(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    if !data.starts_with(b"BND4") {
+        return Err("Not a BND4 file".to_string());
+    }
+    if read::<1>(data, 0x09)?[0] != 0 {
+        return Err("Big-endian BND4 files are not supported".to_string());
+    }
+
+    let bit_big_endian = read::<1>(data, 0x0a)?[0] == 0;
+    let count = u32::from_le_bytes(read(data, 0x0c)?) as usize;
+    let file_header_size = u64::from_le_bytes(read(data, 0x20)?) as usize;
+    let unicode = read::<1>(data, 0x30)?[0] != 0;
+    let raw_format = read::<1>(data, 0x31)?[0];
+    let format = if bit_big_endian || (raw_format & 1 != 0 && raw_format & 0x80 == 0) {
+        raw_format
+    } else {
+        raw_format.reverse_bits()
+    };
+
+    (0..count)
+        .map(|i| {
+            // Skip the flags and the -1 that start every file header
+            let mut offset = 0x40 + i * file_header_size + 8;
+            let size = u64::from_le_bytes(read(data, offset)?) as usize;
+            offset += 8;
+            if format & BND4_COMPRESSION != 0 {
+                offset += 8;
+            }
+            let data_offset = if format & BND4_LONG_OFFSETS != 0 {
+                offset += 8;
+                u64::from_le_bytes(read(data, offset - 8)?) as usize
+            } else {
+                offset += 4;
+                u32::from_le_bytes(read(data, offset - 4)?) as usize
+            };
+            if format & BND4_IDS != 0 {
+                offset += 4;
+            }
+
+            let name = if format & BND4_NAMES != 0 {
+                let name_offset = u32::from_le_bytes(read(data, offset)?) as usize;
+                read_name(data, name_offset, unicode)?
+            } else {
+                i.to_string()
+            };
+
+            let contents = bytes(data, data_offset, size)?;
+            let contents = if contents.starts_with(b"DCX\0") {
+                decompress_dcx(contents).map_err(|e| format!("{name}: {e}"))?
+            } else {
+                contents.to_vec()
+            };
+
+            Ok((name, contents))
+        })
+        .collect()
+}
+
+fn read_name(data: &[u8], offset: usize, unicode: bool) -> Result<String, String> {
+    let tail = data.get(offset..).ok_or_else(|| format!("Name at {offset:#x} out of bounds"))?;
+
+    if unicode {
+        let chars = tail
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect::<Vec<_>>();
+        String::from_utf16(&chars).map_err(|e| format!("Name at {offset:#x}: {e}"))
+    } else {
+        // Shift JIS in general, but param file names are ASCII
+        let len = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+        Ok(String::from_utf8_lossy(&tail[..len]).into_owned())
+    }
+}
+
+/// Unpacks the `.param` files of a regulation, a `parambnd` or a
+/// `parambnd.dcx`, by param name.
+pub fn unpack_param_files(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let decrypted;
+    let data = if data.starts_with(b"BND4") || data.starts_with(b"DCX\0") {
+        data
+    } else {
+        decrypted = decrypt_regulation(data)?;
+        &decrypted
+    };
+
+    let decompressed;
+    let data = if data.starts_with(b"DCX\0") {
+        decompressed = decompress_dcx(data)?;
+        &decompressed
+    } else {
+        data
+    };
+
+    Ok(unpack_bnd4(data)?
+        .into_iter()
+        .filter_map(|(name, contents)| {
+            let file_name = name.rsplit(['\\', '/']).next()?;
+            let param = file_name.strip_suffix(".param")?;
+            Some((param.to_string(), contents))
+        })
+        .collect())
+}
+
+/// Checks that the row table and the rows of a `.param` file are in bounds
+/// and aligned, so that they can be read like the game's memory.
+fn check_param_file(data: &[u8], row_size: usize) -> Result<(), String> {
+    let count = u16::from_le_bytes(read(data, PARAM_ROW_COUNT)?) as usize;
+    bytes(data, PARAM_ROWS, count * PARAM_ROW_SIZE)?;
+
+    for i in 0..count {
+        let entry = PARAM_ROWS + i * PARAM_ROW_SIZE;
+        let row_offset = u64::from_le_bytes(read(data, entry + 8)?) as usize;
+        if !row_offset.is_multiple_of(mem::align_of::<u32>()) {
+            return Err(format!("Row {i} at {row_offset:#x} is not aligned"));
+        }
+        bytes(data, row_offset, row_size).map_err(|e| format!("Row {i}: {e}"))?;
+    }
+
+    Ok(())
+}
+
+impl Params {
+    /// Reads params from a regulation (`Data0.bdt`), a `parambnd` or a
+    /// `parambnd.dcx` file, without the game.
+    pub fn from_regulation(data: &[u8]) -> Result<Params, String> {
+        Ok(Params::from_param_files(unpack_param_files(data)?))
+    }
+
+    /// Reads params from the contents of `.param` files, by param name.
+    /// Files whose rows are out of bounds are logged and skipped.
+    pub fn from_param_files(files: impl IntoIterator<Item = (String, Vec<u8>)>) -> Params {
+        let mut params = Params { params: BTreeMap::new(), files: Vec::new() };
+
+        for (name, contents) in files {
+            let row_size = PARAM_VTABLE.get(&name).map(|entry| entry.size).unwrap_or(0);
+            if let Err(e) = check_param_file(&contents, row_size) {
+                error!("{name}: {e}");
+                continue;
+            }
+
+            // Copied to a buffer aligned like the game's, for the param
+            // structs to be read in place.
+            let mut file = vec![0u64; contents.len().div_ceil(8)].into_boxed_slice();
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    contents.as_ptr(),
+                    file.as_mut_ptr() as *mut u8,
+                    contents.len(),
+                )
+            };
+
+            let count = u16::from_le_bytes(read(&contents, PARAM_ROW_COUNT).unwrap());
+            params.params.insert(name, (file.as_mut_ptr() as *const c_void, count as isize));
+            params.files.push(file);
+        }
+
+        params
+    }
+
+    /// Compares the size of the rows of each param, measured between
+    /// consecutive rows, with the size of its struct. Returns the
+    /// mismatches.
+    pub fn layout_errors(&self) -> Vec<String> {
+        self.params
+            .iter()
+            .filter_map(|(name, &(param_ptr, count))| {
+                let entry = PARAM_VTABLE.get(name)?;
+                let entries = unsafe {
+                    slice::from_raw_parts(
+                        param_ptr.add(PARAM_ROWS) as *const ParamEntryOffset,
+                        count as usize,
+                    )
+                };
+
+                let mut offsets = entries.iter().map(|e| e.param_offset).collect::<Vec<_>>();
+                offsets.sort();
+                offsets.dedup();
+                let row_size = offsets.windows(2).map(|w| w[1] - w[0]).min()? as usize;
+
+                (row_size != entry.size).then(|| {
+                    format!("{name}: rows are {row_size:#x} bytes, the struct is {:#x}", entry.size)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use aes::cipher::BlockEncryptMut;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    fn param_file(rows: &[(u64, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; PARAM_ROWS + rows.len() * PARAM_ROW_SIZE];
+        data[PARAM_ROW_COUNT..PARAM_ROW_COUNT + 2]
+            .copy_from_slice(&(rows.len() as u16).to_le_bytes());
+
+        for (i, (id, row)) in rows.iter().enumerate() {
+            let entry = PARAM_ROWS + i * PARAM_ROW_SIZE;
+            let row_offset = data.len() as u64;
+            data[entry..entry + 8].copy_from_slice(&id.to_le_bytes());
+            data[entry + 8..entry + 16].copy_from_slice(&row_offset.to_le_bytes());
+            data.extend(row);
+        }
+
+        data
+    }
+
+    // Laid out like DS3's: unicode names, IDs and 32-bit offsets.
+    fn bnd4(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let headers_end = 0x40 + files.len() * 0x24;
+
+        let mut out = vec![0u8; 0x40];
+        out[..4].copy_from_slice(b"BND4");
+        out[0x0a] = 1;
+        out[0x0c..0x10].copy_from_slice(&(files.len() as u32).to_le_bytes());
+        out[0x10..0x18].copy_from_slice(&0x40u64.to_le_bytes());
+        out[0x18..0x20].copy_from_slice(b"07D7R6\0\0");
+        out[0x20..0x28].copy_from_slice(&0x24u64.to_le_bytes());
+        out[0x28..0x30].copy_from_slice(&(headers_end as u64).to_le_bytes());
+        out[0x30] = 1;
+        out[0x31] = 0x74;
+
+        let mut names = Vec::new();
+        let mut name_offsets = Vec::new();
+        for (name, _) in files {
+            name_offsets.push(headers_end + names.len());
+            names.extend(name.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        }
+
+        let mut data_offset = headers_end + names.len();
+        for (i, ((_, contents), name_offset)) in files.iter().zip(name_offsets).enumerate() {
+            out.extend([0x40, 0, 0, 0]);
+            out.extend((-1i32).to_le_bytes());
+            out.extend((contents.len() as u64).to_le_bytes());
+            out.extend((contents.len() as u64).to_le_bytes());
+            out.extend((data_offset as u32).to_le_bytes());
+            out.extend((i as i32).to_le_bytes());
+            out.extend((name_offset as u32).to_le_bytes());
+            data_offset += contents.len();
+        }
+
+        out.extend(names);
+        files.iter().for_each(|(_, contents)| out.extend(contents));
+        out
+    }
+
+    fn dcx(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut out = b"DCX\0".to_vec();
+        [0x11000u32, 0x18, 0x24, 0x44, 0x4c].iter().for_each(|v| out.extend(v.to_be_bytes()));
+        out.extend(b"DCS\0");
+        out.extend((data.len() as u32).to_be_bytes());
+        out.extend((compressed.len() as u32).to_be_bytes());
+        out.extend(b"DCP\0DFLT");
+        out.extend(0x20u32.to_be_bytes());
+        out.extend([9, 0, 0, 0]);
+        out.extend([0; 16]);
+        out.extend(b"DCA\0");
+        out.extend(8u32.to_be_bytes());
+        out.extend(compressed);
+        out
+    }
+
+    fn encrypt(data: &[u8]) -> Vec<u8> {
+        let iv = [7u8; 16];
+        let mut encrypted = data.to_vec();
+        encrypted.resize(data.len().div_ceil(16) * 16, 0);
+        cbc::Encryptor::<aes::Aes256>::new_from_slices(REGULATION_KEY, &iv)
+            .unwrap()
+            .encrypt_padded_mut::<NoPadding>(&mut encrypted, data.len().div_ceil(16) * 16)
+            .unwrap();
+
+        [iv.as_slice(), &encrypted].concat()
+    }
+
+    fn item_lot_row(base_point: i16) -> Vec<u8> {
+        let entry = &PARAM_VTABLE["ItemLotParam"];
+        let field = entry.fields.iter().find(|f| f.name == "lot_item_base_point01").unwrap();
+        let mut row = vec![0u8; entry.size];
+        row[field.offset..field.offset + 2].copy_from_slice(&base_point.to_le_bytes());
+        row
+    }
+
+    #[test]
+    fn test_from_regulation() {
+        let bnd = bnd4(&[
+            (
+                r"N:\FDP\data\Param\param\GameParam\ItemLotParam.param",
+                param_file(&[(10, item_lot_row(100)), (20, item_lot_row(1000))]),
+            ),
+            (r"N:\FDP\data\Param\param\GameParam\readme.txt", b"hi".to_vec()),
+        ]);
+
+        for data in [bnd.clone(), dcx(&bnd), encrypt(&bnd), encrypt(&dcx(&bnd))] {
+            let params = Params::from_regulation(&data).unwrap();
+            assert_eq!(params.keys().collect::<Vec<_>>(), ["ItemLotParam"]);
+
+            let ids = unsafe { params.iter_param_ids("ItemLotParam") }.unwrap();
+            assert_eq!(ids.collect::<Vec<_>>(), [10, 20]);
+
+            let row = params.export_row("ItemLotParam", 1).unwrap();
+            assert_eq!(row.id, 20);
+            assert!(row.fields.contains(&("lot_item_base_point01".to_string(), 1000.into())));
+
+            assert_eq!(params.layout_errors(), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn test_bad_param_files() {
+        let item_lot_size = PARAM_VTABLE["ItemLotParam"].size;
+        let npc_think_size = PARAM_VTABLE["NpcThinkParam"].size;
+
+        let params = Params::from_param_files([
+            (
+                "ItemLotParam".to_string(),
+                param_file(&[(1, vec![0; item_lot_size - 4]), (2, vec![0; item_lot_size - 4])]),
+            ),
+            (
+                "NpcThinkParam".to_string(),
+                param_file(&[(1, vec![0; npc_think_size + 4]), (2, vec![0; npc_think_size + 4])]),
+            ),
+        ]);
+
+        assert_eq!(params.keys().collect::<Vec<_>>(), ["NpcThinkParam"]);
+        assert_eq!(params.layout_errors(), [format!(
+            "NpcThinkParam: rows are {:#x} bytes, the struct is {npc_think_size:#x}",
+            npc_think_size + 4
+        )]);
+
+        assert!(decrypt_regulation(&[0; 20]).is_err());
+        assert!(unpack_param_files(b"BND4").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! Field names and types of the param structs, known without reading the
+//! game's memory.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    I8,
+    I16,
+    I32,
+    F32,
+    Bool,
+}
+
+/// A field of a param struct, as listed in [`ParamStruct::FIELDS`].
+///
+/// Bitflags are listed as `bool` fields sharing the offset of their byte,
+/// with the index of their bit. Arrays are listed element by element.
+///
+/// [`ParamStruct::FIELDS`]: crate::ParamStruct::FIELDS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub ty: FieldType,
+    pub offset: usize,
+    pub size: usize,
+    pub bit: Option<u8>,
+    /// Whether this is padding, only visited if
+    /// [`ParamVisitor::visit_padding`] says so.
+    ///
+    /// [`ParamVisitor::visit_padding`]: crate::ParamVisitor::visit_padding
+    pub padding: bool,
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::I8 => "i8",
+            FieldType::I16 => "i16",
+            FieldType::I32 => "i32",
+            FieldType::F32 => "f32",
+            FieldType::Bool => "bool",
+        })
+    }
+}
+
+impl FieldType {
+    /// Inclusive range of integer types.
+    pub fn int_range(self) -> Option<(i64, i64)> {
+        match self {
+            FieldType::U8 => Some((u8::MIN.into(), u8::MAX.into())),
+            FieldType::U16 => Some((u16::MIN.into(), u16::MAX.into())),
+            FieldType::U32 => Some((u32::MIN.into(), u32::MAX.into())),
+            FieldType::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            FieldType::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            FieldType::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            FieldType::F32 | FieldType::Bool => None,
+        }
+    }
+
+    /// Checks that a TOML value can be written to a field of this type.
+    /// Integers are accepted for floats, and 0 or 1 for booleans.
+    pub fn check(self, value: &toml::Value) -> Result<(), String> {
+        match (self, value) {
+            (FieldType::Bool, toml::Value::Boolean(_)) => Ok(()),
+            (FieldType::Bool, toml::Value::Integer(0 | 1)) => Ok(()),
+            (FieldType::Bool, toml::Value::Integer(i)) => {
+                Err(format!("value {i} out of range for bool (0 or 1)"))
+            },
+            (FieldType::F32, toml::Value::Integer(_)) => Ok(()),
+            (FieldType::F32, toml::Value::Float(f)) => {
+                if f.is_finite() && f.abs() > f32::MAX as f64 {
+                    Err(format!("value {f} out of range for f32"))
+                } else {
+                    Ok(())
+                }
+            },
+            _ => match (self.int_range(), value) {
+                (Some((min, max)), toml::Value::Integer(i)) if (min..=max).contains(i) => Ok(()),
+                (Some((min, max)), toml::Value::Integer(i)) => {
+                    Err(format!("value {i} out of range for {self} ({min}..={max})"))
+                },
+                (_, value) => Err(format!("expected {self}, found {}", value.type_str())),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::test_param::TestParam;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_field_infos() {
+        let field =
+            |name, ty, offset, size, bit| FieldInfo { name, ty, offset, size, bit, padding: false };
+
+        assert_eq!(TestParam::FIELDS, &[
+            field("hp", FieldType::U32, 0, 4, None),
+            field("isBoss", FieldType::Bool, 4, 1, Some(0)),
+            field("isGhost", FieldType::Bool, 4, 1, Some(3)),
+            FieldInfo { padding: true, ..field("pad[0]", FieldType::U8, 5, 1, None) },
+            field("level", FieldType::I16, 6, 2, None),
+            field("speed", FieldType::F32, 8, 4, None),
+            field("ids[0]", FieldType::U16, 12, 2, None),
+            field("ids[1]", FieldType::U16, 14, 2, None),
+        ]);
+    }
+
+    #[test]
+    fn test_visit_arrays_and_padding() {
+        let mut row = TestParam { ids: [10, 20], ..Default::default() };
+
+        let mut serializer = RowSerializer::default();
+        row.visit(&mut serializer);
+        let names = serializer.0.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["hp", "isBoss", "isGhost", "level", "speed", "ids[0]", "ids[1]"]);
+        assert_eq!(serializer.0[6].1, 20);
+
+        struct PaddingVisitor(Vec<String>);
+
+        impl ParamVisitor for PaddingVisitor {
+            fn visit_u8(&mut self, name: &str, _: &mut u8) {
+                self.0.push(name.to_string());
+            }
+
+            fn visit_u16(&mut self, _: &str, _: &mut u16) {}
+
+            fn visit_u32(&mut self, _: &str, _: &mut u32) {}
+
+            fn visit_i8(&mut self, _: &str, _: &mut i8) {}
+
+            fn visit_i16(&mut self, _: &str, _: &mut i16) {}
+
+            fn visit_i32(&mut self, _: &str, _: &mut i32) {}
+
+            fn visit_f32(&mut self, _: &str, _: &mut f32) {}
+
+            fn visit_bool(&mut self, _: &str, _: &mut bool) {}
+
+            fn visit_padding(&self) -> bool {
+                true
+            }
+        }
+
+        let mut visitor = PaddingVisitor(Vec::new());
+        row.visit(&mut visitor);
+        assert_eq!(visitor.0, ["pad[0]"]);
+    }
+
+    #[test]
+    fn test_generated_layouts() {
+        for (param, entry) in PARAM_VTABLE.iter() {
+            for pair in entry.fields.windows(2) {
+                let (prev, field) = (&pair[0], &pair[1]);
+                let same_byte =
+                    prev.bit.is_some() && field.bit.is_some() && prev.offset == field.offset;
+                assert!(
+                    same_byte || field.offset >= prev.offset + prev.size,
+                    "{param}.{} overlaps {}",
+                    field.name,
+                    prev.name
+                );
+            }
+            for field in entry.fields {
+                assert!(
+                    field.offset + field.size <= entry.size,
+                    "{param}.{} out of bounds",
+                    field.name
+                );
+            }
+        }
+
+        let fields = PARAM_VTABLE["ItemLotParam"].fields;
+        assert!(fields.iter().any(|f| f.name == "lot_item_base_point01" && f.ty == FieldType::I16));
+    }
+
+    #[test]
+    fn test_check() {
+        use toml::Value;
+
+        assert!(FieldType::U8.check(&Value::Integer(255)).is_ok());
+        assert!(FieldType::U8.check(&Value::Integer(256)).is_err());
+        assert!(FieldType::I16.check(&Value::Integer(-32768)).is_ok());
+        assert!(FieldType::U32.check(&Value::Integer(-1)).is_err());
+        assert!(FieldType::I32.check(&Value::Float(1.0)).is_err());
+        assert!(FieldType::F32.check(&Value::Integer(1)).is_ok());
+        assert!(FieldType::F32.check(&Value::Float(1e39)).is_err());
+        assert!(FieldType::Bool.check(&Value::Integer(1)).is_ok());
+        assert!(FieldType::Bool.check(&Value::Integer(2)).is_err());
+        assert!(FieldType::Bool.check(&Value::String("true".to_string())).is_err());
+    }
+}
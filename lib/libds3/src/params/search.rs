@@ -0,0 +1,200 @@
+//! Searching params by name, rows by ID or name, and fields by value.
+//!
+//! Everything works on names and exported [`ParamTable`]s, so that it can
+//! run without the game.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::ParamTable;
+
+/// Whether all the characters of `needle` appear in `haystack` in the same
+/// order, ignoring case.
+pub fn string_match(needle: &str, haystack: &str) -> bool {
+    let needle = needle.chars().flat_map(char::to_lowercase);
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+
+    'o: for c in needle {
+        for d in &mut haystack {
+            if c == d {
+                continue 'o;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Whether a row matches a search: its ID contains the query, or its name
+/// matches it.
+pub fn row_matches(query: &str, id: u64, name: Option<&str>) -> bool {
+    let query = query.trim();
+    query.is_empty()
+        || id.to_string().contains(query)
+        || name.is_some_and(|name| string_match(query, name))
+}
+
+/// A value to look for in the fields of a table: a number, `true` or
+/// `false`, or an inclusive range like `10..20`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueQuery {
+    Equal(f64),
+    Range(f64, f64),
+}
+
+/// A field whose value matched a [`ValueQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueMatch {
+    pub id: u64,
+    pub name: Option<String>,
+    pub field: String,
+    pub value: Value,
+}
+
+impl FromStr for ValueQuery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |s: &str| match s.trim() {
+            "true" => Ok(1.),
+            "false" => Ok(0.),
+            n => n.parse::<f64>().map_err(|_| format!("{n:?} is not a number")),
+        };
+
+        match s.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (number(start)?, number(end)?);
+                if start <= end {
+                    Ok(ValueQuery::Range(start, end))
+                } else {
+                    Err(format!("range {s} is empty"))
+                }
+            },
+            None => number(s).map(ValueQuery::Equal),
+        }
+    }
+}
+
+impl fmt::Display for ValueQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueQuery::Equal(n) => write!(f, "{n}"),
+            ValueQuery::Range(start, end) => write!(f, "{start}..{end}"),
+        }
+    }
+}
+
+impl ValueQuery {
+    /// Floats are compared at `f32` precision, so that `0.1` finds fields
+    /// set to `0.1`.
+    pub fn matches(&self, value: &Value) -> bool {
+        let Some(v) = value.as_f64().or_else(|| value.as_bool().map(|b| b as u8 as f64)) else {
+            return false;
+        };
+
+        match *self {
+            ValueQuery::Equal(n) => v == n || v as f32 == n as f32,
+            ValueQuery::Range(start, end) => (start..=end).contains(&v),
+        }
+    }
+}
+
+/// Fields of every row of `table` matching `query`. Only fields whose name
+/// matches `field` are looked at, unless it is empty.
+pub fn find_values(table: &ParamTable, field: &str, query: &ValueQuery) -> Vec<ValueMatch> {
+    let field = field.trim();
+
+    table
+        .rows
+        .iter()
+        .flat_map(|row| {
+            row.fields
+                .iter()
+                .filter(|(name, value)| {
+                    (field.is_empty() || string_match(field, name)) && query.matches(value)
+                })
+                .map(|(name, value)| ValueMatch {
+                    id: row.id,
+                    name: row.name.clone(),
+                    field: name.clone(),
+                    value: value.clone(),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamRow;
+
+    #[test]
+    fn test_string_match() {
+        assert!(string_match("spef", "SpEffectParam"));
+        assert!(string_match("", "SpEffectParam"));
+        assert!(!string_match("fes", "SpEffectParam"));
+
+        assert!(row_matches("", 10, None));
+        assert!(row_matches("200", 1200300, None));
+        assert!(row_matches("dagger", 1000, Some("Dagger")));
+        assert!(!row_matches("sword", 1000, Some("Dagger")));
+    }
+
+    #[test]
+    fn test_value_query() {
+        assert_eq!("12".parse(), Ok(ValueQuery::Equal(12.)));
+        assert_eq!("true".parse(), Ok(ValueQuery::Equal(1.)));
+        assert_eq!(" -1 .. 2.5 ".parse(), Ok(ValueQuery::Range(-1., 2.5)));
+        assert!("2..1".parse::<ValueQuery>().is_err());
+        assert!("abc".parse::<ValueQuery>().is_err());
+        assert!("1..".parse::<ValueQuery>().is_err());
+
+        assert!(ValueQuery::Equal(1.).matches(&Value::from(true)));
+        assert!(ValueQuery::Equal(0.1).matches(&Value::from(0.1f32 as f64)));
+        assert!(ValueQuery::Range(10., 20.).matches(&Value::from(20)));
+        assert!(!ValueQuery::Range(10., 20.).matches(&Value::from(21)));
+        assert!(!ValueQuery::Equal(1.).matches(&Value::from("1")));
+    }
+
+    #[test]
+    fn test_find_values() {
+        let row = |id, name: Option<&str>, fields: &[(&str, Value)]| ParamRow {
+            id,
+            name: name.map(str::to_string),
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        };
+        let table = ParamTable {
+            param: "SpEffectParam".to_string(),
+            rows: vec![
+                row(10, Some("Poison"), &[
+                    ("effect_endurance", 90.into()),
+                    ("state_info", 5.into()),
+                ]),
+                row(20, None, &[("effect_endurance", 5.into()), ("state_info", 2.into())]),
+            ],
+        };
+
+        let found = |field, query: &str| {
+            find_values(&table, field, &query.parse().unwrap())
+                .into_iter()
+                .map(|m| format!("{}.{} = {}", m.id, m.field, m.value))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(found("", "5"), ["10.state_info = 5", "20.effect_endurance = 5"]);
+        assert_eq!(found("state", "5"), ["10.state_info = 5"]);
+        assert_eq!(found("", "2..90"), [
+            "10.effect_endurance = 90",
+            "10.state_info = 5",
+            "20.effect_endurance = 5",
+            "20.state_info = 2",
+        ]);
+        assert!(found("nope", "5").is_empty());
+        assert_eq!(
+            find_values(&table, "", &ValueQuery::Equal(90.))[0].name.as_deref(),
+            Some("Poison")
+        );
+    }
+}
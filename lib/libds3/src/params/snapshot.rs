@@ -0,0 +1,262 @@
+//! Original values of param tables, to diff against and revert to.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+
+use super::{ParamRow, ParamTable, Params, RowSerializer, PARAM_VTABLE};
+
+/// A field whose current value differs from the snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub original: Value,
+    pub current: Value,
+}
+
+/// The changed fields of a row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowDiff {
+    pub id: u64,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// What to revert.
+#[derive(Debug, Clone, Copy)]
+pub enum RevertScope<'a> {
+    Field(u64, &'a str),
+    Row(u64),
+    Table,
+}
+
+/// Param tables as they were when first captured, by param and row ID.
+#[derive(Debug, Default)]
+pub struct ParamSnapshot(HashMap<String, BTreeMap<u64, Vec<(String, Value)>>>);
+
+impl ParamSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, param: &str) -> bool {
+        self.0.contains_key(param)
+    }
+
+    /// Stores the table, unless a snapshot of that param was already taken.
+    pub fn capture(&mut self, table: &ParamTable) {
+        self.0
+            .entry(table.param.clone())
+            .or_insert_with(|| table.rows.iter().map(|row| (row.id, row.fields.clone())).collect());
+    }
+
+    /// Stores the table returned by `f` if there is no snapshot of `param`
+    /// yet. Returns whether a snapshot of `param` is available.
+    pub fn capture_with(&mut self, param: &str, f: impl FnOnce() -> Option<ParamTable>) -> bool {
+        if !self.contains(param) {
+            if let Some(table) = f() {
+                self.capture(&table);
+            }
+        }
+        self.contains(param)
+    }
+
+    /// The original rows of `param`, by ID.
+    pub fn rows(&self, param: &str) -> Option<&BTreeMap<u64, Vec<(String, Value)>>> {
+        self.0.get(param)
+    }
+
+    pub fn original(&self, param: &str, id: u64) -> Option<&[(String, Value)]> {
+        self.0.get(param)?.get(&id).map(Vec::as_slice)
+    }
+
+    /// The fields of `current` that differ from the snapshot. Rows that
+    /// are not in the snapshot have no diff.
+    pub fn diff_row(&self, param: &str, current: &ParamRow) -> Vec<FieldDiff> {
+        let Some(original) = self.original(param, current.id) else {
+            return Vec::new();
+        };
+        let original: HashMap<_, _> = original.iter().map(|(k, v)| (k.as_str(), v)).collect();
+
+        current
+            .fields
+            .iter()
+            .filter_map(|(field, current)| {
+                let original = *original.get(field.as_str())?;
+                (original != current).then(|| FieldDiff {
+                    field: field.clone(),
+                    original: original.clone(),
+                    current: current.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// The changed rows of `current`, in order.
+    pub fn diff_table(&self, current: &ParamTable) -> Vec<RowDiff> {
+        current
+            .rows
+            .iter()
+            .map(|row| RowDiff { id: row.id, fields: self.diff_row(&current.param, row) })
+            .filter(|diff| !diff.fields.is_empty())
+            .collect()
+    }
+
+    /// A table that, once applied, restores the original values in `scope`.
+    pub fn revert_patch(&self, param: &str, scope: RevertScope) -> Option<ParamTable> {
+        let rows = self.0.get(param)?;
+        let row = |id: u64, fields: Vec<(String, Value)>| ParamRow { id, name: None, fields };
+
+        let rows = match scope {
+            RevertScope::Field(id, field) => {
+                let value = rows.get(&id)?.iter().find(|(k, _)| k == field)?;
+                vec![row(id, vec![value.clone()])]
+            },
+            RevertScope::Row(id) => vec![row(id, rows.get(&id)?.clone())],
+            RevertScope::Table => {
+                rows.iter().map(|(&id, fields)| row(id, fields.clone())).collect()
+            },
+        };
+
+        Some(ParamTable { param: param.to_string(), rows })
+    }
+}
+
+impl Params {
+    /// Reads a single row by index.
+    pub fn export_row(&self, param: &str, param_idx: usize) -> Option<ParamRow> {
+        PARAM_VTABLE.get(param)?;
+        let id = unsafe { self.iter_param_ids(param) }?.nth(param_idx)?;
+
+        let mut serializer = RowSerializer::default();
+        self.visit_param_item(param, param_idx, &mut serializer);
+
+        Some(ParamRow { id, name: None, fields: serializer.0 })
+    }
+
+    /// Takes a snapshot of `param` if there isn't one yet.
+    pub fn snapshot_param(&self, snapshot: &mut ParamSnapshot, param: &str) -> bool {
+        snapshot.capture_with(param, || self.export_param(param))
+    }
+
+    /// The changed rows of `param`, capturing it first if needed.
+    pub fn diff_param(&self, snapshot: &mut ParamSnapshot, param: &str) -> Vec<RowDiff> {
+        if !self.snapshot_param(snapshot, param) {
+            return Vec::new();
+        }
+
+        self.export_param(param).map(|table| snapshot.diff_table(&table)).unwrap_or_default()
+    }
+
+    /// Restores the original values in `scope`. Returns the rows and fields
+    /// that couldn't be written.
+    pub fn revert_param(
+        &self,
+        snapshot: &ParamSnapshot,
+        param: &str,
+        scope: RevertScope,
+    ) -> Result<Vec<String>, String> {
+        let patch = snapshot
+            .revert_patch(param, scope)
+            .ok_or_else(|| format!("No snapshot of {param} for {scope:?}"))?;
+
+        self.import_param(&patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::test_param::{table, vanilla, TestParam, IDS, TEST_PARAM};
+
+    fn modded() -> (ParamSnapshot, Vec<TestParam>) {
+        let mut rows = vanilla();
+        let mut snapshot = ParamSnapshot::new();
+        snapshot.capture(&table(&mut rows));
+
+        rows[0].hp = 150;
+        rows[0].set_is_boss(true);
+        rows[1].speed = 0.2;
+
+        (snapshot, rows)
+    }
+
+    #[test]
+    fn test_diff() {
+        let (snapshot, mut rows) = modded();
+        let diff = snapshot.diff_table(&table(&mut rows));
+
+        assert_eq!(diff, vec![
+            RowDiff {
+                id: 10,
+                fields: vec![
+                    FieldDiff {
+                        field: "hp".to_string(),
+                        original: Value::from(100),
+                        current: Value::from(150),
+                    },
+                    FieldDiff {
+                        field: "isBoss".to_string(),
+                        original: Value::from(false),
+                        current: Value::from(true),
+                    },
+                ],
+            },
+            RowDiff {
+                id: 20,
+                fields: vec![FieldDiff {
+                    field: "speed".to_string(),
+                    original: serde_json::from_str("0.1").unwrap(),
+                    current: serde_json::from_str("0.2").unwrap(),
+                }],
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_capture_once() {
+        let (mut snapshot, mut rows) = modded();
+        snapshot.capture(&table(&mut rows));
+        assert_eq!(snapshot.diff_table(&table(&mut rows)).len(), 2);
+
+        assert!(!snapshot.capture_with("Other", || None));
+        assert!(snapshot.capture_with(TEST_PARAM, || unreachable!()));
+    }
+
+    fn revert(snapshot: &ParamSnapshot, rows: &mut [TestParam], scope: RevertScope) {
+        let errors = snapshot
+            .revert_patch(TEST_PARAM, scope)
+            .unwrap()
+            .apply_to_rows(IDS.into_iter().zip(rows.iter_mut()));
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_revert_field() {
+        let (snapshot, mut rows) = modded();
+        revert(&snapshot, &mut rows, RevertScope::Field(10, "isBoss"));
+
+        assert_eq!(rows[0], TestParam { hp: 150, ..vanilla()[0].clone() });
+        assert_eq!(rows[1].speed, 0.2);
+        assert!(snapshot.revert_patch(TEST_PARAM, RevertScope::Field(10, "nope")).is_none());
+    }
+
+    #[test]
+    fn test_revert_row() {
+        let (snapshot, mut rows) = modded();
+        revert(&snapshot, &mut rows, RevertScope::Row(10));
+
+        assert_eq!(rows[0], vanilla()[0]);
+        assert_eq!(rows[1].speed, 0.2);
+        assert!(snapshot.revert_patch(TEST_PARAM, RevertScope::Row(30)).is_none());
+    }
+
+    #[test]
+    fn test_revert_table() {
+        let (snapshot, mut rows) = modded();
+        revert(&snapshot, &mut rows, RevertScope::Table);
+
+        assert_eq!(rows, vanilla());
+        assert!(snapshot.diff_table(&table(&mut rows)).is_empty());
+        assert!(snapshot.revert_patch("Other", RevertScope::Table).is_none());
+    }
+}
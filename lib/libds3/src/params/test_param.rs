@@ -0,0 +1,37 @@
+//! A param shared by the tests of the params modules.
+
+use macro_param::ParamStruct;
+
+use crate::prelude::*;
+
+pub(crate) const TEST_PARAM: &str = "TestParam";
+
+/// Row IDs of the rows of [`vanilla`].
+pub(crate) const IDS: [u64; 2] = [10, 20];
+
+#[derive(ParamStruct, Debug, Default, Clone, PartialEq)]
+#[repr(C)]
+#[param(size = 16)]
+pub struct TestParam {
+    pub hp: u32,
+    #[bitflag(isBoss, 0)]
+    #[bitflag(isGhost, 3)]
+    pub flags: u8,
+    #[padding]
+    pub pad: [u8; 1],
+    pub level: i16,
+    pub speed: f32,
+    pub ids: [u16; 2],
+}
+
+/// The rows before any edit.
+pub(crate) fn vanilla() -> Vec<TestParam> {
+    vec![
+        TestParam { hp: 100, level: -3, speed: 1.0, ids: [1, 2], ..Default::default() },
+        TestParam { hp: 200, flags: 1, level: 300, speed: 0.1, ..Default::default() },
+    ]
+}
+
+pub(crate) fn table(rows: &mut [TestParam]) -> ParamTable {
+    ParamTable::from_rows(TEST_PARAM, IDS.into_iter().zip(rows.iter_mut()))
+}
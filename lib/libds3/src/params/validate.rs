@@ -0,0 +1,208 @@
+//! Checks of `param-mod.toml` against the param schema, without the game.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use toml::Spanned;
+
+use super::patch::array_elements;
+use super::{PatchValue, RowSelector, PARAM_ENUMS, PARAM_VTABLE};
+
+type SpannedFields = BTreeMap<Spanned<String>, Spanned<toml::Value>>;
+type SpannedPatch = BTreeMap<Spanned<String>, BTreeMap<Spanned<String>, SpannedFields>>;
+
+/// A problem found in a patch, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+struct Source<'a>(&'a str);
+
+impl Source<'_> {
+    fn at<T>(&self, spanned: &Spanned<T>, message: String) -> Diagnostic {
+        let before = &self.0[..spanned.start().min(self.0.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+
+        Diagnostic { line, column, message }
+    }
+}
+
+/// Checks every param name, row selector, field name, value and expression
+/// of a `param-mod.toml` source. Returns the problems found, in order.
+pub fn validate_patch(source: &str) -> Vec<Diagnostic> {
+    let src = Source(source);
+
+    let patch: SpannedPatch = match toml::from_str(source) {
+        Ok(patch) => patch,
+        Err(e) => {
+            let (line, column) = e.line_col().map(|(l, c)| (l + 1, c + 1)).unwrap_or((1, 1));
+            return vec![Diagnostic { line, column, message: e.to_string() }];
+        },
+    };
+
+    let mut diagnostics = Vec::new();
+
+    for (param, rows) in &patch {
+        let Some(entry) = PARAM_VTABLE.get(param.get_ref()) else {
+            diagnostics.push(src.at(param, format!("Unknown param {}", param.get_ref())));
+            continue;
+        };
+        let fields: HashMap<_, _> = entry.fields.iter().map(|f| (f.name, f)).collect();
+
+        // Rows in source order, so that duplicates are reported after the
+        // row they duplicate
+        let mut rows = rows.iter().collect::<Vec<_>>();
+        rows.sort_by_key(|(id_str, _)| id_str.start());
+
+        let param = param.get_ref();
+        let mut selectors = HashMap::new();
+        for (id_str, row) in rows {
+            let selector = match id_str.get_ref().parse::<RowSelector>() {
+                Ok(selector) => selector,
+                Err(e) => {
+                    diagnostics.push(src.at(id_str, format!("{param}: {e}")));
+                    continue;
+                },
+            };
+            if let Some(prev) = selectors.insert(selector, id_str.get_ref()) {
+                let message = format!("{param}: row ID {} is the same as {prev}", id_str.get_ref());
+                diagnostics.push(src.at(id_str, message));
+            }
+
+            for (field, value) in row {
+                for (name, value) in
+                    array_elements(field.get_ref().clone(), value.get_ref().clone())
+                {
+                    let location = format!("{param}[{selector}].{name}");
+                    let messages = match fields.get(name.as_str()) {
+                        None => vec![format!("{location}: unknown field")],
+                        Some(info) if info.padding => {
+                            vec![format!("{location}: padding can't be set")]
+                        },
+                        Some(info) => match &value {
+                            toml::Value::String(s) => match s.parse::<PatchValue>() {
+                                Ok(PatchValue::Expr(expr)) => expr
+                                    .fields()
+                                    .into_iter()
+                                    .filter(|f| fields.get(f).is_none_or(|info| info.padding))
+                                    .map(|f| format!("{location}: unknown field {f} in {s:?}"))
+                                    .collect(),
+                                Ok(_) => match PARAM_ENUMS.value(param, &name, s) {
+                                    Ok(_) => Vec::new(),
+                                    Err(e) => vec![format!("{location}: {e}")],
+                                },
+                                Err(e) => vec![format!("{location}: {e}")],
+                            },
+                            value => match info.ty.check(value) {
+                                Ok(()) => Vec::new(),
+                                Err(e) => vec![format!("{location}: {e}")],
+                            },
+                        },
+                    };
+                    diagnostics.extend(messages.into_iter().map(|m| src.at(field, m)));
+                }
+            }
+        }
+    }
+
+    diagnostics.sort();
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        validate_patch(source).iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn test_shipped_config_is_valid() {
+        assert_eq!(
+            messages(include_str!("../../../param-mod/param-mod.toml")),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let source = r#"
+[ItemLotParam.11700000]
+lot_item_base_point01 = 0
+lot_item_base_point02 = 100000
+lot_item_base_pointXX = 1
+
+[EquipParamWeapon.2000000]
+weight = 1
+sort_id = 1.5
+
+[EquipParamWeapon.02000000]
+weight = 2.0
+
+[ItemLotParam.abc]
+lot_item_base_point01 = 0
+
+[NotAParam.1]
+foo = 1
+
+[NpcThinkParam.1]
+enable_navi_flag_reserve = [1, 2, 300, 4]
+"enable_navi_flag_reserve[1]" = 5
+
+[GemGenParam.1]
+pad = [0, 0]
+
+[ItemLotParam."1..10"]
+lot_item_base_point01 = "*1.5"
+lot_item_base_point02 = "=lot_item_base_point01 + nope"
+lot_item_base_point03 = "1.5"
+
+[ItemLotParam."*"]
+lot_item_base_point04 = "=(original"
+
+[ItemLotParam."10..1"]
+lot_item_base_point01 = 0
+"#;
+
+        assert_eq!(messages(source), vec![
+            "4:1: ItemLotParam[11700000].lot_item_base_point02: value 100000 out of range for i16 \
+             (-32768..=32767)",
+            "5:1: ItemLotParam[11700000].lot_item_base_pointXX: unknown field",
+            "9:1: EquipParamWeapon[2000000].sort_id: expected i32, found float",
+            "11:19: EquipParamWeapon: row ID 02000000 is the same as 2000000",
+            "14:15: ItemLotParam: row ID abc is not a non-negative integer, a range like 10..20 \
+             or *",
+            "17:2: Unknown param NotAParam",
+            "21:1: NpcThinkParam[1].enable_navi_flag_reserve[2]: value 300 out of range for u8 \
+             (0..=255)",
+            "21:1: NpcThinkParam[1].enable_navi_flag_reserve[3]: unknown field",
+            "25:1: GemGenParam[1].pad[0]: padding can't be set",
+            "25:1: GemGenParam[1].pad[1]: padding can't be set",
+            "29:1: ItemLotParam[1..10].lot_item_base_point02: unknown field nope in \
+             \"=lot_item_base_point01 + nope\"",
+            "30:1: ItemLotParam[1..10].lot_item_base_point03: \"1.5\" is not an expression, and \
+             lot_item_base_point03 has no named values",
+            "33:1: ItemLotParam[*].lot_item_base_point04: missing ) in \"(original\"",
+            "35:15: ItemLotParam: row ID 10..1 is not a non-negative integer, a range like 10..20 \
+             or *",
+        ]);
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let diagnostics = validate_patch("[ItemLotParam.1]\nfoo = \n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+    }
+}
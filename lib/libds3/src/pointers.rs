@@ -5,8 +5,8 @@ use log::debug;
 use windows::Win32::System::LibraryLoader::GetModuleHandleA;
 
 use crate::memedit::*;
+use crate::offsets::StructOffsets;
 use crate::prelude::base_addresses::BaseAddresses;
-use crate::prelude::{Version, VERSION};
 
 // Character stats
 //
@@ -87,6 +87,7 @@ pub struct PointerChains {
 
     #[allow(unused)]
     pub world_chr_man: usize,
+    pub base_addresses: BaseAddresses,
 }
 
 impl From<BaseAddresses> for PointerChains {
@@ -115,167 +116,17 @@ impl From<BaseAddresses> for PointerChains {
 
         let offs_all_no_damage = 9;
         let offs_player_exterminate = 1;
-        let offs_no_goods_consume = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1 => 0x1ECA,
-
-            Version::V1_06_0
-            | Version::V1_07_0
-            | Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0 => 0x1EDA,
-
-            Version::V1_12_0 | Version::V1_13_0 | Version::V1_14_0 => 0x1EE2,
-
-            Version::V1_15_0 | Version::V1_15_1 | Version::V1_15_2 => 0x1EEA,
-        };
-        let offs_deathcam = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0
-            | Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0 => 0x88,
-
-            Version::V1_12_0
-            | Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0x90,
-        };
-        let offs_bloodstain_draw = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3 => 0x2155,
-
-            Version::V1_05_0 | Version::V1_05_1 | Version::V1_06_0 | Version::V1_07_0 => 0x2165,
-
-            Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0 => 0x2185,
-
-            Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0x2195,
-        };
-        let offs_speed = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0
-            | Version::V1_08_0 => 0xa38,
-
-            Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0
-            | Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0xa58,
-        };
-        let offs_igt = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0 => 0x9c,
-
-            Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0
-            | Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0xa4,
-        };
+        let StructOffsets {
+            no_goods_consume: offs_no_goods_consume,
+            deathcam: offs_deathcam,
+            bloodstain_draw: offs_bloodstain_draw,
+            speed: offs_speed,
+            igt: offs_igt,
+            debug_draw: offs_debug_draw,
+            ik_foot_ray: offs_ik_foot_ray,
+            anim: offs_anim,
+        } = StructOffsets::current();
         let offs_fps = 0x08;
-        let offs_debug_draw = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0 => 0x55,
-
-            Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0
-            | Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0x65,
-        };
-
-        let offs_ik_foot_ray = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3
-            | Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0 => 0x5B,
-
-            Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0
-            | Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0x6B,
-        };
-
         let offs_no_update_ai = 0xD;
         let mesh_hi = 0xEC;
         let mesh_lo = 0xED;
@@ -283,31 +134,6 @@ impl From<BaseAddresses> for PointerChains {
         let mesh_hit = 0xF1;
         let mouse_enable_offs = 0x54;
 
-        let offs_anim = match *VERSION {
-            Version::V1_01_1
-            | Version::V1_03_1
-            | Version::V1_03_2
-            | Version::V1_04_1
-            | Version::V1_04_2
-            | Version::V1_04_3 => 0x1F70,
-
-            Version::V1_05_0
-            | Version::V1_05_1
-            | Version::V1_06_0
-            | Version::V1_07_0
-            | Version::V1_08_0
-            | Version::V1_09_0
-            | Version::V1_10_0
-            | Version::V1_11_0
-            | Version::V1_12_0 => 0x1F80,
-
-            Version::V1_13_0
-            | Version::V1_14_0
-            | Version::V1_15_0
-            | Version::V1_15_1
-            | Version::V1_15_2 => 0x1F90,
-        };
-
         PointerChains {
             all_no_damage: bitflag!(0b1; debug + offs_all_no_damage as usize),
             no_death: bitflag!(0b100; world_chr_man, 0x80, xa as _, 0x18, 0x1c0),
@@ -315,7 +141,7 @@ impl From<BaseAddresses> for PointerChains {
             inf_stamina: bitflag!(0b10000; world_chr_man, 0x80, xa as _, 0x18, 0x1c0),
             inf_focus: bitflag!(0b100000; world_chr_man, 0x80, xa as _, 0x18, 0x1c0),
             inf_consumables: bitflag!(0b1000; world_chr_man, 0x80, offs_no_goods_consume as _),
-            deathcam: bitflag!(0b1; world_chr_man, offs_deathcam as usize),
+            deathcam: bitflag!(0b1; world_chr_man, offs_deathcam),
             evt_draw: bitflag!(0b1; sprj_debug_event, 0xa8),
             bloodstain_draw: bitflag!(0b1; world_chr_man, 0x40, 0x0, offs_bloodstain_draw as _),
             evt_disable: bitflag!(0b1; sprj_debug_event, 0xd4),
@@ -359,6 +185,7 @@ impl From<BaseAddresses> for PointerChains {
             current_target: pointer_chain!(current_target),
             no_logo: pointer_chain!(no_logo as _),
             xa: xa as u32,
+            base_addresses: b,
         }
     }
 }
@@ -372,9 +199,16 @@ impl Default for PointerChains {
 impl PointerChains {
     pub fn new() -> Self {
         let base_module_address = unsafe { GetModuleHandleA(None) }.unwrap().0 as usize;
-        let base_addresses = BaseAddresses::from(*crate::version::VERSION)
-            .with_module_base_addr(base_module_address);
+        let base_addresses =
+            crate::version::base_addresses().with_module_base_addr(base_module_address);
 
         base_addresses.into()
     }
+
+    /// Pointer chain starting from a named base address, for the flags and
+    /// values defined in the config file.
+    pub fn custom_chain<T>(&self, base: &str, offsets: &[usize]) -> Option<PointerChain<T>> {
+        let base = self.base_addresses.get(base)?;
+        Some(PointerChain::new(&[&[base], offsets].concat()))
+    }
 }
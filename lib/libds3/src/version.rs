@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::ptr::null_mut;
 
 use log::*;
@@ -10,11 +11,91 @@ use windows::Win32::Storage::FileSystem::{
 };
 use windows::Win32::System::LibraryLoader::{GetModuleFileNameW, GetModuleHandleW};
 
+use crate::aob::{self, Section, BASE_ADDRESSES_AOBS};
+use crate::prelude::base_addresses::BaseAddresses;
 pub use crate::prelude::base_addresses::Version;
 
-pub static VERSION: Lazy<Version> = Lazy::new(get_version);
+/// Version of the running executable, as reported by its version info.
+pub static EXE_VERSION: Lazy<(u32, u32, u32)> = Lazy::new(get_exe_version);
 
-fn get_version() -> Version {
+/// Version to use for struct offsets. If the executable is not a known
+/// version, this is the latest known one.
+pub static VERSION: Lazy<Version> = Lazy::new(|| {
+    known_version(*EXE_VERSION).unwrap_or_else(|| {
+        let (maj, min, patch) = *EXE_VERSION;
+        warn!("Unrecognized version {maj}.{min:02}.{patch}, falling back to the latest known one");
+        KNOWN_VERSIONS[KNOWN_VERSIONS.len() - 1]
+    })
+});
+
+/// Whether the running executable is a known version, i.e. whether base
+/// addresses come from the codegen rather than from a signature scan.
+pub static IS_KNOWN_VERSION: Lazy<bool> = Lazy::new(|| known_version(*EXE_VERSION).is_some());
+
+static SCANNED_BASE_ADDRESSES: Lazy<Result<HashMap<&'static str, usize>, String>> =
+    Lazy::new(|| {
+        let (maj, min, patch) = *EXE_VERSION;
+        info!("Scanning for base addresses of version {maj}.{min:02}.{patch}");
+        let found = unsafe { scan_main_module() };
+        match &found {
+            Ok(found) => info!("Found {} base addresses", found.len()),
+            Err(e) => error!("{e}"),
+        }
+        found
+    });
+
+/// All the versions with generated base addresses, oldest first.
+pub const KNOWN_VERSIONS: &[Version] = crate::prelude::base_addresses::VERSIONS;
+
+/// Looks up a version tuple without panicking on unknown versions.
+pub fn known_version(v: (u32, u32, u32)) -> Option<Version> {
+    KNOWN_VERSIONS.iter().copied().find(|&known| <(u32, u32, u32)>::from(known) == v)
+}
+
+/// Base addresses of the running executable, relative to the module base.
+/// Known versions use the generated tables, unknown ones are scanned for.
+///
+/// # Panics
+///
+/// If the executable is an unknown version and some signatures fail to match.
+pub fn base_addresses() -> BaseAddresses {
+    if *IS_KNOWN_VERSION {
+        return BaseAddresses::from(*VERSION);
+    }
+
+    SCANNED_BASE_ADDRESSES
+        .as_ref()
+        .map_err(String::clone)
+        .and_then(aob::base_addresses_from_scan)
+        .unwrap_or_else(|e| panic!("Couldn't find base addresses: {e}"))
+}
+
+/// # Safety
+///
+/// Reads the headers and sections of the main module, which stay mapped for
+/// the lifetime of the process.
+unsafe fn scan_main_module() -> Result<HashMap<&'static str, usize>, String> {
+    let image_base = GetModuleHandleW(None).map_err(|e| e.to_string())?.0 as usize;
+    let headers = std::slice::from_raw_parts(image_base as *const u8, 0x1000);
+
+    let section_headers = aob::parse_section_headers(headers)?;
+    let sections = section_headers
+        .iter()
+        .map(|s| Section {
+            name: &s.name,
+            address: image_base + s.virtual_address,
+            bytes: std::slice::from_raw_parts(
+                (image_base + s.virtual_address) as *const u8,
+                s.virtual_size,
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    aob::scan_all(BASE_ADDRESSES_AOBS, &sections, image_base)
+        .map_err(|missing| format!("Signatures not found: {}", missing.join(", ")))
+}
+
+fn get_exe_version() -> (u32, u32, u32) {
     let file_path = {
         let mut buf = vec![0u16; MAX_PATH as usize];
         unsafe { GetModuleFileNameW(GetModuleHandleW(None).unwrap(), &mut buf) };
@@ -49,5 +130,5 @@ fn get_version() -> Version {
     let patch = (version_info.dwFileVersionLS >> 16) & 0xffff;
 
     info!("Version {} {} {}", major, minor, patch);
-    Version::from((major, minor, patch))
+    (major, minor, patch)
 }
@@ -3,10 +3,33 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::*;
 
-#[proc_macro_derive(ParamStruct, attributes(bitflag))]
+#[proc_macro_derive(ParamStruct, attributes(bitflag, padding, param))]
 pub fn macro_param(t: TokenStream) -> TokenStream {
     let input = parse_macro_input!(t as DeriveInput);
     let name = input.ident;
+
+    let size_assertion = input
+        .attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(meta_list)) if meta_list.path.is_ident("param") => Some(meta_list),
+            _ => None,
+        })
+        .flat_map(|meta_list| meta_list.nested)
+        .map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path, lit: Lit::Int(size), ..
+            })) if path.is_ident("size") => {
+                quote! {
+                    const _: () = assert!(
+                        ::std::mem::size_of::<#name>() == #size,
+                        concat!("Size of ", stringify!(#name), " differs from its param definition")
+                    );
+                }
+            },
+            other => panic!("Wrong param attribute: {:#?}", other),
+        })
+        .collect::<Vec<_>>();
     let fields_punct = match input.data {
         Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => fields.named,
         _ => panic!("Only structs with named fields can be annotated"),
@@ -20,6 +43,7 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
                 field
                     .attrs
                     .iter()
+                    .filter(|attr| !attr.path.is_ident("padding"))
                     .map(|attr| {
                         let meta_list = match attr.parse_meta() {
                             Ok(Meta::List(meta_list)) if meta_list.path.is_ident("bitflag") => {
@@ -79,52 +103,131 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
     });
 
     let field_visit = fields_with_bitfields
+        .iter()
+        .map(|(field, bitfield_spec)| {
+            let visit = match field {
+                &Field { ident: Some(ident), ty: Type::Path(TypePath { path, .. }), .. } => {
+                    let ty_ident = path.segments[0].ident.to_string();
+                    match ty_ident.as_str() {
+                        "u8" if !bitfield_spec.is_empty() => {
+                            let bitfield_visit = bitfield_spec.iter().map(
+                                |(bitfield_name, _, set_bitfield, get_bitfield)| {
+                                    quote! {
+                                        let mut b = self.#get_bitfield();
+                                        t.visit_bool(stringify!(#bitfield_name), &mut b);
+                                        self.#set_bitfield(b);
+                                    }
+                                },
+                            );
+
+                            quote! {
+                                #(#bitfield_visit)*
+                            }
+                        },
+                        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" => {
+                            let ident = format_ident!("{}", ident);
+                            let visit_ty = format_ident!("visit_{}", ty_ident);
+                            quote! {
+                                t.#visit_ty(stringify!(#ident), &mut self.#ident);
+                            }
+                        },
+                        other => panic!("Unrecognized type {:#?}", other),
+                    }
+                },
+                &Field { ident: Some(ident), ty: Type::Array(_), .. } => {
+                    let (elem_ty, _) = array_type(field);
+                    let visit_ty = format_ident!("visit_{}", elem_ty);
+                    quote! {
+                        for (i, v) in self.#ident.iter_mut().enumerate() {
+                            t.#visit_ty(&format!("{}[{}]", stringify!(#ident), i), v);
+                        }
+                    }
+                },
+                field => {
+                    panic!("Unrecognized field {:#?}", field);
+                },
+            };
+
+            if is_padding(field) {
+                quote! {
+                    if t.visit_padding() {
+                        #visit
+                    }
+                }
+            } else {
+                visit
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let field_infos = fields_with_bitfields
         .iter()
         .filter_map(|(field, bitfield_spec)| match field {
             &Field { ident: Some(ident), ty: Type::Path(TypePath { path, .. }), .. } => {
                 let ty_ident = path.segments[0].ident.to_string();
                 match ty_ident.as_str() {
                     "u8" if !bitfield_spec.is_empty() => {
-                        let bitfield_visit = bitfield_spec.iter().map(
-                            |(bitfield_name, _, set_bitfield, get_bitfield)| {
+                        let bitfield_infos =
+                            bitfield_spec.iter().map(|(bitfield_name, field_idx, ..)| {
                                 quote! {
-                                    let mut b = self.#get_bitfield();
-                                    t.visit_bool(stringify!(#bitfield_name), &mut b);
-                                    self.#set_bitfield(b);
+                                    FieldInfo {
+                                        name: stringify!(#bitfield_name),
+                                        ty: FieldType::Bool,
+                                        offset: ::std::mem::offset_of!(#name, #ident),
+                                        size: 1,
+                                        bit: Some(#field_idx),
+                                        padding: false,
+                                    },
                                 }
-                            },
-                        );
+                            });
 
                         Some(quote! {
-                            #(#bitfield_visit)*
+                            #(#bitfield_infos)*
                         })
                     },
                     "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" => {
-                        let ident = format_ident!("{}", ident);
-                        let visit_ty = format_ident!("visit_{}", ty_ident);
+                        let ty = format_ident!("{}", ty_ident);
+                        let field_ty = format_ident!("{}", ty_ident.to_uppercase());
+                        let padding = is_padding(field);
                         Some(quote! {
-                            t.#visit_ty(stringify!(#ident), &mut self.#ident);
+                            FieldInfo {
+                                name: stringify!(#ident),
+                                ty: FieldType::#field_ty,
+                                offset: ::std::mem::offset_of!(#name, #ident),
+                                size: ::std::mem::size_of::<#ty>(),
+                                bit: None,
+                                padding: #padding,
+                            },
                         })
                     },
                     other => panic!("Unrecognized type {:#?}", other),
                 }
             },
-            &Field {
-                ident: Some(_),
-                ty:
-                    Type::Array(TypeArray {
-                        elem: _,
-                        len: Expr::Lit(ExprLit { lit: Lit::Int(_), .. }),
-                        ..
-                    }),
-                ..
-            } => {
-                // Just ignore array fields
-                None
-            },
-            field => {
-                panic!("Unrecognized field {:#?}", field);
+            field @ &Field { ident: Some(ident), ty: Type::Array(_), .. } => {
+                let (elem_ty, len) = array_type(field);
+                let ty = format_ident!("{}", elem_ty);
+                let field_ty = format_ident!("{}", elem_ty.to_uppercase());
+                let padding = is_padding(field);
+                let element_infos = (0..len).map(|i| {
+                    let element_name = format!("{ident}[{i}]");
+                    quote! {
+                        FieldInfo {
+                            name: #element_name,
+                            ty: FieldType::#field_ty,
+                            offset: ::std::mem::offset_of!(#name, #ident)
+                                + #i * ::std::mem::size_of::<#ty>(),
+                            size: ::std::mem::size_of::<#ty>(),
+                            bit: None,
+                            padding: #padding,
+                        },
+                    }
+                });
+
+                Some(quote! {
+                    #(#element_infos)*
+                })
             },
+            _ => None,
         })
         .collect::<Vec<_>>();
 
@@ -141,9 +244,13 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
         }
 
         impl ParamStruct for #name {
+            const FIELDS: &'static [FieldInfo] = &[#(#field_infos)*];
+
             #visit
         }
 
+        #(#size_assertion)*
+
         impl Params {
             pub unsafe fn #get_name_snake_case(&self) -> Option<impl Iterator<Item = Param<#name>>> {
                 self.iter_param::<#name>(stringify!(#name))
@@ -152,3 +259,27 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+fn is_padding(field: &Field) -> bool {
+    field.attrs.iter().any(|attr| attr.path.is_ident("padding"))
+}
+
+/// Element type and length of an array field.
+fn array_type(field: &Field) -> (String, usize) {
+    match &field.ty {
+        Type::Array(TypeArray {
+            elem, len: Expr::Lit(ExprLit { lit: Lit::Int(len), .. }), ..
+        }) => {
+            let elem_ty = match elem.as_ref() {
+                Type::Path(TypePath { path, .. }) => path.segments[0].ident.to_string(),
+                other => panic!("Unrecognized array element type {:#?}", other),
+            };
+            match elem_ty.as_str() {
+                "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" => {},
+                other => panic!("Unrecognized array element type {:#?}", other),
+            }
+            (elem_ty, len.base10_parse().unwrap())
+        },
+        other => panic!("Unrecognized array field {:#?}", other),
+    }
+}
@@ -1,10 +1,10 @@
-use std::collections::HashMap;
 use std::ffi::c_void;
+use std::fs;
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
 
 use libds3::prelude::*;
 use once_cell::sync::Lazy;
-use serde::{de, Deserialize, Deserializer};
-use toml::Value;
 use windows::core::{GUID, HRESULT, PCSTR};
 use windows::Win32::Foundation::{BOOL, HINSTANCE};
 use windows::Win32::System::Console::AllocConsole;
@@ -19,6 +19,9 @@ type FDirectInput8Create = unsafe extern "stdcall" fn(
     punkouter: HINSTANCE,
 ) -> HRESULT;
 
+const CONFIG_PATH: &str = "param-mod.toml";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 struct State {
     directinput8create: FDirectInput8Create,
 }
@@ -52,153 +55,70 @@ fn no_logo() {
         .unwrap();
 }
 
-#[derive(Deserialize)]
-#[serde(transparent)]
-struct PatchConfig(HashMap<String, ParamIdConfig>);
+fn read_config() -> Result<PatchConfig, String> {
+    let source =
+        fs::read_to_string(CONFIG_PATH).map_err(|e| format!("Couldn't read {CONFIG_PATH}: {e}"))?;
 
-impl PatchConfig {
-    fn apply(self, params: &Params) {
-        for (param_name, param_id_cfg) in self.0 {
-            println!("Applying to {param_name}");
-            param_id_cfg.apply(&param_name, params);
-        }
+    for diagnostic in validate_patch(&source) {
+        eprintln!("{CONFIG_PATH}:{diagnostic}");
     }
-}
 
-#[derive(Deserialize)]
-#[serde(transparent)]
-struct ParamIdConfig(
-    #[serde(deserialize_with = "deserialize_param_id_config_kv")] HashMap<u64, ParamValues>,
-);
-
-impl ParamIdConfig {
-    fn apply(mut self, param_name: &str, params: &Params) {
-        let idx_map = match unsafe { params.iter_param_ids(param_name) } {
-            Some(it) => {
-                it.enumerate().filter(|(_, id)| self.0.contains_key(id as _)).collect::<Vec<_>>()
-            },
-            None => {
-                eprintln!("  Couldn't iter param ids: {param_name}");
-                return;
-            },
-        };
-
-        for (param_idx, id) in idx_map {
-            println!("  Visiting {param_name}:{param_idx}");
-            params.visit_param_item(param_name, param_idx, self.0.get_mut(&id).unwrap());
-        }
-    }
+    source.parse().map_err(|e| format!("Couldn't parse {CONFIG_PATH}: {e}"))
 }
 
-fn deserialize_param_id_config_kv<'de, D>(
-    deserializer: D,
-) -> Result<HashMap<u64, ParamValues>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let str_map = HashMap::<&str, ParamValues>::deserialize(deserializer)?;
-    let original_len = str_map.len();
-    let data = {
-        str_map
-            .into_iter()
-            .map(|(str_key, value)| match str_key.parse() {
-                Ok(int_key) => Ok((int_key, value)),
-                Err(_) => Err({
-                    de::Error::invalid_value(
-                        de::Unexpected::Str(str_key),
-                        &"a non-negative integer",
-                    )
-                }),
-            })
-            .collect::<Result<HashMap<_, _>, _>>()?
-    };
-    // multiple strings could parse to the same int, e.g "0" and "00"
-    if data.len() < original_len {
-        return Err(de::Error::custom("detected duplicate integer key"));
-    }
-    Ok(data)
+fn config_modified() -> Option<SystemTime> {
+    fs::metadata(CONFIG_PATH).and_then(|m| m.modified()).ok()
 }
 
-#[derive(Deserialize)]
-#[serde(transparent)]
-struct ParamValues(HashMap<String, Value>);
-
-impl ParamVisitor for ParamValues {
-    fn visit_u8(&mut self, name: &str, v: &mut u8) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
-
-    fn visit_u16(&mut self, name: &str, v: &mut u16) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
-
-    fn visit_u32(&mut self, name: &str, v: &mut u32) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
-
-    fn visit_i8(&mut self, name: &str, v: &mut i8) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
+fn apply_config(state: &mut PatchState) {
+    let config = match read_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        },
+    };
 
-    fn visit_i16(&mut self, name: &str, v: &mut i16) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
+    let report = PARAMS.write().apply_patch(state, config);
 
-    fn visit_i32(&mut self, name: &str, v: &mut i32) {
-        if let Some(Value::Integer(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
+    println!("Applied {CONFIG_PATH}: {} changes", report.changes.len());
+    for change in &report.changes {
+        println!("  {change}");
     }
-
-    fn visit_f32(&mut self, name: &str, v: &mut f32) {
-        if let Some(Value::Float(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i as _;
-        }
-    }
-
-    fn visit_bool(&mut self, name: &str, v: &mut bool) {
-        if let Some(Value::Boolean(i)) = self.0.get(name) {
-            println!("    Applied {name} := {i}");
-            *v = *i;
-        }
+    for error in &report.errors {
+        eprintln!("  Error: {error}");
     }
 }
 
 unsafe fn patch() {
-    no_logo();
-    std::thread::spawn(|| {
-        let mut params = PARAMS.write();
-
-        let patch_config: PatchConfig =
-            toml::from_str(&std::fs::read_to_string("param-mod.toml").unwrap()).unwrap();
-
-        drop(wait_option(|| {
-            if let Err(e) = params.refresh() {
-                eprintln!("Error: {:?}", e);
+    static PATCH: Once = Once::new();
+
+    PATCH.call_once(|| {
+        no_logo();
+        std::thread::spawn(|| {
+            drop(wait_option(|| {
+                let mut params = PARAMS.write();
+                if let Err(e) = params.refresh() {
+                    eprintln!("Error: {:?}", e);
+                }
+                params.get_equip_param_weapon()
+            }));
+
+            let mut state = PatchState::new();
+            let mut last_modified = config_modified();
+            apply_config(&mut state);
+
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+
+                let modified = config_modified();
+                if modified != last_modified {
+                    last_modified = modified;
+                    println!("{CONFIG_PATH} changed, reloading");
+                    apply_config(&mut state);
+                }
             }
-            params.get_equip_param_weapon()
-        }));
-
-        patch_config.apply(&params);
-
-        println!("Done");
+        });
     });
 }
 
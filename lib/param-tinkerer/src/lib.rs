@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::fs;
 
 use hudhook::hooks::dx11::ImguiDx11Hooks;
 use hudhook::ImguiRenderLoop;
@@ -10,8 +13,28 @@ struct ParamTinkerer {
     shown: bool,
     selected_param: usize,
     selected_param_id: usize,
+    snapshot: ParamSnapshot,
+    show_padding: bool,
+    param_filter: String,
+    row_filter: String,
+    find_field: String,
+    find_value: String,
+    results: Vec<SearchResult>,
+    clipboard: RowClipboard,
+    clipboard_idx: usize,
+    paste_fields: HashSet<String>,
 }
 
+/// A row found by "Find value" or "Find references".
+struct SearchResult {
+    param: String,
+    id: u64,
+    label: String,
+}
+
+const CHANGED_COLOR: [f32; 4] = [1., 0.75, 0.25, 1.];
+const CLIPBOARD_SIZE: usize = 16;
+
 impl ParamTinkerer {
     fn new() -> Self {
         println!("Initializing");
@@ -21,6 +44,16 @@ impl ParamTinkerer {
             shown: false,
             selected_param: 0,
             selected_param_id: 0,
+            snapshot: ParamSnapshot::new(),
+            show_padding: false,
+            param_filter: String::new(),
+            row_filter: String::new(),
+            find_field: String::new(),
+            find_value: String::new(),
+            results: Vec::new(),
+            clipboard: RowClipboard::new(CLIPBOARD_SIZE),
+            clipboard_idx: 0,
+            paste_fields: HashSet::new(),
             pointers: PointerChains::new(),
         }
     }
@@ -28,7 +61,7 @@ impl ParamTinkerer {
 
 impl ImguiRenderLoop for ParamTinkerer {
     fn render(&mut self, ui: &mut imgui::Ui) {
-        if ui.is_key_index_released(0x50) {
+        if !ui.io().want_text_input && ui.is_key_index_released(0x50) {
             // P key
             self.shown = !self.shown;
             self.pointers.cursor_show.set(self.shown);
@@ -53,6 +86,9 @@ impl ImguiRenderLoop for ParamTinkerer {
                     [ui.push_style_color(imgui::StyleColor::ModalWindowDimBg, [0., 0., 0., 0.])];
 
                 self.render_params(ui);
+                self.render_actions(ui);
+                self.render_clipboard(ui);
+                self.render_search(ui);
 
                 style_tokens.into_iter().rev().for_each(|t| t.pop());
             });
@@ -60,6 +96,258 @@ impl ImguiRenderLoop for ParamTinkerer {
 }
 
 impl ParamTinkerer {
+    pub fn render_actions(&mut self, ui: &imgui::Ui) {
+        let params = PARAMS.read();
+        let Some(param_name) = params.keys().nth(self.selected_param) else {
+            return;
+        };
+
+        for (label, ext) in [("Export JSON", "json"), ("Export CSV", "csv")] {
+            if ui.button(label) {
+                match export_param(&params, param_name, ext) {
+                    Ok(path) => println!("Exported {param_name} to {path}"),
+                    Err(e) => println!("{e}"),
+                }
+            }
+            ui.same_line();
+        }
+
+        for (label, ext) in [("Import JSON", "json"), ("Import CSV", "csv")] {
+            if ui.button(label) {
+                params.snapshot_param(&mut self.snapshot, param_name);
+                match import_param(&params, param_name, ext) {
+                    Ok(errors) => {
+                        println!("Imported {param_name} ({} errors)", errors.len());
+                        errors.iter().for_each(|e| println!("  {e}"));
+                    },
+                    Err(e) => println!("{e}"),
+                }
+            }
+            ui.same_line();
+        }
+
+        let row_id = unsafe { params.iter_param_ids(param_name) }
+            .and_then(|mut ids| ids.nth(self.selected_param_id));
+        let revert_row = ui.button("Revert row");
+        ui.same_line();
+        let revert_param = ui.button("Revert param");
+        ui.same_line();
+        ui.checkbox("Show padding", &mut self.show_padding);
+
+        let scope = if revert_row {
+            row_id.map(RevertScope::Row)
+        } else if revert_param {
+            Some(RevertScope::Table)
+        } else {
+            None
+        };
+
+        if let Some(scope) = scope {
+            match params.revert_param(&self.snapshot, param_name, scope) {
+                Ok(errors) => errors.iter().for_each(|e| println!("{e}")),
+                Err(e) => println!("{e}"),
+            }
+        }
+    }
+
+    pub fn render_clipboard(&mut self, ui: &imgui::Ui) {
+        let params = PARAMS.read();
+        let Some(param_name) = params.keys().nth(self.selected_param) else {
+            return;
+        };
+
+        if ui.button("Copy row") {
+            match params.copy_row(param_name, self.selected_param_id) {
+                Some(copied) => {
+                    println!("Copied {param_name}[{}]", copied.row.id);
+                    self.paste_fields = copied.row.fields.iter().map(|(k, _)| k.clone()).collect();
+                    self.clipboard.push(copied);
+                    self.clipboard_idx = 0;
+                },
+                None => println!("{param_name}: no row selected"),
+            }
+        }
+
+        if self.clipboard.is_empty() {
+            return;
+        }
+
+        ui.same_line();
+        ui.set_next_item_width(300.);
+        let labels = self
+            .clipboard
+            .rows()
+            .map(|copied| match &copied.row.name {
+                Some(name) => format!("{}[{}] {name}", copied.param, copied.row.id),
+                None => format!("{}[{}]", copied.param, copied.row.id),
+            })
+            .collect::<Vec<_>>();
+        if ui.combo_simple_string("##clipboard", &mut self.clipboard_idx, &labels) {
+            if let Some(copied) = self.clipboard.get(self.clipboard_idx) {
+                self.paste_fields = copied.row.fields.iter().map(|(k, _)| k.clone()).collect();
+            }
+        }
+
+        let Some(copied) = self.clipboard.get(self.clipboard_idx) else {
+            return;
+        };
+
+        ui.same_line();
+        let fields_label = format!(
+            "Fields ({}/{})##paste_fields",
+            self.paste_fields.len(),
+            copied.row.fields.len()
+        );
+        if ui.button(fields_label) {
+            ui.open_popup("##paste_fields_popup");
+        }
+        ui.popup("##paste_fields_popup", || {
+            if ui.button("All") {
+                self.paste_fields = copied.row.fields.iter().map(|(k, _)| k.clone()).collect();
+            }
+            ui.same_line();
+            if ui.button("None") {
+                self.paste_fields.clear();
+            }
+
+            ui.child_window("##paste_fields_list").size([300., 400.]).build(|| {
+                for (field, value) in &copied.row.fields {
+                    let mut checked = self.paste_fields.contains(field);
+                    if ui.checkbox(format!("{field} = {value}"), &mut checked) {
+                        if checked {
+                            self.paste_fields.insert(field.clone());
+                        } else {
+                            self.paste_fields.remove(field);
+                        }
+                    }
+                }
+            });
+        });
+
+        ui.same_line();
+        if ui.button("Paste row") {
+            let row_id = unsafe { params.iter_param_ids(param_name) }
+                .and_then(|mut ids| ids.nth(self.selected_param_id));
+            if let Some(row_id) = row_id {
+                params.snapshot_param(&mut self.snapshot, param_name);
+                match params.paste_row(copied, param_name, row_id, Some(&self.paste_fields)) {
+                    Ok(errors) => {
+                        println!(
+                            "Pasted {} fields of {}[{}] into {param_name}[{row_id}]",
+                            self.paste_fields.len(),
+                            copied.param,
+                            copied.row.id
+                        );
+                        errors.iter().for_each(|e| println!("  {e}"));
+                    },
+                    Err(e) => println!("{e}"),
+                }
+            }
+        }
+
+        ui.same_line();
+        if ui.button("Copy TOML") {
+            let toml = copied.to_toml(Some(&self.paste_fields));
+            ui.set_clipboard_text(&toml);
+            println!("{toml}");
+        }
+    }
+
+    pub fn render_search(&mut self, ui: &imgui::Ui) {
+        let params = PARAMS.read();
+        let Some(param_name) = params.keys().nth(self.selected_param) else {
+            return;
+        };
+
+        ui.set_next_item_width(200.);
+        ui.input_text("##find_field", &mut self.find_field).hint("Field").build();
+        ui.same_line();
+        ui.set_next_item_width(200.);
+        let enter = ui
+            .input_text("##find_value", &mut self.find_value)
+            .hint("Value, or range like 10..20")
+            .enter_returns_true(true)
+            .build();
+        ui.same_line();
+
+        if ui.button("Find value") || enter {
+            match self.find_value.parse::<ValueQuery>() {
+                Ok(query) => {
+                    let found = params
+                        .export_param(param_name)
+                        .map(|table| find_values(&table, &self.find_field, &query))
+                        .unwrap_or_default();
+                    println!("Found {} fields of {param_name} matching {query}", found.len());
+                    self.results = found
+                        .into_iter()
+                        .map(|m| {
+                            let name = m.name.map(|name| format!(" - {name}")).unwrap_or_default();
+                            SearchResult {
+                                param: param_name.to_string(),
+                                id: m.id,
+                                label: format!("{}{name}: {} = {}", m.id, m.field, m.value),
+                            }
+                        })
+                        .collect();
+                },
+                Err(e) => println!("{e}"),
+            }
+        }
+
+        ui.same_line();
+        let row_id = unsafe { params.iter_param_ids(param_name) }
+            .and_then(|mut ids| ids.nth(self.selected_param_id));
+        if let (true, Some(row_id)) = (ui.button("Find references"), row_id) {
+            let references = params.references_to(param_name, row_id);
+            println!("Found {} references to {param_name}[{row_id}]", references.len());
+            self.results = references
+                .into_iter()
+                .map(|r| SearchResult {
+                    label: format!("{}[{}].{} = {row_id}", r.param, r.id, r.field),
+                    param: r.param,
+                    id: r.id,
+                })
+                .collect();
+        }
+
+        if self.results.is_empty() {
+            return;
+        }
+
+        let mut selected = None;
+        ListBox::new("##search_results").size([1200., 150.]).build(ui, || {
+            for (i, result) in self.results.iter().enumerate() {
+                if ui.selectable(format!("{}##result{i}", result.label)) {
+                    selected = Some((result.param.clone(), result.id));
+                }
+            }
+        });
+
+        if let Some((param, id)) = selected {
+            self.select_row(&params, &param, id);
+        }
+    }
+
+    /// Selects the row with the given ID, clearing the filters that would
+    /// hide it.
+    fn select_row(&mut self, params: &Params, param: &str, id: u64) {
+        let Some(param_idx) = params.keys().position(|k| k == param) else {
+            println!("Unknown param {param}");
+            return;
+        };
+        let Some(row_idx) =
+            unsafe { params.iter_param_ids(param) }.and_then(|mut ids| ids.position(|i| i == id))
+        else {
+            println!("{param}[{id}]: row not found");
+            return;
+        };
+
+        self.selected_param = param_idx;
+        self.selected_param_id = row_idx;
+        self.param_filter.clear();
+        self.row_filter.clear();
+    }
+
     pub fn render_params(&mut self, ui: &imgui::Ui) {
         let params = PARAMS.write();
         const COLUMN1: f32 = 240.;
@@ -78,9 +366,18 @@ impl ParamTinkerer {
                 let param_entries = {
                     ui.set_current_column_width(COLUMN1 + 10.);
 
+                    ui.set_next_item_width(COLUMN1);
+                    ui.input_text("##param_filter", &mut self.param_filter)
+                        .hint("Filter params")
+                        .build();
+
                     let _ = ui.push_item_width(-1.);
-                    ListBox::new("##param_names").size([COLUMN1, 400.]).build(ui, || {
+                    ListBox::new("##param_names").size([COLUMN1, 375.]).build(ui, || {
                         for (idx, k) in params.keys().enumerate() {
+                            if !string_match(&self.param_filter, k) {
+                                continue;
+                            }
+
                             if ui.selectable_config(k).selected(idx == self.selected_param).build()
                             {
                                 self.selected_param = idx;
@@ -88,25 +385,36 @@ impl ParamTinkerer {
                         }
                     });
 
-                    params
-                        .keys()
-                        .nth(self.selected_param)
-                        .and_then(|k| unsafe { params.iter_param_ids(k) }.map(|v| (k, v)))
+                    let param_name = params.keys().nth(self.selected_param);
+                    if let Some(param_name) = param_name {
+                        params.snapshot_param(&mut self.snapshot, param_name);
+                    }
+
+                    param_name.and_then(|k| unsafe { params.iter_param_ids(k) }.map(|v| (k, v)))
                 };
 
                 let param_item = param_entries.map(|(param_name, param_entries)| {
                     ui.next_column();
                     ui.set_current_column_width(COLUMN2 + 10.);
 
+                    ui.set_next_item_width(COLUMN2);
+                    ui.input_text("##row_filter", &mut self.row_filter)
+                        .hint("Filter rows by ID or name")
+                        .build();
+
                     let mut buf = String::new();
                     let _ = ui.push_item_width(-1.);
-                    ListBox::new("##param_ids").size([COLUMN2, 400.]).build(ui, || {
+                    ListBox::new("##param_ids").size([COLUMN2, 375.]).build(ui, || {
                         for (idx, id) in param_entries.enumerate() {
-                            buf.clear();
-                            if let Some(id_name) = PARAM_NAMES
+                            let id_name = PARAM_NAMES
                                 .get(param_name)
-                                .and_then(|param_id_names| param_id_names.get(&(id as usize)))
-                            {
+                                .and_then(|param_id_names| param_id_names.get(&(id as usize)));
+                            if !row_matches(&self.row_filter, id, id_name.map(String::as_str)) {
+                                continue;
+                            }
+
+                            buf.clear();
+                            if let Some(id_name) = id_name {
                                 write!(buf, "{id} - {id_name}").ok();
                             } else {
                                 write!(buf, "{id}").ok();
@@ -126,63 +434,193 @@ impl ParamTinkerer {
                 });
 
                 if let Some((param_name, param_idx)) = param_item {
-                    struct ImguiParamVisitor<'a>(&'a imgui::Ui);
+                    struct ImguiParamVisitor<'a> {
+                        ui: &'a imgui::Ui,
+                        params: &'a Params,
+                        param: &'a str,
+                        jump: Option<(String, u64)>,
+                        changed: HashMap<String, FieldDiff>,
+                        revert: Option<String>,
+                        show_padding: bool,
+                    }
+
+                    impl<'a> ImguiParamVisitor<'a> {
+                        fn field(&mut self, name: &str, f: impl FnOnce(&imgui::Ui)) {
+                            let Some(diff) = self.changed.get(name) else {
+                                f(self.ui);
+                                return;
+                            };
+
+                            let token = self.ui.push_style_color(StyleColor::Text, CHANGED_COLOR);
+                            f(self.ui);
+                            token.pop();
+
+                            if self.ui.is_item_hovered() {
+                                self.ui.tooltip_text(format!("Original: {}", diff.original));
+                            }
+
+                            self.ui.same_line();
+                            if self.ui.small_button(format!("Revert##{name}")) {
+                                self.revert = Some(name.to_string());
+                            }
+                        }
+
+                        /// An integer input, or a combo box of the named
+                        /// values of enum fields.
+                        fn int_field(&mut self, name: &str, i: &mut i32) {
+                            let Some(values) = PARAM_ENUMS.values(self.param, name) else {
+                                self.field(name, |ui| {
+                                    ui.input_int(name, i).build();
+                                });
+                                self.refs(name, *i as i64);
+                                return;
+                            };
+
+                            let current = *i as i64;
+                            let mut items = values
+                                .iter()
+                                .map(|(value, label)| (*value, format!("{label} ({value})")))
+                                .collect::<Vec<_>>();
+                            if !values.iter().any(|(value, _)| *value == current) {
+                                items.push((current, current.to_string()));
+                            }
+                            let mut idx = items.iter().position(|(v, _)| *v == current).unwrap();
+
+                            self.field(name, |ui| {
+                                if ui.combo(name, &mut idx, &items, |(_, label)| {
+                                    Cow::Borrowed(label)
+                                }) {
+                                    *i = items[idx].0 as i32;
+                                }
+                            });
+                        }
+
+                        /// Buttons to jump to the rows an ID field refers to.
+                        fn refs(&mut self, name: &str, value: i64) {
+                            for (target, id) in self.params.resolve_ref(self.param, name, value) {
+                                self.ui.same_line();
+                                if self.ui.small_button(format!("{target} {id}##{name}")) {
+                                    self.jump = Some((target, id));
+                                }
+                            }
+                        }
+                    }
 
                     impl<'a> ParamVisitor for ImguiParamVisitor<'a> {
                         fn visit_u8(&mut self, name: &str, v: &mut u8) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.int_field(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_u16(&mut self, name: &str, v: &mut u16) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.int_field(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_u32(&mut self, name: &str, v: &mut u32) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.int_field(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i8(&mut self, name: &str, v: &mut i8) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.int_field(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i16(&mut self, name: &str, v: &mut i16) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.int_field(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i32(&mut self, name: &str, v: &mut i32) {
-                            let mut i = *v;
-                            self.0.input_int(name, &mut i).build();
-                            *v = i as _;
+                            self.int_field(name, v);
                         }
 
                         fn visit_f32(&mut self, name: &str, v: &mut f32) {
-                            self.0.input_float(name, v).build();
+                            self.field(name, |ui| {
+                                ui.input_float(name, v).build();
+                            });
                         }
 
                         fn visit_bool(&mut self, name: &str, v: &mut bool) {
-                            self.0.checkbox(name, v);
+                            self.field(name, |ui| {
+                                ui.checkbox(name, v);
+                            });
+                        }
+
+                        fn visit_padding(&self) -> bool {
+                            self.show_padding
                         }
                     }
 
                     ui.next_column();
 
+                    let row = params.export_row(param_name, param_idx);
+                    let changed = row
+                        .as_ref()
+                        .map(|row| self.snapshot.diff_row(param_name, row))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|diff| (diff.field.clone(), diff))
+                        .collect();
+                    let mut visitor = ImguiParamVisitor {
+                        ui,
+                        params: &params,
+                        param: param_name,
+                        jump: None,
+                        changed,
+                        revert: None,
+                        show_padding: self.show_padding,
+                    };
+
                     ListBox::new("##param_detail").size([COLUMN3, 400.]).build(ui, || {
                         let _token = ui.push_item_width(120.);
-                        params.visit_param_item(param_name, param_idx, &mut ImguiParamVisitor(ui));
+                        params.visit_param_item(param_name, param_idx, &mut visitor);
                     });
+
+                    if let (Some(row), Some(field)) = (row, visitor.revert) {
+                        let scope = RevertScope::Field(row.id, &field);
+                        if let Err(e) = params.revert_param(&self.snapshot, param_name, scope) {
+                            println!("{e}");
+                        }
+                    }
+
+                    if let Some((param, id)) = visitor.jump {
+                        self.select_row(&params, &param, id);
+                    }
                 };
             });
     }
 }
 
+fn export_param(params: &Params, param_name: &str, ext: &str) -> Result<String, String> {
+    let table =
+        params.export_param(param_name).ok_or_else(|| format!("Couldn't read {param_name}"))?;
+    let contents = if ext == "csv" { table.to_csv() } else { table.to_json() };
+
+    let path = format!("{param_name}.{ext}");
+    fs::write(&path, contents).map_err(|e| format!("Couldn't write {path}: {e}"))?;
+
+    Ok(path)
+}
+
+fn import_param(params: &Params, param_name: &str, ext: &str) -> Result<Vec<String>, String> {
+    let path = format!("{param_name}.{ext}");
+    let contents = fs::read_to_string(&path).map_err(|e| format!("Couldn't read {path}: {e}"))?;
+
+    let table = if ext == "csv" {
+        ParamTable::from_csv(param_name, &contents)
+    } else {
+        ParamTable::from_json(&contents)
+    }
+    .map_err(|e| format!("{path}: {e}"))?;
+
+    params.import_param(&table)
+}
+
 hudhook::hudhook!(ImguiDx11Hooks, ParamTinkerer::new());
@@ -0,0 +1,177 @@
+//! Hotkeys bound more than once in a config.
+
+use std::fmt;
+
+use practice_tool_core::key::Key;
+
+use super::{CfgCommand, Config};
+
+/// The same hotkey bound to two actions.
+#[derive(Debug)]
+pub(crate) struct HotkeyConflict {
+    /// Index of the command binding the key the second time, counting groups
+    /// before their commands. `None` when `display` and `hide` collide.
+    pub(crate) command: Option<usize>,
+    pub(crate) key: Key,
+    pub(crate) first: String,
+    pub(crate) second: String,
+}
+
+impl fmt::Display for HotkeyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hotkey \"{}\" of {} is already bound to {}", self.key, self.second, self.first)
+    }
+}
+
+impl CfgCommand {
+    /// Hotkeys of this command and what they do. The commands of a group
+    /// are not included.
+    fn hotkeys(&self) -> Vec<(Key, String)> {
+        let hotkeys = match self {
+            CfgCommand::SavefileManager { hotkey_load } => {
+                vec![(hotkey_load.as_option(), "savefile manager".to_string())]
+            },
+            CfgCommand::ItemSpawner { hotkey_load } => {
+                vec![(hotkey_load.as_option(), "item spawner".to_string())]
+            },
+            CfgCommand::Flag { flag, hotkey } => {
+                vec![(hotkey.as_ref(), format!("flag \"{}\"", flag.label))]
+            },
+            CfgCommand::CustomFlag { label, hotkey, .. } => {
+                vec![(hotkey.as_ref(), format!("custom flag \"{label}\""))]
+            },
+            CfgCommand::CustomValue { label, hotkey, .. } => {
+                vec![(hotkey.as_ref(), format!("custom value \"{label}\""))]
+            },
+            CfgCommand::Position { position, save, .. } => vec![
+                (position.as_option(), "load position".to_string()),
+                (save.as_ref(), "save position".to_string()),
+            ],
+            CfgCommand::CycleSpeed { hotkey, .. } => vec![(hotkey.as_ref(), "speed".to_string())],
+            CfgCommand::CycleColor { hotkey, .. } => vec![(hotkey.as_ref(), "color".to_string())],
+            CfgCommand::CharacterStats { value } => {
+                vec![(value.as_option(), "character stats".to_string())]
+            },
+            CfgCommand::Souls { amount, hotkey } => {
+                vec![(hotkey.as_ref(), format!("souls ({amount})"))]
+            },
+            CfgCommand::OpenMenu { kind, hotkey } => {
+                vec![(hotkey.as_ref(), format!("open menu ({kind:?})"))]
+            },
+            CfgCommand::Quitout { hotkey } => vec![(hotkey.as_option(), "quitout".to_string())],
+            CfgCommand::Target { hotkey } => vec![(hotkey.as_option(), "target".to_string())],
+            CfgCommand::NudgePosition { nudge_up, nudge_down, .. } => vec![
+                (nudge_up.as_ref(), "nudge up".to_string()),
+                (nudge_down.as_ref(), "nudge down".to_string()),
+            ],
+            CfgCommand::Label { .. } | CfgCommand::Group { .. } => Vec::new(),
+        };
+
+        hotkeys.into_iter().filter_map(|(key, action)| Some((*key?, action))).collect()
+    }
+}
+
+/// Every command, groups before their commands.
+fn walk<'a>(commands: &'a [CfgCommand], out: &mut Vec<&'a CfgCommand>) {
+    for command in commands {
+        out.push(command);
+        if let CfgCommand::Group { commands, .. } = command {
+            walk(commands, out);
+        }
+    }
+}
+
+impl Config {
+    /// Hotkeys bound to more than one action, including the `display` and
+    /// `hide` keys, in the order they are bound.
+    pub(crate) fn hotkey_conflicts(&self) -> Vec<HotkeyConflict> {
+        let mut bound = vec![(self.settings.display, "the display key".to_string())];
+        let mut conflicts = Vec::new();
+
+        if let Some(hide) = self.settings.hide {
+            let action = "the hide key".to_string();
+            match bound.iter().find(|(key, _)| *key == hide) {
+                Some((_, first)) => conflicts.push(HotkeyConflict {
+                    command: None,
+                    key: hide,
+                    first: first.clone(),
+                    second: action,
+                }),
+                None => bound.push((hide, action)),
+            }
+        }
+
+        let mut commands = Vec::new();
+        walk(&self.commands, &mut commands);
+
+        for (index, command) in commands.into_iter().enumerate() {
+            for (key, action) in command.hotkeys() {
+                match bound.iter().find(|(bound_key, _)| *bound_key == key) {
+                    Some((_, first)) => conflicts.push(HotkeyConflict {
+                        command: Some(index),
+                        key,
+                        first: first.clone(),
+                        second: action,
+                    }),
+                    None => bound.push((key, action)),
+                }
+            }
+        }
+
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflicts(commands: &str, hide: &str) -> Vec<(Option<usize>, Key, String, String)> {
+        let source = format!(
+            "commands = [ {commands} ]\n[settings]\nlog_level = \"DEBUG\"\ndisplay = \"0\"\nhide \
+             = \"{hide}\"\n"
+        );
+        let (config, _) = Config::load(&source);
+        config
+            .hotkey_conflicts()
+            .into_iter()
+            .map(|c| (c.command, c.key, c.first, c.second))
+            .collect()
+    }
+
+    fn key(key: &str) -> Key {
+        key.parse().unwrap()
+    }
+
+    #[test]
+    fn test_no_conflicts() {
+        assert!(conflicts(
+            r#"{ flag = "inf_stamina", hotkey = "1" }, { flag = "inf_focus", hotkey = "2" },
+               { flag = "one_shot" }, { quitout = true }"#,
+            "rshift+0",
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let conflicts = conflicts(
+            r#"{ flag = "inf_stamina", hotkey = "1" },
+               { group = "Group", commands = [
+                 { label = "Label" },
+                 { position = "2", save = "0" },
+               ]},
+               { nudge = 1.0, nudge_up = "3", nudge_down = "1" },
+               { target = "2" }"#,
+            "0",
+        );
+
+        let display = "the display key".to_string();
+        assert_eq!(conflicts, vec![
+            (None, key("0"), display.clone(), "the hide key".to_string()),
+            (Some(3), key("0"), display, "save position".to_string()),
+            (Some(4), key("1"), "flag \"Inf Stamina\"".to_string(), "nudge down".to_string()),
+            (Some(5), key("2"), "load position".to_string(), "target".to_string()),
+        ]);
+    }
+}
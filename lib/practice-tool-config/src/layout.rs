@@ -0,0 +1,229 @@
+//! Placement of the overlay windows, from `[settings.layout]`.
+
+use serde::Deserialize;
+
+/// Height of a line in the logs window.
+const LOG_LINE_HEIGHT: f32 = 14.;
+
+/// Width of the logs window, as a fraction of the display width.
+const LOG_WIDTH: f32 = 0.3;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Size of the font, picked from the display width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontSize {
+    Small,
+    Normal,
+    Big,
+}
+
+impl FontSize {
+    pub fn for_width(width: f32) -> FontSize {
+        if width > 2000. {
+            FontSize::Big
+        } else if width > 1200. {
+            FontSize::Normal
+        } else {
+            FontSize::Small
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowLayout {
+    pub anchor: Anchor,
+    /// Distance from the anchor corner, in pixels.
+    pub offset: [f32; 2],
+    /// Distance from the anchor corner, as a fraction of the display size.
+    /// Added to `offset`.
+    pub relative_offset: [f32; 2],
+    pub bg_alpha: f32,
+    /// Scale of the font, over the size picked from the display width.
+    pub font_scale: Option<f32>,
+}
+
+/// Where a window goes: `position` is the point of the display the `pivot`
+/// of the window sits on, both as imgui takes them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub position: [f32; 2],
+    pub pivot: [f32; 2],
+}
+
+impl WindowLayout {
+    pub fn place(&self, [dw, dh]: [f32; 2]) -> Placement {
+        let x = self.offset[0] + self.relative_offset[0] * dw;
+        let y = self.offset[1] + self.relative_offset[1] * dh;
+
+        let (position, pivot) = match self.anchor {
+            Anchor::TopLeft => ([x, y], [0., 0.]),
+            Anchor::TopRight => ([dw - x, y], [1., 0.]),
+            Anchor::BottomLeft => ([x, dh - y], [0., 1.]),
+            Anchor::BottomRight => ([dw - x, dh - y], [1., 1.]),
+        };
+
+        Placement { position, pivot }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(from = "LayoutConfig")]
+pub struct Layout {
+    /// The tool window, shown when the tool is open.
+    pub tool: WindowLayout,
+    /// The title and buttons shown when the tool is closed.
+    pub message: WindowLayout,
+    pub logs: WindowLayout,
+    /// Number of log lines shown.
+    pub log_lines: usize,
+}
+
+impl Layout {
+    /// Size of the logs window.
+    pub fn logs_size(&self, [dw, _]: [f32; 2]) -> [f32; 2] {
+        [dw * LOG_WIDTH, LOG_LINE_HEIGHT * (self.log_lines + 3) as f32]
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::from(LayoutConfig::default())
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct LayoutConfig {
+    tool: WindowConfig,
+    message: WindowConfig,
+    logs: LogsConfig,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct WindowConfig {
+    anchor: Option<Anchor>,
+    offset: Option<[f32; 2]>,
+    relative_offset: Option<[f32; 2]>,
+    bg_alpha: Option<f32>,
+    font_scale: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct LogsConfig {
+    #[serde(flatten)]
+    window: WindowConfig,
+    lines: Option<usize>,
+}
+
+impl WindowConfig {
+    /// Fills the options that are not set with the ones of `default`.
+    fn or(self, default: WindowLayout) -> WindowLayout {
+        WindowLayout {
+            anchor: self.anchor.unwrap_or(default.anchor),
+            offset: self.offset.unwrap_or(default.offset),
+            relative_offset: self.relative_offset.unwrap_or(default.relative_offset),
+            bg_alpha: self.bg_alpha.unwrap_or(default.bg_alpha).clamp(0., 1.),
+            font_scale: self.font_scale.or(default.font_scale).filter(|scale| *scale > 0.),
+        }
+    }
+}
+
+impl From<LayoutConfig> for Layout {
+    fn from(layout: LayoutConfig) -> Self {
+        Layout {
+            tool: layout.tool.or(WindowLayout {
+                anchor: Anchor::TopLeft,
+                offset: [16., 16.],
+                relative_offset: [0., 0.],
+                bg_alpha: 0.8,
+                font_scale: None,
+            }),
+            message: layout.message.or(WindowLayout {
+                anchor: Anchor::TopLeft,
+                offset: [16., 0.],
+                relative_offset: [0., 0.14],
+                bg_alpha: 0.,
+                font_scale: None,
+            }),
+            logs: layout.logs.window.or(WindowLayout {
+                anchor: Anchor::BottomRight,
+                offset: [0., 0.],
+                relative_offset: [0.05, 0.2],
+                bg_alpha: 0.,
+                font_scale: None,
+            }),
+            log_lines: layout.logs.lines.unwrap_or(3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISPLAY: [f32; 2] = [1920., 1080.];
+
+    fn layout(source: &str) -> Layout {
+        toml::from_str(source).unwrap()
+    }
+
+    #[test]
+    fn test_default_placement() {
+        let layout = Layout::default();
+
+        assert_eq!(layout.tool.place(DISPLAY), Placement { position: [16., 16.], pivot: [0., 0.] });
+        assert_eq!(layout.message.place(DISPLAY), Placement {
+            position: [16., 1080. * 0.14],
+            pivot: [0., 0.]
+        });
+        assert_eq!(layout.logs.place(DISPLAY), Placement {
+            position: [1920. * 0.95, 1080. * 0.8],
+            pivot: [1., 1.]
+        });
+        assert_eq!(layout.logs_size(DISPLAY), [1920. * 0.3, 14. * 6.]);
+    }
+
+    #[test]
+    fn test_anchors() {
+        let layout = layout(
+            r#"
+            tool = { anchor = "top_right", offset = [10, 20] }
+            message = { anchor = "bottom_left", offset = [10, 20], relative_offset = [0.5, 0] }
+            logs = { anchor = "top_left", lines = 5, bg_alpha = 2.0 }
+            "#,
+        );
+
+        assert_eq!(layout.tool.place(DISPLAY), Placement {
+            position: [1910., 20.],
+            pivot: [1., 0.]
+        });
+        assert_eq!(layout.message.place(DISPLAY), Placement {
+            position: [970., 1060.],
+            pivot: [0., 1.]
+        });
+        assert_eq!(layout.logs.place(DISPLAY).pivot, [0., 0.]);
+        assert_eq!(layout.logs.bg_alpha, 1.);
+        assert_eq!(layout.logs_size(DISPLAY)[1], 14. * 8.);
+
+        // Options not set keep the defaults of their window.
+        assert_eq!(layout.tool.bg_alpha, 0.8);
+        assert_eq!(layout.logs.relative_offset, [0.05, 0.2]);
+    }
+
+    #[test]
+    fn test_font_size() {
+        assert_eq!(FontSize::for_width(800.), FontSize::Small);
+        assert_eq!(FontSize::for_width(1920.), FontSize::Normal);
+        assert_eq!(FontSize::for_width(2560.), FontSize::Big);
+    }
+}
@@ -0,0 +1,674 @@
+//! Settings and commands of `jdsd_dsiii_practice_tool.toml`, checked with
+//! line and column diagnostics. The tool builds the widgets of the commands;
+//! this crate doesn't depend on it, so configs can be checked on any target.
+
+use std::str::FromStr;
+
+use libds3::prelude::base_addresses::BaseAddresses;
+use libds3::prelude::*;
+use practice_tool_core::key::Key;
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::filter::LevelFilter;
+
+mod hotkeys;
+mod layout;
+mod validate;
+
+pub use layout::{Anchor, FontSize, Layout, Placement, WindowLayout};
+pub use validate::{check_config, report_config, Diagnostic, Severity};
+
+#[derive(Debug)]
+pub struct Config {
+    pub settings: Settings,
+    pub commands: Vec<CfgCommand>,
+    /// Names of the `[profiles.<name>]` of the config file.
+    pub profiles: Vec<String>,
+    /// The profile the settings and commands come from, if any.
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Settings {
+    pub log_level: LevelFilterSerde,
+    pub display: Key,
+    pub hide: Option<Key>,
+    #[serde(default)]
+    pub show_console: bool,
+    #[serde(default = "Indicator::default_set")]
+    pub indicators: Vec<Indicator>,
+    /// Refuse to load the config if a hotkey is bound more than once.
+    #[serde(default)]
+    pub fatal_hotkey_conflicts: bool,
+    /// Profile loaded on startup.
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub layout: Layout,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum IndicatorType {
+    Igt,
+    Position,
+    PositionChange,
+    GameVersion,
+    ImguiDebug,
+    Fps,
+    FrameCount,
+    Animation,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(try_from = "IndicatorConfig")]
+pub struct Indicator {
+    pub indicator: IndicatorType,
+    pub enabled: bool,
+}
+
+impl Indicator {
+    fn default_set() -> Vec<Indicator> {
+        vec![
+            Indicator { indicator: IndicatorType::GameVersion, enabled: true },
+            Indicator { indicator: IndicatorType::Igt, enabled: true },
+            Indicator { indicator: IndicatorType::Position, enabled: false },
+            Indicator { indicator: IndicatorType::PositionChange, enabled: false },
+            Indicator { indicator: IndicatorType::Animation, enabled: false },
+            Indicator { indicator: IndicatorType::Fps, enabled: false },
+            Indicator { indicator: IndicatorType::FrameCount, enabled: false },
+            Indicator { indicator: IndicatorType::ImguiDebug, enabled: false },
+        ]
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct IndicatorConfig {
+    indicator: String,
+    enabled: bool,
+}
+
+impl TryFrom<IndicatorConfig> for Indicator {
+    type Error = String;
+
+    fn try_from(indicator: IndicatorConfig) -> Result<Self, Self::Error> {
+        match indicator.indicator.as_str() {
+            "igt" => Ok(Indicator { indicator: IndicatorType::Igt, enabled: indicator.enabled }),
+            "position" => {
+                Ok(Indicator { indicator: IndicatorType::Position, enabled: indicator.enabled })
+            },
+            "position_change" => Ok(Indicator {
+                indicator: IndicatorType::PositionChange,
+                enabled: indicator.enabled,
+            }),
+            "game_version" => {
+                Ok(Indicator { indicator: IndicatorType::GameVersion, enabled: indicator.enabled })
+            },
+            "imgui_debug" => {
+                Ok(Indicator { indicator: IndicatorType::ImguiDebug, enabled: indicator.enabled })
+            },
+            "fps" => Ok(Indicator { indicator: IndicatorType::Fps, enabled: indicator.enabled }),
+            "framecount" => {
+                Ok(Indicator { indicator: IndicatorType::FrameCount, enabled: indicator.enabled })
+            },
+            "animation" => {
+                Ok(Indicator { indicator: IndicatorType::Animation, enabled: indicator.enabled })
+            },
+            value => Err(format!("Unrecognized indicator: {value}")),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum PlaceholderOption<T> {
+    Data(T),
+    #[allow(dead_code)]
+    Placeholder(bool),
+}
+
+impl<T> PlaceholderOption<T> {
+    pub fn as_option(&self) -> Option<&T> {
+        match self {
+            PlaceholderOption::Data(d) => Some(d),
+            PlaceholderOption::Placeholder(_) => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum OpenMenuKind {
+    #[serde(rename = "travel")]
+    Travel,
+    #[serde(rename = "attune")]
+    Attune,
+}
+
+/// A command of the config file. Commands are told apart by the name of their
+/// first field, see [`validate::COMMANDS`].
+#[derive(Debug, Deserialize, PartialEq)]
+pub enum CfgCommand {
+    SavefileManager {
+        #[serde(rename = "savefile_manager")]
+        hotkey_load: PlaceholderOption<Key>,
+    },
+    ItemSpawner {
+        #[serde(rename = "item_spawner")]
+        hotkey_load: PlaceholderOption<Key>,
+    },
+    Flag {
+        flag: FlagSpec,
+        hotkey: Option<Key>,
+    },
+    CustomFlag {
+        #[serde(rename = "custom_flag")]
+        label: String,
+        base: BaseName,
+        #[serde(default)]
+        offsets: Vec<usize>,
+        bit: FlagBit,
+        hotkey: Option<Key>,
+    },
+    CustomValue {
+        #[serde(rename = "custom_value")]
+        label: String,
+        base: BaseName,
+        #[serde(default)]
+        offsets: Vec<usize>,
+        #[serde(flatten)]
+        values: CustomValues,
+        hotkey: Option<Key>,
+    },
+    Label {
+        #[serde(rename = "label")]
+        label: String,
+    },
+    Position {
+        position: PlaceholderOption<Key>,
+        save: Option<Key>,
+        /// Where the position is saved, counting position commands in order.
+        #[serde(skip)]
+        slot: usize,
+    },
+    CycleSpeed {
+        #[serde(rename = "cycle_speed")]
+        values: Vec<f32>,
+        hotkey: Option<Key>,
+    },
+    CycleColor {
+        #[serde(rename = "cycle_color")]
+        values: Vec<i32>,
+        hotkey: Option<Key>,
+    },
+    CharacterStats {
+        #[serde(rename = "character_stats")]
+        value: PlaceholderOption<Key>,
+    },
+    Souls {
+        #[serde(rename = "souls")]
+        amount: u32,
+        hotkey: Option<Key>,
+    },
+    OpenMenu {
+        #[serde(rename = "open_menu")]
+        kind: OpenMenuKind,
+        hotkey: Option<Key>,
+    },
+    Quitout {
+        #[serde(rename = "quitout")]
+        hotkey: PlaceholderOption<Key>,
+    },
+    Target {
+        #[serde(rename = "target")]
+        hotkey: PlaceholderOption<Key>,
+    },
+    NudgePosition {
+        nudge: f32,
+        nudge_up: Option<Key>,
+        nudge_down: Option<Key>,
+    },
+    Group {
+        #[serde(rename = "group")]
+        label: String,
+        /// Checked one by one, and filled in after the group itself.
+        #[serde(skip)]
+        commands: Vec<CfgCommand>,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct LevelFilterSerde(LevelFilter);
+
+impl LevelFilterSerde {
+    pub fn inner(&self) -> LevelFilter {
+        self.0
+    }
+}
+
+impl TryFrom<String> for LevelFilterSerde {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(LevelFilterSerde(
+            LevelFilter::from_str(&value)
+                .map_err(|e| format!("Couldn't parse log level filter: {}", e))?,
+        ))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            settings: Settings {
+                log_level: LevelFilterSerde(LevelFilter::DEBUG),
+                display: "0".parse().unwrap(),
+                hide: "rshift+0".parse().ok(),
+                show_console: false,
+                indicators: Indicator::default_set(),
+                fatal_hotkey_conflicts: false,
+                default_profile: None,
+                layout: Layout::default(),
+            },
+            commands: Vec::new(),
+            profiles: Vec::new(),
+            profile: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+pub struct FlagSpec {
+    pub label: String,
+    pub getter: FlagGetter,
+}
+
+// The label tells flags apart, getters can't be compared.
+impl PartialEq for FlagSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+    }
+}
+
+impl std::fmt::Debug for FlagSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FlagSpec {{ label: {:?} }}", self.label)
+    }
+}
+
+pub type FlagGetter = fn(&PointerChains) -> &Bitflag<u8>;
+
+/// Flags by name, with their label.
+const FLAGS: &[(&str, &str, FlagGetter)] = &[
+    ("all_no_damage", "All no damage", |c| &c.all_no_damage),
+    ("inf_stamina", "Inf Stamina", |c| &c.inf_stamina),
+    ("inf_focus", "Inf Focus", |c| &c.inf_focus),
+    ("inf_consumables", "Inf Consumables", |c| &c.inf_consumables),
+    ("deathcam", "Deathcam", |c| &c.deathcam),
+    ("no_death", "No death", |c| &c.no_death),
+    ("one_shot", "One shot", |c| &c.one_shot),
+    ("evt_draw", "Event draw", |c| &c.evt_draw),
+    ("bloodstain_draw", "Stable/Bloodstain draw", |c| &c.bloodstain_draw),
+    ("evt_disable", "Event disable", |c| &c.evt_disable),
+    ("ai_disable", "AI disable", |c| &c.ai_disable),
+    ("ember", "Ember", |c| &c.ember),
+    ("rend_chr", "Render characters", |c| &c.rend_chr),
+    ("rend_obj", "Render objects", |c| &c.rend_obj),
+    ("rend_map", "Render map", |c| &c.rend_map),
+    ("rend_mesh_hi", "Collision mesh hi", |c| &c.rend_mesh_hi),
+    ("rend_mesh_lo", "Collision mesh lo", |c| &c.rend_mesh_lo),
+    ("rend_mesh_hit", "Collision mesh hit", |c| &c.rend_mesh_hit),
+    ("debug_draw", "Debug draw", |c| &c.debug_draw),
+    ("hurtbox", "Hurtbox", |c| &c.rend_hurtbox),
+    ("all_draw_hit", "All draw hit", |c| &c.all_draw_hit),
+    ("ik_foot_ray", "IK foot ray", |c| &c.ik_foot_ray),
+    ("debug_sphere_1", "Debug sphere 1", |c| &c.debug_sphere_1),
+    ("debug_sphere_2", "Debug sphere 2", |c| &c.debug_sphere_2),
+    ("gravity", "No Gravity", |c| &c.gravity),
+    ("collision", "No Collision", |c| &c.collision),
+];
+
+impl TryFrom<String> for FlagSpec {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match FLAGS.iter().find(|(name, ..)| *name == value) {
+            Some(&(_, label, getter)) => Ok(FlagSpec { label: label.to_string(), getter }),
+            None => Err(format!(
+                "\"{}\" is not a valid flag specifier{}",
+                value,
+                validate::did_you_mean(&value, FLAGS.iter().map(|(name, ..)| *name))
+            )),
+        }
+    }
+}
+
+/// Name of one of the [`BaseAddresses`], where the pointer chain of a custom
+/// flag or value starts.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub struct BaseName(String);
+
+impl BaseName {
+    pub fn chain<T>(&self, chains: &PointerChains, offsets: &[usize]) -> PointerChain<T> {
+        chains.custom_chain(&self.0, offsets).expect("base names are checked when parsing")
+    }
+}
+
+impl TryFrom<String> for BaseName {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if BaseAddresses::NAMES.contains(&value.as_str()) {
+            Ok(BaseName(value))
+        } else {
+            Err(format!(
+                "\"{}\" is not a valid base address{}",
+                value,
+                validate::did_you_mean(&value, BaseAddresses::NAMES.iter().copied())
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "u8")]
+pub struct FlagBit(u8);
+
+impl FlagBit {
+    pub fn mask(&self) -> u8 {
+        1 << self.0
+    }
+}
+
+impl TryFrom<u8> for FlagBit {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value < 8 {
+            Ok(FlagBit(value))
+        } else {
+            Err(format!("Bit {} is out of range, expected 0 to 7", value))
+        }
+    }
+}
+
+/// Values a custom value cycles through, in the type of the value in memory.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "CustomValuesConfig")]
+pub enum CustomValues {
+    I32(Vec<i32>),
+    F32(Vec<f32>),
+    U8(Vec<u8>),
+}
+
+#[derive(Debug, Deserialize)]
+struct CustomValuesConfig {
+    #[serde(rename = "type")]
+    ty: String,
+    values: Vec<f64>,
+}
+
+impl TryFrom<CustomValuesConfig> for CustomValues {
+    type Error = String;
+
+    fn try_from(config: CustomValuesConfig) -> Result<Self, Self::Error> {
+        fn integers<T: TryFrom<i64>>(ty: &str, values: &[f64]) -> Result<Vec<T>, String> {
+            values
+                .iter()
+                .map(|&v| {
+                    let value = if v.fract() == 0. { T::try_from(v as i64).ok() } else { None };
+                    value.ok_or_else(|| format!("{} is not a valid {} value", v, ty))
+                })
+                .collect()
+        }
+
+        let CustomValuesConfig { ty, values } = config;
+        if values.is_empty() {
+            return Err("Custom values need at least one value to cycle through".to_string());
+        }
+
+        match ty.as_str() {
+            "i32" => integers(&ty, &values).map(CustomValues::I32),
+            "u8" => integers(&ty, &values).map(CustomValues::U8),
+            "f32" => Ok(CustomValues::F32(values.iter().map(|&v| v as f32).collect())),
+            e => Err(format!("\"{}\" is not a valid value type, expected i32, f32 or u8", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a config, failing on any error.
+    fn parse(cfg: &str) -> Result<Config, String> {
+        let (config, diagnostics) = Config::load(cfg);
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(format!("TOML configuration parse error: {}", errors.join("\n")))
+        }
+    }
+
+    #[test]
+    fn test_parse_ok() {
+        println!(
+            "{:#?}",
+            toml::from_str::<toml::Value>(include_str!("../../../jdsd_dsiii_practice_tool.toml"))
+        );
+        println!("{:#?}", parse(include_str!("../../../jdsd_dsiii_practice_tool.toml")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        println!(
+            "{:#?}",
+            parse(
+                r#"commands = [ { boh = 3 } ]
+                [settings]
+                log_level = "DEBUG"
+                "#
+            )
+        );
+    }
+
+    fn parse_command(command: &str) -> Result<Config, String> {
+        parse(&format!(
+            "commands = [ {command} ]\n[settings]\nlog_level = \"DEBUG\"\ndisplay = \"0\"\n"
+        ))
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let config = parse_command(
+            r#"{ custom_flag = "My flag", base = "world_chr_man", offsets = [0x80, 0x1a08], bit = 6 }"#,
+        )
+        .unwrap();
+        match &config.commands[..] {
+            [CfgCommand::CustomFlag { label, base, offsets, bit, hotkey: None }] => {
+                assert_eq!(label, "My flag");
+                assert_eq!(base.0, "world_chr_man");
+                assert_eq!(offsets, &[0x80, 0x1a08]);
+                assert_eq!(bit.mask(), 0b1000000);
+            },
+            commands => panic!("{commands:?}"),
+        }
+
+        let config = parse_command(
+            r#"{ custom_value = "Souls", base = "base_a", offsets = [0x10, 0x74], type = "i32", values = [10000, 0], hotkey = "9" }"#,
+        )
+        .unwrap();
+        match &config.commands[..] {
+            [CfgCommand::CustomValue {
+                values: CustomValues::I32(values), hotkey: Some(_), ..
+            }] => {
+                assert_eq!(values, &[10000, 0]);
+            },
+            commands => panic!("{commands:?}"),
+        }
+
+        let config = parse_command(
+            r#"{ custom_value = "Mesh color", base = "base_hbd", type = "f32", values = [1, 2.5] }"#,
+        )
+        .unwrap();
+        match &config.commands[..] {
+            [CfgCommand::CustomValue { offsets, values: CustomValues::F32(values), .. }] => {
+                assert!(offsets.is_empty());
+                assert_eq!(values, &[1., 2.5]);
+            },
+            commands => panic!("{commands:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_custom_errors() {
+        for command in [
+            r#"{ custom_flag = "F", base = "world_chr_mam", offsets = [0x80], bit = 6 }"#,
+            r#"{ custom_flag = "F", base = "xa", offsets = [0x80], bit = 6 }"#,
+            r#"{ custom_flag = "F", base = "world_chr_man", offsets = [-0x80], bit = 6 }"#,
+            r#"{ custom_flag = "F", base = "world_chr_man", offsets = ["0x80"], bit = 6 }"#,
+            r#"{ custom_flag = "F", base = "world_chr_man", offsets = [0x80], bit = 8 }"#,
+            r#"{ custom_flag = "F", base = "world_chr_man", offsets = [0x80] }"#,
+            r#"{ custom_value = "V", base = "base_a", type = "u64", values = [1] }"#,
+            r#"{ custom_value = "V", base = "base_a", type = "i32", values = [1.5] }"#,
+            r#"{ custom_value = "V", base = "base_a", type = "u8", values = [256] }"#,
+            r#"{ custom_value = "V", base = "base_a", type = "u8", values = [] }"#,
+            r#"{ custom_value = "V", base = "base_a", values = [1] }"#,
+        ] {
+            assert!(parse_command(command).is_err(), "{command}");
+        }
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        let source = r#"
+            [settings]
+            log_level = "DEBUG"
+            display = "0"
+
+            [settings.layout]
+            logs = { anchor = "top_right", lines = 6 }
+        "#;
+        let (config, diagnostics) = Config::load(source);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(config.settings.layout.log_lines, 6);
+        assert_eq!(config.settings.layout.tool, Layout::default().tool);
+
+        let (config, diagnostics) = Config::load(&source.replace("top_right", "middle"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(config.settings.layout, Layout::default());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let source = r#"commands = [ { flag = "inf_stamina" }, { quitout = "p" } ]
+
+[settings]
+log_level = "DEBUG"
+display = "0"
+hide = "rshift+0"
+default_profile = "any"
+
+[profiles.any.settings]
+display = "9"
+
+[profiles.bosses]
+commands = [ { souls = 10000 } ]
+
+[profiles.glitches.settings]
+hide = "rshift+1"
+"#;
+
+        let (config, diagnostics) = Config::load(source);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(config.profiles, ["any", "bosses", "glitches"]);
+        assert_eq!(config.profile.as_deref(), Some("any"));
+        assert_eq!(config.settings.display, "9".parse().unwrap());
+        assert_eq!(config.settings.hide, "rshift+0".parse().ok());
+        assert_eq!(config.commands.len(), 2);
+
+        let config = Config::load_profile(source, Some("bosses")).0.unwrap();
+        assert_eq!(config.profile.as_deref(), Some("bosses"));
+        assert_eq!(config.settings.display, "0".parse().unwrap());
+        assert!(matches!(config.commands[..], [CfgCommand::Souls { amount: 10000, .. }]));
+
+        let config = Config::load_profile(source, Some("glitches")).0.unwrap();
+        assert_eq!(config.settings.hide, "rshift+1".parse().ok());
+        assert_eq!(config.commands.len(), 2);
+
+        let config = Config::load_profile(source, None).0.unwrap();
+        assert_eq!(config.profile, None);
+        assert_eq!(config.settings.display, "0".parse().unwrap());
+
+        let (config, diagnostics) = Config::load_profile(source, Some("glitch"));
+        let config = config.unwrap();
+        assert_eq!(config.profile, None);
+        assert_eq!(config.settings.display, "0".parse().unwrap());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown profile \"glitch\", did you mean \"glitches\"?"
+        );
+
+        let (_, diagnostics) = Config::load(&source.replace("\"any\"", "\"all\""));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].severity), (4, Severity::Error));
+    }
+
+    #[test]
+    fn test_profile_diagnostics() {
+        let (_, diagnostics) = Config::load(
+            r#"commands = [ { flag = "inf_stamina", hotkey = "1" } ]
+[settings]
+log_level = "DEBUG"
+display = "0"
+
+[profiles.a.settings]
+hide = "nope"
+
+[profiles.b]
+commands = [ { flga = "one_shot" }, { souls = 1, hotkey = "1" }, { quitout = "1" } ]
+"#,
+        );
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.severity)).collect::<Vec<_>>(), [
+            (7, Severity::Error),
+            (10, Severity::Error),
+            (10, Severity::Warning)
+        ]);
+    }
+
+    #[test]
+    fn test_profile_errors() {
+        let source = r#"commands = [ { quitout = "1" } ]
+[settings]
+log_level = "DEBUG"
+display = "0"
+
+[profiles.a.settings]
+hide = "nope"
+
+[profiles.b]
+commands = [ { souls = 1 } ]
+"#;
+
+        // Errors in the other profiles are reported, but don't fail the load.
+        let (config, diagnostics) = Config::load_profile(source, Some("b"));
+        assert_eq!(config.unwrap().profile.as_deref(), Some("b"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].severity), (7, Severity::Error));
+        assert!(Config::load_profile(source, None).0.is_ok());
+
+        let error = Config::load_profile(source, Some("a")).0.unwrap_err();
+        assert_eq!((error.line, error.severity), (7, Severity::Error));
+
+        let source = source.replace("display = \"0\"", "display = \"nope\"");
+        assert_eq!(Config::load_profile(&source, Some("b")).0.unwrap_err().line, 3);
+    }
+}
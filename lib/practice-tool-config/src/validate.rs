@@ -0,0 +1,591 @@
+//! Checks of `jdsd_dsiii_practice_tool.toml` that report every problem with
+//! its line and column, and keep the commands that are fine.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{fmt, iter};
+
+use serde::de::value::{MapAccessDeserializer, MapDeserializer};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use toml::Spanned;
+
+use super::{CfgCommand, Config, Settings};
+
+/// Commands by the name of their first field, with the enum variant they
+/// are read as and the names of their other fields.
+pub(super) const COMMANDS: &[(&str, &str, &[&str])] = &[
+    ("savefile_manager", "SavefileManager", &[]),
+    ("item_spawner", "ItemSpawner", &[]),
+    ("flag", "Flag", &["hotkey"]),
+    ("custom_flag", "CustomFlag", &["base", "offsets", "bit", "hotkey"]),
+    ("custom_value", "CustomValue", &["base", "offsets", "type", "values", "hotkey"]),
+    ("label", "Label", &[]),
+    ("position", "Position", &["save"]),
+    ("cycle_speed", "CycleSpeed", &["hotkey"]),
+    ("cycle_color", "CycleColor", &["hotkey"]),
+    ("character_stats", "CharacterStats", &[]),
+    ("souls", "Souls", &["hotkey"]),
+    ("open_menu", "OpenMenu", &["hotkey"]),
+    ("quitout", "Quitout", &[]),
+    ("target", "Target", &[]),
+    ("nudge", "NudgePosition", &["nudge_up", "nudge_down"]),
+    ("group", "Group", &["commands"]),
+];
+
+const SETTINGS: &[&str] = &[
+    "log_level",
+    "display",
+    "hide",
+    "show_console",
+    "indicators",
+    "fatal_hotkey_conflicts",
+    "default_profile",
+    "layout",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in a config, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}: {}", self.line, self.column, self.severity, self.message)
+    }
+}
+
+/// Number of single-character edits, including swapping two adjacent
+/// characters, turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// `, did you mean "candidate"?` for the candidate closest to `name`, if it
+/// is close enough to be a typo. Empty otherwise.
+pub(super) fn did_you_mean<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| format!(", did you mean \"{candidate}\"?"))
+        .unwrap_or_default()
+}
+
+/// A command as written, with the position of each field. The commands of a
+/// group are kept apart, so that they are checked one by one.
+#[derive(Clone)]
+struct RawCommand {
+    fields: Vec<(Spanned<String>, toml::Value)>,
+    commands: Option<Vec<Spanned<RawCommand>>>,
+}
+
+impl<'de> Deserialize<'de> for RawCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawCommandVisitor;
+
+        impl<'de> Visitor<'de> for RawCommandVisitor {
+            type Value = RawCommand;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a command table")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawCommand, A::Error> {
+                let mut command = RawCommand { fields: Vec::new(), commands: None };
+                while let Some(key) = map.next_key::<Spanned<String>>()? {
+                    if key.get_ref() == "commands" {
+                        command.commands = Some(map.next_value()?);
+                    } else {
+                        command.fields.push((key, map.next_value()?));
+                    }
+                }
+                Ok(command)
+            }
+        }
+
+        deserializer.deserialize_map(RawCommandVisitor)
+    }
+}
+
+type RawSettings = BTreeMap<Spanned<String>, toml::Value>;
+
+/// Settings and commands that replace the ones of the config when the
+/// profile is selected. Settings are replaced one by one, commands all at once.
+#[derive(Deserialize)]
+struct RawProfile {
+    settings: Option<RawSettings>,
+    commands: Option<Vec<Spanned<RawCommand>>>,
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    settings: Option<RawSettings>,
+    #[serde(default)]
+    commands: Vec<Spanned<RawCommand>>,
+    #[serde(default)]
+    profiles: BTreeMap<Spanned<String>, RawProfile>,
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    diagnostics: Vec<Diagnostic>,
+    /// Start of the settings table.
+    settings_start: usize,
+    /// Start of each command that was kept, groups before their commands.
+    command_starts: Vec<usize>,
+    /// Number of position commands kept.
+    positions: usize,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, offset: usize, severity: Severity, message: String) {
+        let before = &self.source[..offset.min(self.source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+
+        self.diagnostics.push(Diagnostic { line, column, severity, message });
+    }
+
+    /// The first error found since there were `start` diagnostics.
+    fn first_error(&self, start: usize) -> Option<Diagnostic> {
+        self.diagnostics[start..].iter().find(|d| d.severity == Severity::Error).cloned()
+    }
+
+    /// Checks the settings. Problems of the table as a whole are reported at
+    /// `start`, or at its first key if `None`.
+    fn settings(&mut self, settings: Option<RawSettings>, start: Option<usize>) -> Settings {
+        let Some(settings) = settings else {
+            self.push(0, Severity::Error, "Missing [settings] table".to_string());
+            return Config::default().settings;
+        };
+
+        // The span of a `[table]` does not cover its header.
+        let start = start.or(settings.keys().map(|key| key.start()).min()).unwrap_or(0);
+        self.settings_start = start;
+        let mut table = toml::value::Table::new();
+        for (key, value) in settings {
+            if !SETTINGS.contains(&key.get_ref().as_str()) {
+                let message = format!(
+                    "Unknown setting \"{}\"{}",
+                    key.get_ref(),
+                    did_you_mean(key.get_ref(), SETTINGS.iter().copied())
+                );
+                self.push(key.start(), Severity::Warning, message);
+            }
+            table.insert(key.into_inner(), value);
+        }
+
+        match toml::Value::Table(table).try_into() {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.push(start, Severity::Error, format!("Invalid settings, using defaults: {e}"));
+                Config::default().settings
+            },
+        }
+    }
+
+    fn command(&mut self, command: Spanned<RawCommand>) -> Option<CfgCommand> {
+        let start = command.start();
+        let RawCommand { fields, commands } = command.into_inner();
+
+        let Some(&(name, variant, others)) = fields
+            .iter()
+            .find_map(|(key, _)| COMMANDS.iter().find(|(name, ..)| key.get_ref() == name))
+        else {
+            let message = match fields.first() {
+                Some((key, _)) => format!(
+                    "Unknown command \"{}\"{}",
+                    key.get_ref(),
+                    did_you_mean(key.get_ref(), COMMANDS.iter().map(|(name, ..)| *name))
+                ),
+                None => "Empty command".to_string(),
+            };
+            self.push(start, Severity::Error, message);
+            return None;
+        };
+
+        let mut table = toml::value::Table::new();
+        for (key, value) in fields {
+            if key.get_ref() == name || others.contains(&key.get_ref().as_str()) {
+                table.insert(key.into_inner(), value);
+            } else {
+                let message = format!(
+                    "Unknown field \"{}\" in {name} command{}",
+                    key.get_ref(),
+                    did_you_mean(key.get_ref(), others.iter().copied())
+                );
+                self.push(key.start(), Severity::Warning, message);
+            }
+        }
+
+        // `toml::Value` only reads unit variants, so the variant is handed
+        // over as the single key of a map.
+        let tagged = MapDeserializer::new(iter::once((variant, toml::Value::Table(table))));
+        match (CfgCommand::deserialize(MapAccessDeserializer::new(tagged)), commands) {
+            (Ok(CfgCommand::Group { label, .. }), Some(commands)) => {
+                self.command_starts.push(start);
+                Some(CfgCommand::Group {
+                    label,
+                    commands: commands.into_iter().filter_map(|c| self.command(c)).collect(),
+                })
+            },
+            (Ok(CfgCommand::Group { .. }), None) => {
+                self.push(start, Severity::Error, "Group without commands".to_string());
+                None
+            },
+            (Ok(mut command), commands) => {
+                if let CfgCommand::Position { slot, .. } = &mut command {
+                    *slot = self.positions;
+                    self.positions += 1;
+                }
+                if commands.is_some() {
+                    let message = format!("Unknown field \"commands\" in {name} command");
+                    self.push(start, Severity::Warning, message);
+                }
+                self.command_starts.push(start);
+                Some(command)
+            },
+            (Err(e), _) => {
+                self.push(start, Severity::Error, format!("Invalid {name} command: {e}"));
+                None
+            },
+        }
+    }
+}
+
+impl Checker<'_> {
+    fn config(
+        &mut self,
+        settings: Option<RawSettings>,
+        settings_start: Option<usize>,
+        commands: Vec<Spanned<RawCommand>>,
+    ) -> Config {
+        self.command_starts.clear();
+        self.positions = 0;
+        let config = Config {
+            settings: self.settings(settings, settings_start),
+            commands: commands.into_iter().filter_map(|c| self.command(c)).collect(),
+            profiles: Vec::new(),
+            profile: None,
+        };
+        self.hotkeys(config)
+    }
+
+    /// Checks the config and each of its profiles, and keeps the chosen one.
+    /// Also returns the first error in the settings and commands kept, as
+    /// errors in the other profiles don't affect them.
+    fn profiles(&mut self, raw: RawConfig, choice: ProfileChoice) -> (Config, Option<Diagnostic>) {
+        let RawConfig { settings, commands, profiles } = raw;
+        let names = profiles.keys().map(|name| name.get_ref().clone()).collect::<Vec<_>>();
+
+        let start = self.diagnostics.len();
+        let base = self.config(settings.clone(), None, commands.clone());
+        let base_error = self.first_error(start);
+        let settings_start = self.settings_start;
+
+        let (wanted, severity) = match choice {
+            ProfileChoice::Default => (base.settings.default_profile.clone(), Severity::Error),
+            ProfileChoice::Named(profile) => (profile.map(String::from), Severity::Warning),
+        };
+
+        let mut selected = None;
+        for (name, profile) in profiles {
+            let mut settings = settings.clone().unwrap_or_default();
+            let profile_settings = profile.settings.unwrap_or_default();
+            let start = profile_settings.keys().map(|key| key.start()).min();
+            for (key, value) in profile_settings {
+                // Replace the key too, so that problems point at the profile.
+                settings.remove(&key);
+                settings.insert(key, value);
+            }
+
+            let commands = profile.commands.unwrap_or_else(|| commands.clone());
+            let diagnostics_start = self.diagnostics.len();
+            let config = self.config(Some(settings), start, commands);
+            if wanted.as_ref() == Some(name.get_ref()) {
+                let error = self.first_error(diagnostics_start);
+                selected = Some((name.into_inner(), config, error));
+            }
+        }
+
+        let (mut config, error) = match (wanted, selected) {
+            (_, Some((name, mut config, error))) => {
+                config.profile = Some(name);
+                (config, error)
+            },
+            (Some(wanted), None) => {
+                let message = format!(
+                    "Unknown profile \"{wanted}\"{}",
+                    did_you_mean(&wanted, names.iter().map(String::as_str))
+                );
+                let start = self.diagnostics.len();
+                self.push(settings_start, severity, message);
+                let error = base_error.or_else(|| self.first_error(start));
+                (base, error)
+            },
+            (None, None) => (base, base_error),
+        };
+        config.profiles = names;
+        (config, error)
+    }
+
+    fn hotkeys(&mut self, config: Config) -> Config {
+        let fatal = config.settings.fatal_hotkey_conflicts;
+        let severity = if fatal { Severity::Error } else { Severity::Warning };
+
+        let conflicts = config.hotkey_conflicts();
+        for conflict in &conflicts {
+            let start = conflict.command.map_or(self.settings_start, |i| self.command_starts[i]);
+            self.push(start, severity, conflict.to_string());
+        }
+
+        if fatal && !conflicts.is_empty() {
+            let message = "Hotkey conflicts are fatal, using the default config".to_string();
+            self.push(self.settings_start, Severity::Error, message);
+            Config::default()
+        } else {
+            config
+        }
+    }
+}
+
+enum ProfileChoice<'a> {
+    Default,
+    /// A profile by name, or none at all.
+    Named(Option<&'a str>),
+}
+
+impl Config {
+    /// Parses a config with its default profile, keeping the settings and
+    /// commands that are valid. Returns the problems found, in order.
+    pub fn load(source: &str) -> (Config, Vec<Diagnostic>) {
+        let (config, diagnostics, _) = Config::load_with(source, ProfileChoice::Default);
+        (config, diagnostics)
+    }
+
+    /// Like [`Config::load`], with the settings and commands of `profile`
+    /// instead of the default profile. `None` loads no profile. The config is
+    /// an `Err` with the first error in its settings and commands, if any;
+    /// errors in the other profiles are only reported.
+    pub fn load_profile(
+        source: &str,
+        profile: Option<&str>,
+    ) -> (Result<Config, Diagnostic>, Vec<Diagnostic>) {
+        let (config, diagnostics, error) = Config::load_with(source, ProfileChoice::Named(profile));
+        (error.map_or(Ok(config), Err), diagnostics)
+    }
+
+    /// Loads the config, with the problems found and the first error in the
+    /// settings and commands loaded.
+    fn load_with(
+        source: &str,
+        choice: ProfileChoice,
+    ) -> (Config, Vec<Diagnostic>, Option<Diagnostic>) {
+        let mut checker = Checker {
+            source,
+            diagnostics: Vec::new(),
+            settings_start: 0,
+            command_starts: Vec::new(),
+            positions: 0,
+        };
+
+        let (config, error) = match toml::from_str::<RawConfig>(source) {
+            Ok(raw) => checker.profiles(raw, choice),
+            Err(e) => {
+                let (line, column) = e.line_col().map(|(l, c)| (l + 1, c + 1)).unwrap_or((1, 1));
+                let message = e.to_string();
+                checker.diagnostics.push(Diagnostic {
+                    line,
+                    column,
+                    severity: Severity::Error,
+                    message,
+                });
+                (Config::default(), checker.first_error(0))
+            },
+        };
+
+        // Profiles share commands and settings, and their problems.
+        checker.diagnostics.sort();
+        checker.diagnostics.dedup();
+        (config, checker.diagnostics, error)
+    }
+}
+
+/// Checks a config file without loading it.
+pub fn check_config(source: &str) -> Vec<Diagnostic> {
+    Config::load(source).1
+}
+
+/// Checks the config file read from `path` and prints its problems to
+/// stderr. Returns the number of errors.
+pub fn report_config(path: &Path, source: &str) -> usize {
+    let diagnostics = check_config(source);
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", path.display());
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    if errors == 0 {
+        eprintln!("{}: ok", path.display());
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        check_config(source).iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn test_shipped_config_is_valid() {
+        assert_eq!(
+            messages(include_str!("../../../jdsd_dsiii_practice_tool.toml")),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        assert_eq!(edit_distance("flga", "flag"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(
+            did_you_mean("hotkye", ["hotkey", "save"].into_iter()),
+            ", did you mean \"hotkey\"?"
+        );
+        assert_eq!(did_you_mean("xyz", ["hotkey", "save"].into_iter()), "");
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let source = r#"commands = [
+  { flag = "all_no_damage", hotkey = "1" },
+  { flga = "inf_stamina" },
+  { flag = "inf_stamnia", hotkey = "2" },
+  { flag = "deathcam", hotkye = "3" },
+  { group = "Render", commands = [
+    { flag = "rend_chr" },
+    { cycle_speed = "fast" },
+  ]},
+  { custom_flag = "F", base = "world_chr_mam", bit = 1 },
+  {},
+]
+
+[settings]
+log_level = "DEBUG"
+display = "0"
+hid = "rshift+0"
+"#;
+
+        assert_eq!(messages(source), vec![
+            "3:3: error: Unknown command \"flga\", did you mean \"flag\"?",
+            "4:3: error: Invalid flag command: \"inf_stamnia\" is not a valid flag specifier, did \
+             you mean \"inf_stamina\"? for key `flag`",
+            "5:24: warning: Unknown field \"hotkye\" in flag command, did you mean \"hotkey\"?",
+            "8:5: error: Invalid cycle_speed command: invalid type: string \"fast\", expected a \
+             sequence for key `cycle_speed`",
+            "10:3: error: Invalid custom_flag command: \"world_chr_mam\" is not a valid base \
+             address, did you mean \"world_chr_man\"? for key `base`",
+            "11:3: error: Empty command",
+            "17:1: warning: Unknown setting \"hid\", did you mean \"hide\"?",
+        ]);
+
+        let (config, _) = Config::load(source);
+        assert_eq!(config.commands.len(), 3);
+        assert!(config.settings.hide.is_none());
+        match &config.commands[2] {
+            CfgCommand::Group { label, commands } => {
+                assert_eq!(label, "Render");
+                assert_eq!(commands.len(), 1);
+            },
+            command => panic!("{command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let (config, diagnostics) =
+            Config::load("commands = [{ flag = \"ember\" }]\n[settings]\ndisplay = \"nope+0\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].severity), (3, Severity::Error));
+        assert_eq!(config.settings.display, Config::default().settings.display);
+        assert_eq!(config.commands.len(), 1);
+
+        let (config, diagnostics) = Config::load("commands = [\n{ flag = ");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(config.commands.is_empty());
+    }
+
+    #[test]
+    fn test_hotkey_conflicts() {
+        let source = r#"commands = [
+  { flag = "inf_stamina", hotkey = "1" },
+  { flag = "inf_focus", hotkey = "1" },
+]
+[settings]
+log_level = "DEBUG"
+display = "0"
+"#;
+
+        let (config, diagnostics) = Config::load(source);
+        assert_eq!(config.commands.len(), 2);
+        assert_eq!(
+            diagnostics.iter().map(|d| (d.line, d.column, d.severity)).collect::<Vec<_>>(),
+            vec![(3, 3, Severity::Warning)]
+        );
+
+        let (config, diagnostics) =
+            Config::load(&format!("{source}fatal_hotkey_conflicts = true\n"));
+        assert!(config.commands.is_empty());
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.severity)).collect::<Vec<_>>(), vec![
+            (3, Severity::Error),
+            (6, Severity::Error)
+        ]);
+    }
+}
@@ -16,6 +16,7 @@
 
 mod config;
 mod practice_tool;
+mod state;
 mod util;
 mod widgets;
 
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use hudhook::inject::Process;
 use hudhook::tracing::trace;
 use pkg_version::*;
@@ -65,7 +67,34 @@ fn perform_injection() -> Result<(), String> {
     Ok(())
 }
 
+/// Lints a config file without injecting anything. Defaults to the config
+/// next to the launcher.
+fn check_config(path: Option<String>) -> Result<(), String> {
+    let path = path.map(PathBuf::from).unwrap_or_else(|| {
+        let mut path = std::env::current_exe().unwrap();
+        path.pop();
+        path.push("jdsd_dsiii_practice_tool.toml");
+        path
+    });
+    let source = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+
+    match practice_tool_config::report_config(&path, &source) {
+        0 => Ok(()),
+        errors => Err(format!("{errors} errors found in {}", path.display())),
+    }
+}
+
 fn main() {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--check-config") {
+        if let Err(e) = check_config(args.next()) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tracing_subscriber::fmt()
         .with_max_level(LevelFilter::TRACE)
         .with_thread_ids(true)
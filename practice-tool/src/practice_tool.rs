@@ -1,25 +1,33 @@
 use std::fmt::Write;
+use std::mem;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
 use const_format::formatcp;
 use hudhook::tracing::metadata::LevelFilter;
-use hudhook::tracing::{debug, error, info};
+use hudhook::tracing::{debug, error, info, warn};
 use hudhook::{ImguiRenderLoop, RenderContext};
 use imgui::*;
 use libds3::prelude::*;
 use pkg_version::*;
+use practice_tool_config::{Config, Diagnostic, FontSize, IndicatorType, Severity};
 use practice_tool_core::crossbeam_channel::{self, Receiver, Sender};
 use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
 use tracing_subscriber::prelude::*;
 
-use crate::config::{Config, IndicatorType, Settings};
+use crate::config::{make_commands, remake_commands};
+use crate::state::{SharedState, State};
 use crate::util;
 
 const MAJOR: usize = pkg_version_major!();
 const MINOR: usize = pkg_version_minor!();
 const PATCH: usize = pkg_version_patch!();
 
+/// How often the config file is checked for changes and the state file is
+/// written.
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 struct FontIDs {
     small: FontId,
     normal: FontId,
@@ -36,7 +44,13 @@ enum UiState {
 }
 
 pub(crate) struct PracticeTool {
-    settings: Settings,
+    config: Config,
+    config_path: Option<PathBuf>,
+    config_modified: Option<SystemTime>,
+    config_checked: Instant,
+    state: SharedState,
+    state_path: Option<PathBuf>,
+    state_saved: State,
     pointers: PointerChains,
     version_label: String,
     widgets: Vec<Box<dyn Widget>>,
@@ -65,23 +79,18 @@ impl PracticeTool {
         hudhook::alloc_console().ok();
         log_panics::init();
 
-        fn load_config() -> Result<Config, String> {
-            let config_path = util::get_dll_path()
-                .map(|mut path| {
-                    path.pop();
-                    path.push("jdsd_dsiii_practice_tool.toml");
-                    path
-                })
-                .ok_or_else(|| "Couldn't find config file".to_string())?;
+        fn load_config() -> Result<(Config, Vec<Diagnostic>), String> {
+            let config_path =
+                config_path().ok_or_else(|| "Couldn't find config file".to_string())?;
             let config_content = std::fs::read_to_string(config_path)
                 .map_err(|e| format!("Couldn't read config file: {:?}", e))?;
             println!("{}", config_content);
-            Config::parse(&config_content).map_err(String::from)
+            Ok(Config::load(&config_content))
         }
 
-        let (config, config_err) = match load_config() {
-            Ok(config) => (config, None),
-            Err(e) => (Config::default(), Some(e)),
+        let (mut config, diagnostics, config_err) = match load_config() {
+            Ok((config, diagnostics)) => (config, diagnostics, None),
+            Err(e) => (Config::default(), Vec::new(), Some(e)),
         };
 
         let log_file = util::get_dll_path()
@@ -138,6 +147,8 @@ impl PracticeTool {
             debug!("{:?}", err);
         }
 
+        log_diagnostics(&diagnostics);
+
         if config.settings.log_level.inner() < LevelFilter::DEBUG || !config.settings.show_console {
             hudhook::free_console().ok();
         } else {
@@ -161,17 +172,45 @@ impl PracticeTool {
 
         let pointers = PointerChains::new();
         let version_label = {
-            let (maj, min, patch) = (*VERSION).into();
-            format!("Game Ver {}.{:02}.{}", maj, min, patch)
+            let (maj, min, patch) = *EXE_VERSION;
+            if *IS_KNOWN_VERSION {
+                format!("Game Ver {}.{:02}.{}", maj, min, patch)
+            } else {
+                format!("Game Ver {}.{:02}.{} (unknown)", maj, min, patch)
+            }
         };
-        let settings = config.settings.clone();
-        let widgets = config.make_commands(&pointers);
-
         let (log_tx, log_rx) = crossbeam_channel::unbounded();
+        if !diagnostics.is_empty() {
+            log_tx
+                .send(format!("{} problems in the config file, check the log", diagnostics.len()))
+                .ok();
+        }
+
+        let state_path = state_path();
+        let state_saved = match state_path.as_deref().map(State::read).transpose() {
+            Ok(state) => state.unwrap_or_default(),
+            Err(e) => {
+                error!("{}", e);
+                log_tx.send(format!("{e}, starting from the config")).ok();
+                State::default()
+            },
+        };
+        state_saved.apply_indicators(&mut config.settings.indicators);
+        let state = SharedState::new(&state_saved);
+
+        let widgets = make_commands(&config, &pointers, &state);
+        let config_path = config_path();
+        let config_modified = config_path.as_deref().and_then(modified);
         info!("Initialized");
 
         PracticeTool {
-            settings,
+            config,
+            config_path,
+            config_modified,
+            config_checked: Instant::now(),
+            state,
+            state_path,
+            state_saved,
             pointers,
             version_label,
             widgets,
@@ -191,10 +230,91 @@ impl PracticeTool {
         }
     }
 
+    /// Reloads the config if its file changed since it was last read.
+    fn reload_config(&mut self) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+
+        let config_modified = modified(path);
+        if config_modified.is_none() || config_modified == self.config_modified {
+            return;
+        }
+        self.config_modified = config_modified;
+
+        let profile = self.config.profile.clone();
+        self.load_config(profile.as_deref());
+    }
+
+    /// Writes the state file if the state changed since it was last written.
+    fn save_state(&mut self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+
+        let state = self.state.snapshot(&self.config.settings.indicators);
+        if state == self.state_saved {
+            return;
+        }
+
+        if let Err(e) = state.write(path) {
+            error!("{}", e);
+        }
+        self.state_saved = state;
+    }
+
+    /// Reads the config file again, with the settings and commands of
+    /// `profile`. The running config is kept if the new one has errors, but
+    /// not for errors in the other profiles.
+    /// Logging settings only apply on restart.
+    fn load_config(&mut self, profile: Option<&str>) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                self.log_tx.send(format!("Couldn't read config file: {e}")).ok();
+                return;
+            },
+        };
+
+        let (config, diagnostics) = Config::load_profile(&source, profile);
+        log_diagnostics(&diagnostics);
+
+        let mut config = match config {
+            Ok(config) => config,
+            Err(error) => {
+                self.log_tx.send(format!("Config not reloaded, {error}")).ok();
+                return;
+            },
+        };
+
+        // Keep the indicators as they were toggled, not as in the file.
+        self.state
+            .snapshot(&self.config.settings.indicators)
+            .apply_indicators(&mut config.settings.indicators);
+
+        let widgets = mem::take(&mut self.widgets);
+        self.widgets = remake_commands(&config, &self.config, widgets, &self.pointers, &self.state);
+        self.config = config;
+
+        let message = match &self.config.profile {
+            Some(profile) => format!("Config reloaded, profile \"{profile}\""),
+            None => "Config reloaded".to_string(),
+        };
+        self.log_tx.send(message).ok();
+    }
+
     fn render_visible(&mut self, ui: &imgui::Ui) {
+        let layout = self.config.settings.layout.tool.clone();
+        let placement = layout.place(ui.io().display_size);
+
         ui.window("##tool_window")
-            .position([16., 16.], Condition::Always)
-            .bg_alpha(0.8)
+            .position(placement.position, Condition::Always)
+            .position_pivot(placement.pivot)
+            .bg_alpha(layout.bg_alpha)
             .flags({
                 WindowFlags::NO_TITLE_BAR
                     | WindowFlags::NO_RESIZE
@@ -203,6 +323,10 @@ impl PracticeTool {
                     | WindowFlags::ALWAYS_AUTO_RESIZE
             })
             .build(|| {
+                if let Some(scale) = layout.font_scale {
+                    ui.set_window_font_scale(scale);
+                }
+
                 if !(ui.io().want_capture_keyboard && ui.is_any_item_active()) {
                     for w in self.widgets.iter_mut() {
                         w.interact(ui);
@@ -238,9 +362,13 @@ impl PracticeTool {
             ui.push_style_var(StyleVar::FrameBorderSize(0.)),
             ui.push_style_var(StyleVar::WindowBorderSize(0.)),
         ];
+        let layout = self.config.settings.layout.message.clone();
+        let placement = layout.place(ui.io().display_size);
+
         ui.window("##msg_window")
-            .position([16., ui.io().display_size[1] * 0.14], Condition::Always)
-            .bg_alpha(0.0)
+            .position(placement.position, Condition::Always)
+            .position_pivot(placement.pivot)
+            .bg_alpha(layout.bg_alpha)
             .flags({
                 WindowFlags::NO_TITLE_BAR
                     | WindowFlags::NO_RESIZE
@@ -249,6 +377,10 @@ impl PracticeTool {
                     | WindowFlags::ALWAYS_AUTO_RESIZE
             })
             .build(|| {
+                if let Some(scale) = layout.font_scale {
+                    ui.set_window_font_scale(scale);
+                }
+
                 ui.text("johndisandonato's Dark Souls III Practice Tool");
 
                 // ui.same_line();
@@ -279,7 +411,7 @@ impl PracticeTool {
                         );
                         ui.separator();
 
-                        for indicator in &mut self.settings.indicators {
+                        for indicator in &mut self.config.settings.indicators {
                             let label = match indicator.indicator {
                                 IndicatorType::GameVersion => "Game Version",
                                 IndicatorType::Position => "Player Position",
@@ -326,6 +458,64 @@ impl PracticeTool {
                         }
                     });
 
+                if !self.config.profiles.is_empty() {
+                    ui.same_line();
+
+                    if ui.small_button("Profile") {
+                        ui.open_popup("##profile_window");
+                    }
+
+                    let mut selected = None;
+
+                    ui.modal_popup_config("##profile_window")
+                        .resizable(false)
+                        .movable(false)
+                        .title_bar(false)
+                        .build(|| {
+                            let style = ui.clone_style();
+
+                            self.pointers.cursor_show.set(true);
+
+                            ui.text(
+                                "Profiles replace the commands and\nsettings of your config \
+                                 file.\n\nThey are defined in\njdsd_dsiii_practice_tool.toml.",
+                            );
+                            ui.separator();
+
+                            if ui
+                                .selectable_config("No profile")
+                                .selected(self.config.profile.is_none())
+                                .build()
+                            {
+                                selected = Some(None);
+                            }
+
+                            for profile in &self.config.profiles {
+                                if ui
+                                    .selectable_config(profile)
+                                    .selected(self.config.profile.as_ref() == Some(profile))
+                                    .build()
+                                {
+                                    selected = Some(Some(profile.clone()));
+                                }
+                            }
+
+                            ui.separator();
+
+                            let btn_close_width =
+                                ui.content_region_max()[0] - style.frame_padding[0] * 2.0;
+
+                            if ui.button_with_size("Close", [btn_close_width, 0.0]) {
+                                ui.close_current_popup();
+                                self.pointers.cursor_show.set(false);
+                            }
+                        });
+
+                    if let Some(profile) = selected {
+                        self.load_config(profile.as_deref());
+                    }
+                }
+
                 ui.same_line();
 
                 if ui.small_button("Help") {
@@ -352,7 +542,7 @@ impl PracticeTool {
                              your tool by editing\nthe jdsd_dsiii_practice_tool.toml file with\na \
                              text editor. If you break something,\njust download a fresh \
                              file!\n\nThank you for using my tool! <3\n",
-                            self.settings.display
+                            self.config.settings.display
                         ));
                         ui.separator();
                         ui.text("-- johndisandonato");
@@ -380,7 +570,7 @@ impl PracticeTool {
 
                 ui.new_line();
 
-                for indicator in &self.settings.indicators {
+                for indicator in &self.config.settings.indicators {
                     if !indicator.enabled {
                         continue;
                     }
@@ -512,10 +702,10 @@ impl PracticeTool {
     }
 
     fn render_logs(&mut self, ui: &imgui::Ui) {
-        let io = ui.io();
-
-        let [dw, dh] = io.display_size;
-        let [ww, wh] = [dw * 0.3, 14.0 * 6.];
+        let display_size = ui.io().display_size;
+        let layout = &self.config.settings.layout;
+        let placement = layout.logs.place(display_size);
+        let lines = layout.log_lines;
 
         let stack_tokens = vec![
             ui.push_style_var(StyleVar::WindowRounding(0.)),
@@ -524,8 +714,8 @@ impl PracticeTool {
         ];
 
         ui.window("##logs")
-            .position_pivot([1., 1.])
-            .position([dw * 0.95, dh * 0.8], Condition::Always)
+            .position_pivot(placement.pivot)
+            .position(placement.position, Condition::Always)
             .flags({
                 WindowFlags::NO_TITLE_BAR
                     | WindowFlags::NO_RESIZE
@@ -534,13 +724,17 @@ impl PracticeTool {
                     | WindowFlags::ALWAYS_AUTO_RESIZE
                     | WindowFlags::NO_INPUTS
             })
-            .size([ww, wh], Condition::Always)
-            .bg_alpha(0.0)
+            .size(layout.logs_size(display_size), Condition::Always)
+            .bg_alpha(layout.logs.bg_alpha)
             .build(|| {
-                for _ in 0..5 {
+                if let Some(scale) = layout.logs.font_scale {
+                    ui.set_window_font_scale(scale);
+                }
+
+                for _ in 0..lines + 2 {
                     ui.text("");
                 }
-                for l in self.log.iter().rev().take(3).rev() {
+                for l in self.log.iter().rev().take(lines).rev() {
                     ui.text(&l.1);
                 }
                 ui.set_scroll_here_y();
@@ -556,14 +750,10 @@ impl PracticeTool {
         let font_id = self
             .fonts
             .as_mut()
-            .map(|fonts| {
-                if width > 2000. {
-                    fonts.big
-                } else if width > 1200. {
-                    fonts.normal
-                } else {
-                    fonts.small
-                }
+            .map(|fonts| match FontSize::for_width(width) {
+                FontSize::Big => fonts.big,
+                FontSize::Normal => fonts.normal,
+                FontSize::Small => fonts.small,
             })
             .unwrap();
 
@@ -571,15 +761,50 @@ impl PracticeTool {
     }
 }
 
+fn config_path() -> Option<PathBuf> {
+    util::get_dll_path().map(|mut path| {
+        path.pop();
+        path.push("jdsd_dsiii_practice_tool.toml");
+        path
+    })
+}
+
+fn state_path() -> Option<PathBuf> {
+    util::get_dll_path().map(|mut path| {
+        path.pop();
+        path.push("jdsd_dsiii_practice_tool.state.json");
+        path
+    })
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn log_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            Severity::Error => error!("jdsd_dsiii_practice_tool.toml:{diagnostic}"),
+            Severity::Warning => warn!("jdsd_dsiii_practice_tool.toml:{diagnostic}"),
+        }
+    }
+}
+
 impl ImguiRenderLoop for PracticeTool {
     fn render(&mut self, ui: &mut imgui::Ui) {
         let font_token = self.set_font(ui);
 
-        let display = self.settings.display.is_pressed(ui);
-        let hide = self.settings.hide.map(|k| k.is_pressed(ui)).unwrap_or(false);
+        let display = self.config.settings.display.is_pressed(ui);
+        let hide = self.config.settings.hide.map(|k| k.is_pressed(ui)).unwrap_or(false);
 
         self.framecount += 1;
 
+        if self.config_checked.elapsed() > CONFIG_CHECK_INTERVAL {
+            self.config_checked = Instant::now();
+            self.reload_config();
+            self.save_state();
+        }
+
         if !ui.io().want_capture_keyboard && (display || hide) {
             self.ui_state = match (&self.ui_state, hide) {
                 (UiState::Hidden, _) => UiState::Closed,
@@ -0,0 +1,257 @@
+//! State changed while the tool runs that survives restarts: indicators,
+//! the item spawner selection and saved positions. It is kept in
+//! `jdsd_dsiii_practice_tool.state.json` next to the DLL, and applied over
+//! the config file.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use practice_tool_config::{Indicator, IndicatorType};
+use serde::{Deserialize, Serialize};
+
+use crate::widgets::item_spawn::{INFUSION_TYPES, MAX_DURABILITY, MAX_QTY, UPGRADES};
+
+const DEFAULT_ITEM: u32 = 0x007A1200;
+
+/// A position saved with a position command: x, y, z and angle.
+pub(crate) type SavedPosition = Arc<Mutex<Option<[f32; 4]>>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IndicatorState {
+    pub(crate) indicator: IndicatorType,
+    pub(crate) enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ItemSpawnerState {
+    pub(crate) item_id: u32,
+    pub(crate) qty: u32,
+    pub(crate) durability: u32,
+    /// Index in the upgrades of the item spawner.
+    pub(crate) upgrade: usize,
+    /// Index in the infusion types of the item spawner.
+    pub(crate) infusion_type: usize,
+}
+
+impl Default for ItemSpawnerState {
+    fn default() -> Self {
+        ItemSpawnerState {
+            item_id: DEFAULT_ITEM,
+            qty: 1,
+            durability: 100,
+            upgrade: 0,
+            infusion_type: 0,
+        }
+    }
+}
+
+impl ItemSpawnerState {
+    /// Brings values edited in the state file back in the ranges of the item
+    /// spawner.
+    fn clamped(self) -> Self {
+        ItemSpawnerState {
+            qty: self.qty.clamp(1, MAX_QTY),
+            durability: self.durability.min(MAX_DURABILITY),
+            upgrade: self.upgrade.min(UPGRADES.len() - 1),
+            infusion_type: self.infusion_type.min(INFUSION_TYPES.len() - 1),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct State {
+    /// Indicators in the order they are shown.
+    pub(crate) indicators: Vec<IndicatorState>,
+    pub(crate) item_spawner: ItemSpawnerState,
+    /// Saved positions, in the order of the position commands.
+    pub(crate) positions: Vec<Option<[f32; 4]>>,
+}
+
+impl State {
+    /// Reads the state file. A missing file is an empty state.
+    pub(crate) fn read(path: &Path) -> Result<State, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Couldn't parse state file: {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(State::default()),
+            Err(e) => Err(format!("Couldn't read state file: {e}")),
+        }
+    }
+
+    /// Writes the state file to a temporary file first, so that a crash never
+    /// leaves it half written.
+    pub(crate) fn write(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Couldn't serialize state: {e}"))?;
+        let tmp_path = path.with_extension("json.tmp");
+
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|e| format!("Couldn't write state file: {e}"))
+    }
+
+    /// Orders and toggles the indicators of the config as saved. Indicators
+    /// that were never saved keep their setting, after the saved ones.
+    pub(crate) fn apply_indicators(&self, indicators: &mut Vec<Indicator>) {
+        let mut saved = self
+            .indicators
+            .iter()
+            .filter_map(|state| {
+                let i = indicators.iter().position(|i| i.indicator == state.indicator)?;
+                let mut indicator = indicators.remove(i);
+                indicator.enabled = state.enabled;
+                Some(indicator)
+            })
+            .collect::<Vec<_>>();
+
+        saved.append(indicators);
+        *indicators = saved;
+    }
+}
+
+/// State shared with the widgets that change it.
+#[derive(Default)]
+pub(crate) struct SharedState {
+    item_spawner: Arc<Mutex<ItemSpawnerState>>,
+    positions: Mutex<Vec<SavedPosition>>,
+}
+
+impl SharedState {
+    pub(crate) fn new(state: &State) -> Self {
+        SharedState {
+            item_spawner: Arc::new(Mutex::new(state.item_spawner.clamped())),
+            positions: Mutex::new(
+                state.positions.iter().map(|&p| Arc::new(Mutex::new(p))).collect(),
+            ),
+        }
+    }
+
+    pub(crate) fn item_spawner(&self) -> Arc<Mutex<ItemSpawnerState>> {
+        Arc::clone(&self.item_spawner)
+    }
+
+    /// The position saved by the `slot`-th position command.
+    pub(crate) fn position(&self, slot: usize) -> SavedPosition {
+        let mut positions = self.positions.lock();
+        if positions.len() <= slot {
+            positions.resize_with(slot + 1, Default::default);
+        }
+        Arc::clone(&positions[slot])
+    }
+
+    /// The state to save, with the indicators as they are now.
+    pub(crate) fn snapshot(&self, indicators: &[Indicator]) -> State {
+        State {
+            indicators: indicators
+                .iter()
+                .map(|i| IndicatorState { indicator: i.indicator.clone(), enabled: i.enabled })
+                .collect(),
+            item_spawner: *self.item_spawner.lock(),
+            positions: self.positions.lock().iter().map(|p| *p.lock()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indicator(indicator: IndicatorType, enabled: bool) -> Indicator {
+        Indicator { indicator, enabled }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let shared = SharedState::new(&State::default());
+        shared.item_spawner().lock().qty = 5;
+        *shared.position(1).lock() = Some([1.0, 2.0, 3.0, 0.5]);
+
+        let state = shared.snapshot(&[indicator(IndicatorType::Fps, true)]);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+
+        assert_eq!(state.positions, [None, Some([1.0, 2.0, 3.0, 0.5])]);
+        assert_eq!(state.item_spawner.qty, 5);
+        assert_eq!(state.item_spawner.item_id, DEFAULT_ITEM);
+
+        let shared = SharedState::new(&state);
+        assert_eq!(*shared.position(1).lock(), Some([1.0, 2.0, 3.0, 0.5]));
+        assert_eq!(*shared.position(2).lock(), None);
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let state = serde_json::from_str::<State>(r#"{ "item_spawner": { "qty": 3 } }"#).unwrap();
+        assert_eq!(state.item_spawner, ItemSpawnerState { qty: 3, ..Default::default() });
+        assert!(state.indicators.is_empty());
+        assert!(state.positions.is_empty());
+    }
+
+    #[test]
+    fn test_item_spawner_out_of_range() {
+        let item_spawner =
+            r#"{ "qty": 0, "durability": 10000, "upgrade": 11, "infusion_type": 16 }"#;
+        let state = State {
+            item_spawner: serde_json::from_str(item_spawner).unwrap(),
+            ..Default::default()
+        };
+
+        assert_eq!(*SharedState::new(&state).item_spawner().lock(), ItemSpawnerState {
+            qty: 1,
+            durability: MAX_DURABILITY,
+            upgrade: UPGRADES.len() - 1,
+            infusion_type: INFUSION_TYPES.len() - 1,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_apply_indicators() {
+        let state = State {
+            indicators: vec![
+                IndicatorState { indicator: IndicatorType::Fps, enabled: true },
+                IndicatorState { indicator: IndicatorType::Animation, enabled: true },
+                IndicatorState { indicator: IndicatorType::Igt, enabled: false },
+            ],
+            ..Default::default()
+        };
+
+        let mut indicators = vec![
+            indicator(IndicatorType::GameVersion, true),
+            indicator(IndicatorType::Igt, true),
+            indicator(IndicatorType::Fps, false),
+        ];
+        state.apply_indicators(&mut indicators);
+
+        assert_eq!(
+            indicators.iter().map(|i| (i.indicator.clone(), i.enabled)).collect::<Vec<_>>(),
+            [
+                (IndicatorType::Fps, true),
+                (IndicatorType::Igt, false),
+                (IndicatorType::GameVersion, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_write() {
+        let path = std::env::temp_dir().join("jdsd_dsiii_practice_tool_test.state.json");
+        fs::remove_file(&path).ok();
+        assert_eq!(State::read(&path).unwrap(), State::default());
+
+        let state = State { positions: vec![Some([1.0, 2.0, 3.0, 4.0])], ..Default::default() };
+        state.write(&path).unwrap();
+        assert_eq!(State::read(&path).unwrap(), state);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        fs::write(&path, "{").unwrap();
+        assert!(State::read(&path).is_err());
+        fs::remove_file(&path).ok();
+    }
+}
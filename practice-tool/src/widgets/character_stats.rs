@@ -4,12 +4,12 @@ use practice_tool_core::widgets::stats_editor::{Datum, Stats, StatsEditor};
 use practice_tool_core::widgets::Widget;
 
 #[derive(Debug)]
-struct CharacterStatsEdit {
-    ptr: PointerChain<CharacterStats>,
+struct CharacterStatsEdit<M = ProcessMemory> {
+    ptr: PointerChain<CharacterStats, M>,
     stats: Option<CharacterStats>,
 }
 
-impl Stats for CharacterStatsEdit {
+impl<M: MemoryBackend> Stats for CharacterStatsEdit<M> {
     fn data(&mut self) -> Option<impl Iterator<Item = Datum>> {
         self.stats.as_mut().map(|s| {
             [
@@ -55,3 +55,35 @@ pub(crate) fn character_stats_edit(
         Some(key_close),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use libds3::memedit::FakeMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_character_stats() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_value(0x2044, [10i32, 11, 12, 13, 14, 15, 16, 17, 0, 0, 18, 20, 3000]);
+
+        let mut edit = CharacterStatsEdit { ptr: mem.pointer_chain(&[0x1000, 0x44]), stats: None };
+
+        edit.read();
+        let stats = edit.stats.as_mut().unwrap();
+        assert_eq!(stats.vigor, 10);
+        assert_eq!(stats.vitality, 18);
+        assert_eq!(stats.souls, 3000);
+
+        stats.strength = 40;
+        stats.souls = 0;
+        edit.write();
+
+        let written = mem.read_value::<[i32; 13]>(0x2044).unwrap();
+        assert_eq!(written, [10, 11, 12, 40, 14, 15, 16, 17, 0, 0, 18, 20, 0]);
+
+        edit.clear();
+        assert!(edit.stats.is_none());
+    }
+}
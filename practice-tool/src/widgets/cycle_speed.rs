@@ -1,28 +1,28 @@
 use std::cmp::Ordering;
 use std::fmt::Write;
 
-use libds3::memedit::PointerChain;
+use libds3::memedit::{MemoryBackend, PointerChain, ProcessMemory};
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::store_value::{ReadWrite, StoreValue};
 use practice_tool_core::widgets::Widget;
 
 #[derive(Debug)]
-struct CycleSpeed {
-    ptr: PointerChain<f32>,
+struct CycleSpeed<M = ProcessMemory> {
+    ptr: PointerChain<f32, M>,
     values: Vec<f32>,
     current: Option<f32>,
     label: String,
 }
 
-impl CycleSpeed {
-    fn new(values: &[f32], ptr: PointerChain<f32>) -> Self {
+impl<M: MemoryBackend> CycleSpeed<M> {
+    fn new(values: &[f32], ptr: PointerChain<f32, M>) -> Self {
         let mut values = values.to_vec();
         values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
         CycleSpeed { ptr, values, current: None, label: String::new() }
     }
 }
 
-impl ReadWrite for CycleSpeed {
+impl<M: MemoryBackend> ReadWrite for CycleSpeed<M> {
     fn read(&mut self) -> bool {
         self.current = self.ptr.read();
 
@@ -57,3 +57,38 @@ pub(crate) fn cycle_speed(
 ) -> Box<dyn Widget> {
     Box::new(StoreValue::new(CycleSpeed::new(values, ptr), key))
 }
+
+#[cfg(test)]
+mod tests {
+    use libds3::memedit::FakeMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_cycle_speed() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_value(0x2a58, 1.0f32);
+
+        let mut speed = CycleSpeed::new(&[5.0, 0.5, 2.0, 1.0], mem.pointer_chain(&[0x1000, 0xa58]));
+
+        assert!(speed.read());
+        assert_eq!(speed.label(), "Speed [1.0x]");
+
+        for expected in [2.0, 5.0, 0.5, 1.0] {
+            speed.write();
+            assert!(speed.read());
+            assert_eq!(mem.read_value::<f32>(0x2a58), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_cycle_speed_unreadable() {
+        let mem = FakeMemory::new();
+
+        let mut speed = CycleSpeed::new(&[0.5, 1.0], mem.pointer_chain(&[0x1000, 0xa58]));
+
+        assert!(!speed.read());
+        assert_eq!(speed.label(), "Speed");
+    }
+}
@@ -0,0 +1,91 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Write};
+
+use libds3::memedit::{MemoryBackend, PointerChain, ProcessMemory};
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::store_value::{ReadWrite, StoreValue};
+use practice_tool_core::widgets::Widget;
+
+#[derive(Debug)]
+struct CycleValue<T, M = ProcessMemory> {
+    name: String,
+    ptr: PointerChain<T, M>,
+    values: Vec<T>,
+    current: Option<T>,
+    label: String,
+}
+
+impl<T: Copy + PartialOrd, M: MemoryBackend> CycleValue<T, M> {
+    fn new(name: &str, values: &[T], ptr: PointerChain<T, M>) -> Self {
+        let mut values = values.to_vec();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        CycleValue { name: name.to_string(), ptr, values, current: None, label: String::new() }
+    }
+}
+
+impl<T: Copy + PartialOrd + Display, M: MemoryBackend> ReadWrite for CycleValue<T, M> {
+    fn read(&mut self) -> bool {
+        self.current = self.ptr.read();
+
+        self.label.clear();
+
+        match self.current {
+            Some(c) => write!(self.label, "{} [{}]", self.name, c).ok(),
+            None => write!(self.label, "{}", self.name).ok(),
+        };
+
+        self.current.is_some()
+    }
+
+    fn write(&mut self) {
+        let next = self
+            .current
+            .and_then(|current| self.values.iter().find(|&&x| x > current))
+            .or_else(|| self.values.first());
+
+        if let Some(&next) = next {
+            self.ptr.write(next);
+        }
+    }
+
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+pub(crate) fn cycle_value<T>(
+    name: &str,
+    values: &[T],
+    ptr: PointerChain<T>,
+    key: Option<Key>,
+) -> Box<dyn Widget>
+where
+    T: Copy + PartialOrd + Display + Send + Sync + 'static,
+{
+    Box::new(StoreValue::new(CycleValue::new(name, values, ptr), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use libds3::memedit::FakeMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_cycle_value() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_value(0x2010, 3u8);
+
+        let mut value = CycleValue::new("Value", &[5u8, 0, 3], mem.pointer_chain(&[0x1000, 0x10]));
+
+        assert!(value.read());
+        assert_eq!(value.label(), "Value [3]");
+
+        for expected in [5, 0, 3] {
+            value.write();
+            assert!(value.read());
+            assert_eq!(mem.read_value::<u8>(0x2010), Some(expected));
+        }
+    }
+}
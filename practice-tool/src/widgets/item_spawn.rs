@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::ffi::c_void;
 use std::fmt::Display;
+use std::sync::Arc;
 
 use imgui::sys::{
     igGetCursorPosX, igGetCursorPosY, igGetTreeNodeToLabelSpacing, igGetWindowPos, igIndent,
@@ -8,16 +9,18 @@ use imgui::sys::{
 };
 use imgui::{Condition, InputText, TreeNodeFlags};
 use libds3::memedit::Bitflag;
+use libds3::params::string_match;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use practice_tool_core::crossbeam_channel::Sender;
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer};
 
-const DEFAULT_ITEM: u32 = 0x007A1200;
+use crate::state::ItemSpawnerState;
 
-static INFUSION_TYPES: [(u32, &str); 16] = [
+pub(crate) static INFUSION_TYPES: [(u32, &str); 16] = [
     (0, "Normal"),
     (100, "Heavy"),
     (200, "Sharp"),
@@ -36,7 +39,7 @@ static INFUSION_TYPES: [(u32, &str); 16] = [
     (1500, "Hollow"),
 ];
 
-static UPGRADES: [(u32, &str); 11] = [
+pub(crate) static UPGRADES: [(u32, &str); 11] = [
     (0, "+0"),
     (1, "+1"),
     (2, "+2"),
@@ -50,6 +53,9 @@ static UPGRADES: [(u32, &str); 11] = [
     (10, "+10"),
 ];
 
+pub(crate) const MAX_QTY: u32 = 99;
+pub(crate) const MAX_DURABILITY: u32 = 9999;
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum ItemIDNode {
@@ -142,21 +148,6 @@ impl ItemIDNode {
     }
 }
 
-fn string_match(needle: &str, haystack: &str) -> bool {
-    let needle = needle.chars().flat_map(char::to_lowercase);
-    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
-
-    'o: for c in needle {
-        for d in &mut haystack {
-            if c == d {
-                continue 'o;
-            }
-        }
-        return false;
-    }
-    true
-}
-
 const ISP_TAG: &str = "##item-spawn";
 static ITEM_ID_TREE: Lazy<Vec<ItemIDNode>> =
     Lazy::new(|| serde_json::from_str(include_str!("item_ids.json")).unwrap());
@@ -172,11 +163,7 @@ pub(crate) struct ItemSpawner<'a> {
     label_load: String,
     label_close: String,
 
-    qty: u32,
-    item_id: u32,
-    durability: u32,
-    upgrade: usize,
-    infusion_type: usize,
+    selection: Arc<Mutex<ItemSpawnerState>>,
 
     filter_string: String,
     logs: Vec<String>,
@@ -190,6 +177,7 @@ impl ItemSpawner<'_> {
         sentinel: Bitflag<u8>,
         hotkey_load: Option<Key>,
         hotkey_close: Key,
+        selection: Arc<Mutex<ItemSpawnerState>>,
     ) -> Self {
         let label_load = if let Some(hotkey_load) = hotkey_load {
             format!("Spawn item ({hotkey_load})")
@@ -205,11 +193,7 @@ impl ItemSpawner<'_> {
             label_load,
             label_close,
             sentinel,
-            qty: 1,
-            durability: 100,
-            item_id: DEFAULT_ITEM,
-            upgrade: 0,
-            infusion_type: 0,
+            selection,
             filter_string: String::new(),
             logs: Vec::new(),
             item_id_tree: ITEM_ID_TREE.iter().map(ItemIDNodeRef::from).collect(),
@@ -222,23 +206,9 @@ impl ItemSpawner<'_> {
             return;
         }
 
-        let upgrade = UPGRADES[self.upgrade].0;
-        let infusion = INFUSION_TYPES[self.infusion_type].0;
-
-        let i = ItemSpawnInstance {
-            spawn_item_func_ptr: self.func_ptr as _,
-            map_item_man: self.map_item_man as _,
-            qty: self.qty,
-            durability: self.durability,
-            upgrade,
-            infusion,
-            item_id: self.item_id,
-        };
-
-        self.write_log(format!(
-            "Spawning {} #{} {} {}",
-            i.qty, self.item_id, UPGRADES[self.upgrade].1, INFUSION_TYPES[self.infusion_type].1,
-        ));
+        let selection = *self.selection.lock();
+        let i = ItemSpawnInstance::new(self.func_ptr, self.map_item_man, &selection);
+        self.write_log(spawn_log(&selection));
 
         unsafe {
             i.spawn();
@@ -294,39 +264,43 @@ impl Widget for ItemSpawner<'_> {
                         ITEM_ID_TREE.iter().filter_map(|n| n.filter(&self.filter_string)).collect();
                 }
             }
-            ui.child_window("##item-spawn-list").size([400., 200.]).build(|| {
-                for node in &self.item_id_tree {
-                    node.render(ui, &mut self.item_id, !self.filter_string.is_empty());
-                }
-            });
-
-            ui.set_next_item_width(195.);
-            ui.combo(
-                "##item-spawn-infusion-type",
-                &mut self.infusion_type,
-                &INFUSION_TYPES,
-                |(_, label)| Cow::Borrowed(label),
-            );
-
-            ui.same_line();
-            ui.set_next_item_width(195.);
-            ui.combo("##item-spawn-upgrade", &mut self.upgrade, &UPGRADES, |(_, label)| {
-                Cow::Borrowed(label)
-            });
-
-            ui.slider_config("Qty", 1, 99).build(&mut self.qty);
-            ui.slider_config("Dur", 0, 9999).build(&mut self.durability);
+            {
+                let mut selection = self.selection.lock();
+
+                ui.child_window("##item-spawn-list").size([400., 200.]).build(|| {
+                    for node in &self.item_id_tree {
+                        node.render(ui, &mut selection.item_id, !self.filter_string.is_empty());
+                    }
+                });
+
+                ui.set_next_item_width(195.);
+                ui.combo(
+                    "##item-spawn-infusion-type",
+                    &mut selection.infusion_type,
+                    &INFUSION_TYPES,
+                    |(_, label)| Cow::Borrowed(label),
+                );
+
+                ui.same_line();
+                ui.set_next_item_width(195.);
+                ui.combo(
+                    "##item-spawn-upgrade",
+                    &mut selection.upgrade,
+                    &UPGRADES,
+                    |(_, label)| Cow::Borrowed(label),
+                );
+
+                ui.slider_config("Qty", 1, MAX_QTY).build(&mut selection.qty);
+                ui.slider_config("Dur", 0, MAX_DURABILITY).build(&mut selection.durability);
+            }
+
             if ui.button_with_size(&self.label_load, [400., button_height]) {
                 self.spawn();
             }
 
             if ui.button_with_size("Clear", [400., button_height]) {
                 self.filter_string.clear();
-                self.qty = 1;
-                self.durability = 100;
-                self.item_id = DEFAULT_ITEM;
-                self.upgrade = 0;
-                self.infusion_type = 0;
+                *self.selection.lock() = ItemSpawnerState::default();
                 self.item_id_tree = ITEM_ID_TREE.iter().map(ItemIDNodeRef::from).collect();
             }
 
@@ -415,7 +389,29 @@ impl Display for ItemSpawnInstance {
     }
 }
 
+fn spawn_log(selection: &ItemSpawnerState) -> String {
+    format!(
+        "Spawning {} #{} {} {}",
+        selection.qty,
+        selection.item_id,
+        UPGRADES[selection.upgrade].1,
+        INFUSION_TYPES[selection.infusion_type].1,
+    )
+}
+
 impl ItemSpawnInstance {
+    fn new(func_ptr: usize, map_item_man: usize, selection: &ItemSpawnerState) -> Self {
+        ItemSpawnInstance {
+            spawn_item_func_ptr: func_ptr as _,
+            map_item_man: map_item_man as _,
+            qty: selection.qty,
+            durability: selection.durability,
+            upgrade: UPGRADES[selection.upgrade].0,
+            infusion: INFUSION_TYPES[selection.infusion_type].0,
+            item_id: selection.item_id,
+        }
+    }
+
     unsafe fn spawn(&self) {
         #[repr(C)]
         struct SpawnRequest {
@@ -438,3 +434,20 @@ impl ItemSpawnInstance {
         spawn_fn_ptr(*pp_map_item_man, &mut spawn_request as *mut _, &mut [0u32; 4] as *mut _);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{SharedState, State};
+
+    #[test]
+    fn test_spawn_out_of_range_state() {
+        let state: State = serde_json::from_str(r#"{"item_spawner":{"upgrade":999}}"#).unwrap();
+        let selection = *SharedState::new(&state).item_spawner().lock();
+
+        let i = ItemSpawnInstance::new(0, 0, &selection);
+        assert_eq!(i.upgrade, 10);
+        assert_eq!(i.infusion, 0);
+        assert_eq!(spawn_log(&selection), "Spawning 1 #8000000 +10 Normal");
+    }
+}
@@ -1,6 +1,7 @@
 pub(crate) mod character_stats;
 pub(crate) mod cycle_color;
 pub(crate) mod cycle_speed;
+pub(crate) mod cycle_value;
 pub(crate) mod flag;
 pub(crate) mod group;
 pub(crate) mod item_spawn;
@@ -1,17 +1,9 @@
 use std::mem;
 
+use practice_tool_config::OpenMenuKind;
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::store_value::{ReadWrite, StoreValue};
 use practice_tool_core::widgets::Widget;
-use serde::Deserialize;
-
-#[derive(Deserialize, Debug, Clone, Copy)]
-pub(crate) enum OpenMenuKind {
-    #[serde(rename = "travel")]
-    Travel,
-    #[serde(rename = "attune")]
-    Attune,
-}
 
 #[derive(Debug)]
 struct OpenMenu {
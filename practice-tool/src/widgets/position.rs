@@ -1,27 +1,29 @@
 use std::fmt::Write;
 
-use libds3::memedit::PointerChain;
+use libds3::memedit::{MemoryBackend, PointerChain, ProcessMemory};
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::nudge_position::NudgePositionStorage;
 use practice_tool_core::widgets::position::{Position, PositionStorage};
 use practice_tool_core::widgets::Widget;
 
-pub(super) struct SavePosition {
-    ptr_angle: PointerChain<f32>,
-    ptr_pos: PointerChain<[f32; 3]>,
-    saved_position: [f32; 4],
+use crate::state::SavedPosition;
+
+pub(super) struct SavePosition<M = ProcessMemory> {
+    ptr_angle: PointerChain<f32, M>,
+    ptr_pos: PointerChain<[f32; 3], M>,
+    saved_position: SavedPosition,
     label_current: String,
     label_stored: String,
     valid: bool,
     nudge: f32,
 }
 
-impl SavePosition {
-    pub(super) fn new(ptr: (PointerChain<f32>, PointerChain<[f32; 3]>), nudge: f32) -> Self {
+impl<M: MemoryBackend> SavePosition<M> {
+    pub(super) fn new(ptr: (PointerChain<f32, M>, PointerChain<[f32; 3], M>), nudge: f32) -> Self {
         Self {
             ptr_angle: ptr.0,
             ptr_pos: ptr.1,
-            saved_position: [0.0; 4],
+            saved_position: SavedPosition::default(),
             label_current: String::new(),
             label_stored: String::new(),
             valid: false,
@@ -30,10 +32,10 @@ impl SavePosition {
     }
 }
 
-impl PositionStorage for SavePosition {
+impl<M: MemoryBackend> PositionStorage for SavePosition<M> {
     fn save(&mut self) {
         if let (Some(pos), Some(angle)) = (self.ptr_pos.read(), self.ptr_angle.read()) {
-            self.saved_position = [pos[0], pos[1], pos[2], angle];
+            *self.saved_position.lock() = Some([pos[0], pos[1], pos[2], angle]);
             self.valid = true;
         } else {
             self.valid = false;
@@ -41,12 +43,10 @@ impl PositionStorage for SavePosition {
     }
 
     fn load(&mut self) {
-        self.ptr_pos.write([
-            self.saved_position[0],
-            self.saved_position[1],
-            self.saved_position[2],
-        ]);
-        self.ptr_angle.write(self.saved_position[3]);
+        if let Some([x, y, z, angle]) = *self.saved_position.lock() {
+            self.ptr_pos.write([x, y, z]);
+            self.ptr_angle.write(angle);
+        }
     }
 
     fn display_current(&mut self) -> &str {
@@ -76,7 +76,7 @@ impl PositionStorage for SavePosition {
     fn display_stored(&mut self) -> &str {
         self.label_stored.clear();
 
-        let [x, y, z, a] = self.saved_position;
+        let [x, y, z, a] = self.saved_position.lock().unwrap_or_default();
 
         write!(self.label_stored, "{:7.1} {:7.1} {:7.1} {:7.1}", x, y, z, a).ok();
 
@@ -88,7 +88,7 @@ impl PositionStorage for SavePosition {
     }
 }
 
-impl NudgePositionStorage for SavePosition {
+impl<M: MemoryBackend> NudgePositionStorage for SavePosition<M> {
     fn nudge_up(&mut self) {
         if let Some([x, y, z]) = self.ptr_pos.read() {
             self.ptr_pos.write([x, y + self.nudge, z]);
@@ -106,6 +106,77 @@ pub(crate) fn save_position(
     ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
     key_load: Option<Key>,
     key_save: Option<Key>,
+    saved_position: SavedPosition,
 ) -> Box<dyn Widget> {
-    Box::new(Position::new(SavePosition::new(ptr, 0.0), key_load, key_save))
+    let storage = SavePosition { saved_position, ..SavePosition::new(ptr, 0.0) };
+    Box::new(Position::new(storage, key_load, key_save))
+}
+
+#[cfg(test)]
+mod tests {
+    use libds3::memedit::FakeMemory;
+
+    use super::*;
+
+    fn setup(nudge: f32) -> (FakeMemory, SavePosition<FakeMemory>) {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_pointer(0x2040, 0x3000);
+        mem.write_pointer(0x3028, 0x4000);
+        mem.write_value(0x4074, 0.5f32);
+        mem.write_value(0x4080, [1.0f32, 2.0, 3.0]);
+
+        let ptr = (
+            mem.pointer_chain(&[0x1000, 0x40, 0x28, 0x74]),
+            mem.pointer_chain(&[0x1000, 0x40, 0x28, 0x80]),
+        );
+
+        (mem, SavePosition::new(ptr, nudge))
+    }
+
+    #[test]
+    fn test_save_load() {
+        let (mem, mut pos) = setup(0.0);
+
+        pos.save();
+        assert!(pos.is_valid());
+        assert_eq!(pos.display_stored(), "    1.0     2.0     3.0     0.5");
+
+        mem.write_value(0x4074, -1.0f32);
+        mem.write_value(0x4080, [10.0f32, 20.0, 30.0]);
+        assert_eq!(pos.display_current(), "   10.0    20.0    30.0    -1.0");
+
+        pos.load();
+        assert_eq!(mem.read_value::<[f32; 3]>(0x4080), Some([1.0, 2.0, 3.0]));
+        assert_eq!(mem.read_value::<f32>(0x4074), Some(0.5));
+    }
+
+    #[test]
+    fn test_load_unsaved() {
+        let (mem, mut pos) = setup(0.0);
+
+        pos.load();
+        assert_eq!(mem.read_value::<[f32; 3]>(0x4080), Some([1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_save_invalid() {
+        let (mem, mut pos) = setup(0.0);
+
+        mem.write_pointer(0x3028, 0);
+        pos.save();
+        assert!(!pos.is_valid());
+    }
+
+    #[test]
+    fn test_nudge() {
+        let (mem, mut pos) = setup(1.5);
+
+        pos.nudge_up();
+        assert_eq!(mem.read_value::<[f32; 3]>(0x4080), Some([1.0, 3.5, 3.0]));
+
+        pos.nudge_down();
+        pos.nudge_down();
+        assert_eq!(mem.read_value::<[f32; 3]>(0x4080), Some([1.0, 0.5, 3.0]));
+    }
 }
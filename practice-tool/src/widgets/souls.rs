@@ -1,22 +1,22 @@
-use libds3::memedit::PointerChain;
+use libds3::memedit::{MemoryBackend, PointerChain, ProcessMemory};
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::store_value::{ReadWrite, StoreValue};
 use practice_tool_core::widgets::Widget;
 
-struct Souls {
-    ptr: PointerChain<u32>,
+struct Souls<M = ProcessMemory> {
+    ptr: PointerChain<u32, M>,
     current: u32,
     amount: u32,
     label: String,
 }
 
-impl Souls {
-    fn new(amount: u32, ptr: PointerChain<u32>) -> Self {
+impl<M: MemoryBackend> Souls<M> {
+    fn new(amount: u32, ptr: PointerChain<u32, M>) -> Self {
         Self { ptr, current: 0, amount, label: format!("Add {amount} souls") }
     }
 }
 
-impl ReadWrite for Souls {
+impl<M: MemoryBackend> ReadWrite for Souls<M> {
     fn read(&mut self) -> bool {
         if let Some(current) = self.ptr.read() {
             self.current = current;
@@ -38,3 +38,32 @@ impl ReadWrite for Souls {
 pub(crate) fn souls(amount: u32, ptr: PointerChain<u32>, key: Option<Key>) -> Box<dyn Widget> {
     Box::new(StoreValue::new(Souls::new(amount, ptr), key))
 }
+
+#[cfg(test)]
+mod tests {
+    use libds3::memedit::FakeMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_add_souls() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0x2000);
+        mem.write_value(0x2074, 1234u32);
+
+        let mut souls = Souls::new(10000, mem.pointer_chain(&[0x1000, 0x74]));
+        assert!(souls.read());
+        souls.write();
+        assert_eq!(mem.read_value::<u32>(0x2074), Some(11234));
+        assert_eq!(souls.label(), "Add 10000 souls");
+    }
+
+    #[test]
+    fn test_add_souls_not_loaded() {
+        let mem = FakeMemory::new();
+        mem.write_pointer(0x1000, 0);
+
+        let mut souls = Souls::new(10000, mem.pointer_chain(&[0x1000, 0x74]));
+        assert!(!souls.read());
+    }
+}
@@ -1,9 +1,16 @@
-use std::env;
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
+use std::{env, fs};
 
-use practice_tool_tasks::codegen::{self, aob_direct, aob_indirect, aob_indirect_twice};
+use anyhow::{anyhow, bail, Context, Result};
+use heck::ToSnakeCase;
+use libds3::aob::{scan_matches, Aob, Section, BASE_ADDRESSES_AOBS};
+use pelite::pe64::{Pe, PeFile};
 use textwrap::dedent;
 
+type Version = (u32, u32, u32);
+
 fn patches_paths() -> impl Iterator<Item = PathBuf> {
     let base_path = PathBuf::from(
         env::var("DSIII_PATCHES_PATH").unwrap_or_else(|_| panic!("{}", dedent(r"
@@ -31,120 +38,256 @@ fn base_addresses_rs_path() -> PathBuf {
         .join("base_addresses.rs")
 }
 
-pub fn get_base_addresses() {
-    let aobs = &[
-        aob_indirect_twice(
-            "WorldChrMan",
-            &["48 8B 1D ?? ?? ?? 04 48 8B F9 48 85 DB ?? ?? 8B 11 85 D2 ?? ?? 8D"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "WorldChrManDbg",
-            &["48 8B 05 ?? ?? ?? ?? 66 0F 7F 44 24 40 48 85 C0"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "MenuMan",
-            &["48 89 15 ?? ?? ?? ?? 44 8b 82 ?? ?? ?? ?? 44 8b 8a ?? ?? ?? ?? 48 8b c3"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "BaseA",
-            &["48 8B 05 ?? ?? ?? ?? 48 85 C0 ?? ?? 48 8b 40 ?? C3"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice("BaseD", &["48 8B 0D ?? ?? ?? ?? 48 85 C9 74 26 44 8B"], 3, 7, true),
-        aob_indirect_twice(
-            "SprjDebugEvent",
-            &["48 8B 05 ?? ?? ?? ?? 41 0F B6 D8 8B EA"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "Debug",
-            &["C6 05 ?? ?? ?? ?? 01 48 8B 8C 24 ?? ?? ?? ?? 48 33 CC E8 ?? ?? ?? ?? 4C 8D 9C 24"],
-            2,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "Grend",
-            &["C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? 00 C6 05 ?? ?? ?? ?? \
-               00 4C 8B 05 ?? ?? ?? ?? 4C 89 44 24 58"],
-            2,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "BaseHBD",
-            &["48 8B 0D ?? ?? ?? ?? 41 B0 01 E8 ?? ?? ?? ?? 48 8B D3 48 8B CF"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "MapItemMan",
-            &["48 8B 0D ?? ?? ?? ?? 48 8B 89 ?? ?? ?? ?? E8 ?? ?? ?? ?? E9"],
-            3,
-            7,
-            true,
-        ),
-        aob_indirect_twice(
-            "SpawnItemFuncPtr",
-            // "E8 ?? ?? ?? ?? C7 44 24 20 00 01 00 00 4C 8D 4C 24 40 41 B8 2C 00 00 00 48 8B D3",
-            &["E8 ?? ?? ?? ?? C7 44 24 20 00 01 00 00 4C 8D 4C 24 40 41 B8"],
-            1,
-            5,
-            true,
-        ),
-        aob_indirect_twice(
-            "Param",
-            &["48 8B 0D ?? ?? ?? ?? 48 85 C9 74 0B 4C 8B C0 48 8B D7"],
-            3,
-            7,
-            true,
-        ),
-        aob_direct(
-            "FormatString",
-            &["3C 00 54 00 45 00 58 00 54 00 46 00 4F 00 52 00 4D 00 41 00 54 00"],
-            true,
-        ),
-        aob_direct(
-            "NoLogo",
-            &["E8 ?? ?? ?? FF 90 4D 8B C7 49 8B D4 48 8B C8 E8 ?? ?? ?? FF"],
-            true,
-        ),
-        aob_direct("CurrentTarget", &["48 8B 80 ?? ?? ?? ?? 48 8B 08 48 8B ?? 58"], true),
-        aob_direct(
-            "MenuTravel",
-            &["40 55 53 56 57 41 56 48 8D 6C 24 C9 48 81 EC 00 01 00 00 48 C7 45 97 FE FF FF FF"],
-            true,
-        ),
-        aob_direct(
-            "MenuAttune",
-            &["48 8D 45 0F 48 89 45 EF 48 8D 45 0F 48 89 45 F7 48 8D ?? ?? ?? ?? ?? 48 89 45 0F \
-               48 8D ?? ?? ?? ?? ?? 48 89 45 0F 48 8D ?? ?? ?? ?? ?? 48 89 45 17"],
-            true,
-        ),
-        aob_indirect("XA", &["48 8B 83 ?? ?? ?? ?? 48 8B 10 48 85 D2 ?? ?? 8B"], 3, false),
-        aob_indirect_twice(
-            "BaseFPS",
-            &["48 8B 0D ?? ?? ?? ?? 84 C0 74 32 48 85 C9 75 26 4C 8D 0D ?? ?? ?? ?? 4C 8D 05 ?? \
-               ?? ?? ?? 48 8D 0D ?? ?? ?? ??"],
-            3,
-            7,
-            true,
-        ),
-    ];
-
-    codegen::codegen_base_addresses(base_addresses_rs_path(), patches_paths(), aobs)
+/// Results of scanning a single executable.
+struct PatchScan {
+    version: Version,
+    /// First match of each signature, the one the runtime scan resolves, in the
+    /// order of `BASE_ADDRESSES_AOBS`.
+    addresses: Vec<(&'static Aob, usize)>,
+    failed: Vec<&'static str>,
+    ambiguous: Vec<(&'static str, Vec<usize>)>,
+}
+
+impl PatchScan {
+    fn report(&self) -> String {
+        let (maj, min, patch) = self.version;
+        let mut report = format!("{maj}.{min:02}.{patch}: ");
+
+        if self.failed.is_empty() && self.ambiguous.is_empty() {
+            report.push_str("ok");
+        }
+        if !self.failed.is_empty() {
+            write!(report, "not found: {}", self.failed.join(", ")).unwrap();
+        }
+        for (name, values) in &self.ambiguous {
+            let values = values.iter().map(|v| format!("{v:#x}")).collect::<Vec<_>>();
+            write!(report, "\n  {name} matched {} times: {}", values.len(), values.join(", "))
+                .unwrap();
+        }
+
+        report
+    }
+}
+
+fn scan_exe(path: &Path) -> Result<PatchScan> {
+    let bytes = fs::read(path).with_context(|| format!("Couldn't read {path:?}"))?;
+    let pe = PeFile::from_bytes(&bytes).with_context(|| format!("Couldn't parse {path:?}"))?;
+
+    let version = pe
+        .resources()
+        .ok()
+        .and_then(|resources| resources.version_info().ok())
+        .and_then(|version_info| version_info.fixed())
+        .map(|fixed| {
+            let v = fixed.dwFileVersion;
+            (v.Major as u32, v.Minor as u32, v.Patch as u32)
+        })
+        .ok_or_else(|| anyhow!("Couldn't read version info of {path:?}"))?;
+
+    let image_base = pe.optional_header().ImageBase as usize;
+    let sections = pe
+        .section_headers()
+        .iter()
+        .filter_map(|header| {
+            Some(Section {
+                name: header.name().ok()?,
+                address: image_base + header.VirtualAddress as usize,
+                bytes: pe.get_section_bytes(header).ok()?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut scan =
+        PatchScan { version, addresses: Vec::new(), failed: Vec::new(), ambiguous: Vec::new() };
+
+    for aob in BASE_ADDRESSES_AOBS {
+        let values = scan_matches(aob, &sections, image_base);
+        match values[..] {
+            [] => scan.failed.push(aob.name),
+            [value] => scan.addresses.push((aob, value)),
+            [value, ..] => {
+                scan.addresses.push((aob, value));
+                scan.ambiguous.push((aob.name, values));
+            },
+        }
+    }
+
+    Ok(scan)
+}
+
+fn version_variant((maj, min, patch): Version) -> String {
+    format!("V{maj}_{min:02}_{patch}")
+}
+
+fn base_addresses_const((maj, min, patch): Version) -> String {
+    format!("BASE_ADDRESSES_{maj}_{min:02}_{patch}")
+}
+
+fn codegen_base_addresses(scans: &BTreeMap<Version, PatchScan>) -> String {
+    let fields = BASE_ADDRESSES_AOBS
+        .iter()
+        .map(|aob| (aob.name.to_snake_case(), aob.relative))
+        .collect::<Vec<_>>();
+
+    let mut out = String::new();
+
+    writeln!(out, "// **********************************").unwrap();
+    writeln!(out, "// *** AUTOGENERATED, DO NOT EDIT ***").unwrap();
+    writeln!(out, "// **********************************").unwrap();
+
+    writeln!(out, "#[derive(Debug)]").unwrap();
+    writeln!(out, "pub struct BaseAddresses {{").unwrap();
+    for (field, _) in &fields {
+        writeln!(out, "    pub {field}: usize,").unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl BaseAddresses {{").unwrap();
+    // Only the module-relative fields are addresses; the others are offsets.
+    writeln!(out, "    pub const NAMES: &'static [&'static str] = &[").unwrap();
+    for (field, _) in fields.iter().filter(|(_, relative)| *relative) {
+        writeln!(out, "        \"{field}\",").unwrap();
+    }
+    writeln!(out, "    ];\n").unwrap();
+
+    writeln!(out, "    pub fn with_module_base_addr(self, base: usize) -> BaseAddresses {{")
+        .unwrap();
+    writeln!(out, "        BaseAddresses {{").unwrap();
+    for (field, relative) in &fields {
+        if *relative {
+            writeln!(out, "            {field}: self.{field} + base,").unwrap();
+        } else {
+            writeln!(out, "            {field}: self.{field},").unwrap();
+        }
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}\n").unwrap();
+
+    writeln!(out, "    pub fn get(&self, name: &str) -> Option<usize> {{").unwrap();
+    writeln!(out, "        match name {{").unwrap();
+    for (field, _) in fields.iter().filter(|(_, relative)| *relative) {
+        writeln!(out, "            \"{field}\" => Some(self.{field}),").unwrap();
+    }
+    writeln!(out, "            _ => None,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "#[derive(Clone, Copy)]").unwrap();
+    writeln!(out, "pub enum Version {{").unwrap();
+    for &version in scans.keys() {
+        writeln!(out, "    {},", version_variant(version)).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "pub const VERSIONS: &[Version] = &[").unwrap();
+    for &version in scans.keys() {
+        writeln!(out, "    Version::{},", version_variant(version)).unwrap();
+    }
+    writeln!(out, "];\n").unwrap();
+
+    writeln!(out, "impl From<(u32, u32, u32)> for Version {{").unwrap();
+    writeln!(out, "    fn from(v: (u32, u32, u32)) -> Self {{").unwrap();
+    writeln!(out, "        match v {{").unwrap();
+    for &version @ (maj, min, patch) in scans.keys() {
+        writeln!(
+            out,
+            "            ({maj}, {min}, {patch}) => Version::{},",
+            version_variant(version)
+        )
+        .unwrap();
+    }
+    writeln!(out, "            (maj, min, patch) => {{").unwrap();
+    writeln!(
+        out,
+        "                log::error!(\"Unrecognized version {{maj}}.{{min:02}}.{{patch}}\");"
+    )
+    .unwrap();
+    writeln!(out, "                panic!()").unwrap();
+    writeln!(out, "            }},").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl From<Version> for (u32, u32, u32) {{").unwrap();
+    writeln!(out, "    fn from(v: Version) -> Self {{").unwrap();
+    writeln!(out, "        match v {{").unwrap();
+    for &version @ (maj, min, patch) in scans.keys() {
+        writeln!(
+            out,
+            "            Version::{} => ({maj}, {min}, {patch}),",
+            version_variant(version)
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl From<Version> for BaseAddresses {{").unwrap();
+    writeln!(out, "    fn from(v: Version) -> Self {{").unwrap();
+    writeln!(out, "        match v {{").unwrap();
+    for &version in scans.keys() {
+        writeln!(
+            out,
+            "            Version::{} => {},",
+            version_variant(version),
+            base_addresses_const(version)
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    write!(out, "}}").unwrap();
+
+    for (&version, scan) in scans {
+        writeln!(out, "\n").unwrap();
+        writeln!(
+            out,
+            "pub const {}: BaseAddresses = BaseAddresses {{",
+            base_addresses_const(version)
+        )
+        .unwrap();
+        for (aob, value) in &scan.addresses {
+            writeln!(out, "    {}: {value:#x},", aob.name.to_snake_case()).unwrap();
+        }
+        write!(out, "}};").unwrap();
+    }
+    writeln!(out).unwrap();
+
+    out
+}
+
+/// Scans every patch in `DSIII_PATCHES_PATH` and regenerates
+/// `base_addresses.rs`. Patterns that fail to match on any patch abort the
+/// generation; patterns that match more than once are only reported.
+pub fn get_base_addresses() -> Result<()> {
+    let mut scans = BTreeMap::new();
+
+    for path in patches_paths() {
+        let scan = scan_exe(&path)?;
+        eprintln!("{}", scan.report());
+
+        if let Some(prev) = scans.insert(scan.version, scan) {
+            let (maj, min, patch) = prev.version;
+            bail!("Version {maj}.{min:02}.{patch} found more than once");
+        }
+    }
+
+    let failed = scans
+        .values()
+        .filter(|scan| !scan.failed.is_empty())
+        .map(|scan| {
+            let (maj, min, patch) = scan.version;
+            format!("{maj}.{min:02}.{patch} ({})", scan.failed.join(", "))
+        })
+        .collect::<Vec<_>>();
+
+    if !failed.is_empty() {
+        bail!("Some patterns were not found, base addresses not generated: {}", failed.join("; "));
+    }
+
+    fs::write(base_addresses_rs_path(), codegen_base_addresses(&scans))?;
+
+    Ok(())
 }
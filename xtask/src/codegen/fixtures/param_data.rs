@@ -0,0 +1,103 @@
+// **********************************
+// *** AUTOGENERATED, DO NOT EDIT ***
+// **********************************
+use std::collections::HashMap;
+use std::ffi::c_void;
+
+use macro_param::ParamStruct;
+use once_cell::sync::Lazy;
+
+use crate::prelude::*;
+
+unsafe fn get_vtable<T: ParamStruct>() -> ParamVTableEntry {
+    ParamVTableEntry {
+        visit: Box::new(|ptr, v| {
+            if let Some(r) = (ptr as *mut T).as_mut() {
+                r.visit(&mut *v);
+            }
+        }),
+        fields: T::FIELDS,
+        size: std::mem::size_of::<T>(),
+    }
+}
+
+type BoxedVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamVisitor) + Send + Sync>;
+
+pub struct ParamVTableEntry {
+    pub visit: BoxedVisitorLambda,
+    pub fields: &'static [FieldInfo],
+    pub size: usize,
+}
+
+pub static PARAM_VTABLE: Lazy<HashMap<String, ParamVTableEntry>> = Lazy::new(|| {
+    [
+        ("AtkParam_Npc".to_string(), unsafe { get_vtable::<AtkParam_Npc>() }),
+        ("AtkParam_Pc".to_string(), unsafe { get_vtable::<AtkParam_Pc>() }),
+        ("SpEffectParam".to_string(), unsafe { get_vtable::<SpEffectParam>() }),
+    ]
+    .into_iter()
+    .collect()
+});
+#[derive(ParamStruct, Debug)]
+#[repr(C)]
+#[param(size = 0x16)]
+pub struct AtkParam_Npc {
+    /// Hit Radius 0
+    pub hit0_radius: f32,
+    /// Physical Attack Power, applied before the motion values of the attack
+    /// and any buffs coming from special effects
+    pub atk_phys: i16,
+    pub ty: u8,
+    #[bitflag(disableGuard, 0)]
+    #[bitflag(IsArrowAtk, 1)]
+    #[bitflag(field0x81, 2)]
+    #[bitflag(field0x81_0, 3)]
+    #[bitflag(isChargeAtk, 4)]
+    pub bitfield0: u8,
+    #[padding]
+    pub pad1: [u8; 2],
+    pub atk_phys_0: i32,
+    pub name: [u16; 4],
+}
+
+#[derive(ParamStruct, Debug)]
+#[repr(C)]
+#[param(size = 0x16)]
+pub struct AtkParam_Pc {
+    /// Hit Radius 0
+    pub hit0_radius: f32,
+    /// Physical Attack Power, applied before the motion values of the attack
+    /// and any buffs coming from special effects
+    pub atk_phys: i16,
+    pub ty: u8,
+    #[bitflag(disableGuard, 0)]
+    #[bitflag(IsArrowAtk, 1)]
+    #[bitflag(field0x81, 2)]
+    #[bitflag(field0x81_0, 3)]
+    #[bitflag(isChargeAtk, 4)]
+    pub bitfield0: u8,
+    #[padding]
+    pub pad1: [u8; 2],
+    pub atk_phys_0: i32,
+    pub name: [u16; 4],
+}
+
+#[derive(ParamStruct, Debug)]
+#[repr(C)]
+#[param(size = 0x17)]
+pub struct SpEffectParam {
+    /// Duration
+    pub effect_endurance: f32,
+    pub replace_sp_effect_id: i32,
+    pub cycle_occurrence_sp_effect_id: i32,
+    pub atk_param_id: i32,
+    pub state_info: u8,
+    #[bitflag(effectTargetSelf, 0)]
+    #[bitflag(effectTargetFriend, 1)]
+    pub bitfield0: u8,
+    #[bitflag(vowType0, 0)]
+    #[bitflag(vowType1, 1)]
+    pub bitfield1: u16,
+    #[padding]
+    pub pad3: [u8; 3],
+}
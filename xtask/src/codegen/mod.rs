@@ -1,11 +1,16 @@
 use anyhow::Result;
 
 mod aob_scans;
+mod paramdef;
 mod params;
 
 pub(crate) fn codegen() -> Result<()> {
-    aob_scans::get_base_addresses();
+    aob_scans::get_base_addresses()?;
     params::codegen()?;
 
     Ok(())
 }
+
+pub(crate) fn codegen_base_addresses() -> Result<()> {
+    aob_scans::get_base_addresses()
+}
@@ -0,0 +1,641 @@
+//! Param structs, enum names and references out of Paramdex's paramdefs.
+//! Credits: Soulsmodding community's Paramdex.
+//!
+//! Reads `DS3/Defs/*.xml` for the layouts and `DS3/Meta/*.xml` for the
+//! names of enum values and the fields referring to other params, and
+//! writes them out already formatted with the repository's rustfmt config.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// Params whose paramdef has a different name, as slugs.
+const DEF_CORRECTIONS: &[(&str, &str)] = &[
+    ("atkparampc", "atkparam"),
+    ("atkparamnpc", "atkparam"),
+    ("behaviorparampc", "behaviorparam"),
+    ("bullet", "bulletparam"),
+    ("ceremony", "ceremonyparam"),
+    ("charainitparam", "characterinitparam"),
+    ("calccorrectgraph", "caclcorrectgraph"),
+    ("hpestusflaskrecoveryparam", "estusflaskrecoveryparam"),
+    ("mpestusflaskrecoveryparam", "estusflaskrecoveryparam"),
+    ("lodparam", "lodbank"),
+    ("lodparamps", "lodbank"),
+    ("lodparamxb", "lodbank"),
+    ("magic", "magicparam"),
+    ("menupropertylayoutparam", "menupropertylayout"),
+    ("menupropertyspecparam", "menupropertyspec"),
+    ("menuvaluetableparam", "menuvaluetablespec"),
+    ("multihpestusflaskbonusparam", "multiestusflaskbonusparam"),
+    ("multimpestusflaskbonusparam", "multiestusflaskbonusparam"),
+    ("newmenucolortableparam", "menuparamcolortable"),
+    ("throwparam", "throwinfobank"),
+    ("wind", "windparam"),
+];
+
+const HEADER: &str = r#"// **********************************
+// *** AUTOGENERATED, DO NOT EDIT ***
+// **********************************
+use std::collections::HashMap;
+use std::ffi::c_void;
+
+use macro_param::ParamStruct;
+use once_cell::sync::Lazy;
+
+use crate::prelude::*;
+
+unsafe fn get_vtable<T: ParamStruct>() -> ParamVTableEntry {
+    ParamVTableEntry {
+        visit: Box::new(|ptr, v| {
+            if let Some(r) = (ptr as *mut T).as_mut() {
+                r.visit(&mut *v);
+            }
+        }),
+        fields: T::FIELDS,
+        size: std::mem::size_of::<T>(),
+    }
+}
+
+type BoxedVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamVisitor) + Send + Sync>;
+
+pub struct ParamVTableEntry {
+    pub visit: BoxedVisitorLambda,
+    pub fields: &'static [FieldInfo],
+    pub size: usize,
+}
+
+pub static PARAM_VTABLE: Lazy<HashMap<String, ParamVTableEntry>> = Lazy::new(|| {
+    ["#;
+
+const MAX_WIDTH: usize = 100;
+const COMMENT_WIDTH: usize = 80;
+
+static DEF_ARRAY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\w+)\s+(\w+)\[(\d+)\]").unwrap());
+static DEF_BITFIELD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\w+)\s+(\w+):(\d+)").unwrap());
+static DEF_BASIC_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\w+)\s+(\w+)").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Normal,
+    Array(usize),
+    Bitfield(usize),
+}
+
+/// A field as declared in a paramdef, e.g. `u8 disableGuard:1`.
+#[derive(Debug, Clone)]
+struct Field {
+    /// The name in the paramdef, used to match the metadata.
+    def_name: String,
+    /// The name, with a suffix if it is a duplicate.
+    name: String,
+    ty: &'static str,
+    kind: FieldKind,
+    padding: bool,
+    display_name: Option<String>,
+}
+
+/// A struct field: either a paramdef field, or consecutive bitfields
+/// filling up an integer, of which single bits are exposed as flags.
+#[derive(Debug, Clone)]
+enum Item {
+    Field(Field),
+    Bitfield { name: String, ty: &'static str, flags: Vec<(usize, String)> },
+}
+
+/// The layout of a param struct and the metadata of its fields.
+#[derive(Debug)]
+pub(crate) struct ParamLayout {
+    pub(crate) name: String,
+    items: Vec<Item>,
+    /// Attributes of the fields in the metadata, by paramdef name.
+    meta: HashMap<String, HashMap<String, String>>,
+}
+
+/// Enum value names by value, by enum name.
+pub(crate) type Enums = BTreeMap<String, BTreeMap<String, String>>;
+
+fn to_snake_case(s: &str) -> String {
+    // Runs of capitals after the first character start a new word
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 && (i == 1 || !chars[i - 1].is_ascii_uppercase()) {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+
+    let mut snake = String::with_capacity(out.len());
+    for c in out.chars() {
+        if !(c == '_' && snake.ends_with('_')) {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn to_slug(s: &str) -> String {
+    s.chars().filter(char::is_ascii_alphabetic).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn fix_name(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field{name}")
+    } else if name == "type" {
+        "ty".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn rust_type(ty: &str) -> Result<&'static str> {
+    Ok(match ty {
+        "s8" => "i8",
+        "u8" | "fixstr" | "dummy8" => "u8",
+        "s16" => "i16",
+        "u16" | "fixstrW" => "u16",
+        "s32" => "i32",
+        "u32" => "u32",
+        "f32" => "f32",
+        ty => bail!("Unknown paramdef type {ty}"),
+    })
+}
+
+fn type_size(ty: &str) -> usize {
+    match ty {
+        "i8" | "u8" => 1,
+        "i16" | "u16" => 2,
+        _ => 4,
+    }
+}
+
+/// Renames fields whose snake case name was already taken, with a suffix
+/// counting the renames.
+fn dedup_names<'a>(names: impl Iterator<Item = &'a mut String>) {
+    let mut seen = HashSet::new();
+    let mut idx = 0;
+    for name in names {
+        let snake = to_snake_case(name);
+        if seen.contains(&snake) {
+            write!(name, "_{idx}").unwrap();
+            idx += 1;
+        }
+        seen.insert(snake);
+    }
+}
+
+fn parse_xml(source: &str) -> Result<roxmltree::Document<'_>> {
+    roxmltree::Document::parse(source.trim_start_matches('\u{feff}')).map_err(|e| anyhow!("{e}"))
+}
+
+impl Field {
+    fn parse(def: &str, display_name: Option<String>) -> Result<Field> {
+        let (captures, kind) = if let Some(c) = DEF_ARRAY_RE.captures(def) {
+            let len = c[3].parse()?;
+            (c, FieldKind::Array(len))
+        } else if let Some(c) = DEF_BITFIELD_RE.captures(def) {
+            let bits = c[3].parse()?;
+            (c, FieldKind::Bitfield(bits))
+        } else if let Some(c) = DEF_BASIC_RE.captures(def) {
+            (c, FieldKind::Normal)
+        } else {
+            bail!("Couldn't parse: {def}");
+        };
+
+        Ok(Field {
+            def_name: captures[2].to_string(),
+            name: captures[2].to_string(),
+            ty: rust_type(&captures[1])?,
+            kind,
+            padding: def.starts_with("dummy8"),
+            display_name,
+        })
+    }
+
+    fn rust_name(&self) -> String {
+        fix_name(&to_snake_case(&self.name))
+    }
+}
+
+impl Item {
+    fn name(&mut self) -> &mut String {
+        match self {
+            Item::Field(field) => &mut field.name,
+            Item::Bitfield { name, .. } => name,
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Item::Field(Field { ty, kind: FieldKind::Array(len), .. }) => type_size(ty) * len,
+            Item::Field(Field { ty, .. }) | Item::Bitfield { ty, .. } => type_size(ty),
+        }
+    }
+}
+
+/// Groups consecutive bitfields by the integers they fill up. Padding bits
+/// are often declared as a single wide field, so only single bits become
+/// flags. Bitfields that don't exactly fill up their integer are an error,
+/// as the layout of the rest of the param would be off.
+fn group_bitfields(param: &str, fields: Vec<Field>) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    let mut bitfield: Vec<(usize, Field)> = Vec::new();
+    let mut bits = 0;
+    let mut bitfield_idx = 0;
+
+    for field in fields {
+        let FieldKind::Bitfield(width) = field.kind else {
+            if let Some((_, open)) = bitfield.first() {
+                bail!("{param}: bitfield {} is not filled up before {}", open.name, field.name);
+            }
+            items.push(Item::Field(field));
+            continue;
+        };
+
+        let ty = field.ty;
+        if let Some((_, open)) = bitfield.first().filter(|(_, open)| open.ty != ty) {
+            bail!("{param}: bitfield {} is not filled up before {}", open.name, field.name);
+        }
+
+        bits += width;
+        if bits > type_size(ty) * 8 {
+            bail!("{param}: bitfield {} overflows its {ty}", field.name);
+        }
+
+        bitfield.push((bits - width, field));
+        if bits == type_size(ty) * 8 {
+            dedup_names(bitfield.iter_mut().map(|(_, f)| &mut f.name));
+            let flags = bitfield
+                .drain(..)
+                .filter(|(_, f)| f.kind == FieldKind::Bitfield(1))
+                .map(|(bit, f)| (bit, f.name))
+                .collect();
+            items.push(Item::Bitfield { name: format!("bitfield{bitfield_idx}"), ty, flags });
+            bits = 0;
+            bitfield_idx += 1;
+        }
+    }
+
+    if let Some((_, open)) = bitfield.first() {
+        bail!("{param}: bitfield {} is not filled up at the end of the param", open.name);
+    }
+
+    Ok(items)
+}
+
+impl ParamLayout {
+    /// Reads a paramdef, and the metadata of its fields if any.
+    pub(crate) fn new(name: &str, def: &str, meta: Option<&str>) -> Result<ParamLayout> {
+        let doc = parse_xml(def)?;
+        let fields = doc
+            .root_element()
+            .children()
+            .find(|n| n.has_tag_name("Fields"))
+            .ok_or_else(|| anyhow!("No Fields"))?
+            .children()
+            .filter(|n| n.is_element())
+            .map(|n| {
+                let def = n.attribute("Def").ok_or_else(|| anyhow!("Field without Def"))?;
+                let display_name = n
+                    .children()
+                    .find(|c| c.has_tag_name("DisplayName"))
+                    .and_then(|c| c.text())
+                    .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|s| !s.is_empty());
+                Field::parse(def, display_name)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut items = group_bitfields(name, fields)?;
+        dedup_names(items.iter_mut().map(Item::name));
+
+        let meta = match meta {
+            Some(meta) => parse_xml(meta)?
+                .root_element()
+                .children()
+                .filter(|n| n.has_tag_name("Field"))
+                .flat_map(|n| n.children().filter(|n| n.is_element()))
+                .map(|n| {
+                    let attributes =
+                        n.attributes().map(|a| (a.name().to_string(), a.value().to_string()));
+                    (n.tag_name().name().to_string(), attributes.collect())
+                })
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        Ok(ParamLayout { name: name.to_string(), items, meta })
+    }
+
+    fn size(&self) -> usize {
+        self.items.iter().map(Item::size).sum()
+    }
+
+    /// An attribute of the fields in the metadata, by Rust field name.
+    fn meta_attributes(&self, attribute: &str) -> BTreeMap<String, &str> {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Field(field) if field.kind == FieldKind::Normal => Some(field),
+                _ => None,
+            })
+            .filter_map(|field| {
+                let value = self.meta.get(&field.def_name)?.get(attribute)?;
+                (!value.is_empty()).then(|| (field.rust_name(), value.as_str()))
+            })
+            .collect()
+    }
+
+    /// Value names of the enum fields.
+    pub(crate) fn enums(&self, enums: &Enums) -> BTreeMap<String, BTreeMap<String, String>> {
+        self.meta_attributes("Enum")
+            .into_iter()
+            .filter_map(|(field, name)| Some((field, enums.get(name)?.clone())))
+            .collect()
+    }
+
+    /// Params referred to by the reference fields. Refs look like
+    /// `SpEffectParam,Magic(category=1)`: keeps the params that exist,
+    /// without their conditions.
+    pub(crate) fn refs(&self, param_names: &HashSet<&str>) -> BTreeMap<String, Vec<String>> {
+        self.meta_attributes("Refs")
+            .into_iter()
+            .filter_map(|(field, refs)| {
+                let mut targets: Vec<String> = Vec::new();
+                for target in refs.split(',').map(|r| r.split('(').next().unwrap_or(r).trim()) {
+                    if param_names.contains(target) && !targets.iter().any(|t| t == target) {
+                        targets.push(target.to_string());
+                    }
+                }
+                (!targets.is_empty()).then_some((field, targets))
+            })
+            .collect()
+    }
+
+    fn write_struct(&self, out: &mut String) {
+        writeln!(out, "#[derive(ParamStruct, Debug)]").unwrap();
+        writeln!(out, "#[repr(C)]").unwrap();
+        writeln!(out, "#[param(size = {:#x})]", self.size()).unwrap();
+        writeln!(out, "pub struct {} {{", self.name).unwrap();
+
+        for item in &self.items {
+            match item {
+                Item::Field(field) => {
+                    if let Some(display_name) = &field.display_name {
+                        for line in textwrap::wrap(display_name, COMMENT_WIDTH - "    /// ".len()) {
+                            writeln!(out, "    /// {line}").unwrap();
+                        }
+                    }
+                    if field.padding {
+                        writeln!(out, "    #[padding]").unwrap();
+                    }
+                    let ty = match field.kind {
+                        FieldKind::Array(len) => format!("[{}; {len}]", field.ty),
+                        _ => field.ty.to_string(),
+                    };
+                    writeln!(out, "    pub {}: {ty},", field.rust_name()).unwrap();
+                },
+                Item::Bitfield { name, ty, flags } => {
+                    for (bit, flag) in flags {
+                        writeln!(out, "    #[bitflag({}, {bit})]", fix_name(flag)).unwrap();
+                    }
+                    writeln!(out, "    pub {}: {ty},", fix_name(&to_snake_case(name))).unwrap();
+                },
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+    }
+}
+
+/// Reads the layouts of the params listed in `param_names`, in order,
+/// matching them to the paramdefs in `paramdex_path` by name.
+pub(crate) fn read_param_layouts(
+    paramdex_path: &Path,
+    param_names: &str,
+) -> Result<Vec<ParamLayout>> {
+    let defs_path = paramdex_path.join("DS3/Defs");
+    let mut def_files = HashMap::new();
+    for entry in fs::read_dir(&defs_path).with_context(|| format!("{defs_path:?}"))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "xml") {
+            let stem = path.file_stem().unwrap().to_string_lossy().replace("_ST", "");
+            def_files.insert(to_slug(&stem), path);
+        }
+    }
+
+    for (param, def) in DEF_CORRECTIONS {
+        if let Some(path) = def_files.get(*def).cloned() {
+            def_files.insert(param.to_string(), path);
+        }
+    }
+    for (_, def) in DEF_CORRECTIONS {
+        def_files.remove(*def);
+    }
+
+    let param_names =
+        param_names.lines().filter(|l| !l.is_empty()).map(|l| (to_slug(l), l)).collect::<Vec<_>>();
+
+    let slugs = param_names.iter().map(|(slug, _)| slug.as_str()).collect::<HashSet<_>>();
+    let mut unmatched = def_files
+        .keys()
+        .filter(|slug| !slugs.contains(slug.as_str()))
+        .chain(
+            param_names.iter().map(|(slug, _)| slug).filter(|slug| !def_files.contains_key(*slug)),
+        )
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unmatched.is_empty() {
+        unmatched.sort();
+        bail!("Params and paramdefs don't match: {}", unmatched.join(", "));
+    }
+
+    param_names
+        .into_iter()
+        .map(|(slug, name)| {
+            let def_path = &def_files[&slug];
+            let meta_path =
+                defs_path.parent().unwrap().join("Meta").join(def_path.file_name().unwrap());
+            let def = fs::read_to_string(def_path).with_context(|| format!("{def_path:?}"))?;
+            let meta = fs::read_to_string(&meta_path).ok();
+            ParamLayout::new(name, &def, meta.as_deref()).with_context(|| format!("{def_path:?}"))
+        })
+        .collect()
+}
+
+/// Reads the enums declared in every metadata file of `paramdex_path`.
+pub(crate) fn read_enums(paramdex_path: &Path) -> Result<Enums> {
+    let meta_path = paramdex_path.join("DS3/Meta");
+    let mut paths = fs::read_dir(&meta_path)
+        .with_context(|| format!("{meta_path:?}"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "xml"));
+    paths.sort();
+
+    let mut enums = Enums::new();
+    for path in paths {
+        let source = fs::read_to_string(&path).with_context(|| format!("{path:?}"))?;
+        let doc = parse_xml(&source).with_context(|| format!("{path:?}"))?;
+        for enum_node in doc
+            .root_element()
+            .children()
+            .filter(|n| n.has_tag_name("Enums"))
+            .flat_map(|n| n.children().filter(|n| n.has_tag_name("Enum")))
+        {
+            let Some(name) = enum_node.attribute("Name") else {
+                continue;
+            };
+            let options = enum_node
+                .children()
+                .filter(|n| n.has_tag_name("Option"))
+                .filter_map(|n| {
+                    Some((n.attribute("Value")?.to_string(), n.attribute("Name")?.to_string()))
+                })
+                .collect();
+            enums.insert(name.to_string(), options);
+        }
+    }
+
+    Ok(enums)
+}
+
+/// `param_data.rs`: the vtable of every param, and their structs.
+pub(crate) fn codegen_param_data(layouts: &[ParamLayout]) -> String {
+    let mut out = String::new();
+    writeln!(out, "{HEADER}").unwrap();
+
+    for layout in layouts {
+        let name = &layout.name;
+        let line = format!("        ({name:?}.to_string(), unsafe {{ get_vtable::<{name}>() }}),");
+        if line.len() <= MAX_WIDTH {
+            writeln!(out, "{line}").unwrap();
+        } else {
+            writeln!(out, "        ({name:?}.to_string(), unsafe {{").unwrap();
+            writeln!(out, "            get_vtable::<{name}>()").unwrap();
+            writeln!(out, "        }}),").unwrap();
+        }
+    }
+
+    writeln!(out, "    ]").unwrap();
+    writeln!(out, "    .into_iter()").unwrap();
+    writeln!(out, "    .collect()").unwrap();
+    writeln!(out, "}});").unwrap();
+
+    for (i, layout) in layouts.iter().enumerate() {
+        if i > 0 {
+            writeln!(out).unwrap();
+        }
+        layout.write_struct(&mut out);
+    }
+
+    out
+}
+
+/// A JSON file of fields by param name, leaving out the params without
+/// any.
+pub(crate) fn codegen_param_json<T: Serialize>(
+    layouts: &[ParamLayout],
+    fields: impl Fn(&ParamLayout) -> BTreeMap<String, T>,
+) -> String {
+    let params = layouts
+        .iter()
+        .map(|layout| (layout.name.clone(), fields(layout)))
+        .filter(|(_, fields)| !fields.is_empty())
+        .collect::<BTreeMap<_, _>>();
+
+    serde_json::to_string_pretty(&params).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/codegen/fixtures")
+    }
+
+    fn layouts() -> Vec<ParamLayout> {
+        let param_names = fs::read_to_string(fixtures().join("param_names.txt")).unwrap();
+        read_param_layouts(&fixtures().join("Paramdex"), &param_names).unwrap()
+    }
+
+    fn golden(name: &str) -> String {
+        fs::read_to_string(fixtures().join(name)).unwrap().replace("\r\n", "\n")
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(to_snake_case("hit0_Radius"), "hit0_radius");
+        assert_eq!(to_snake_case("IsArrowAtk"), "is_arrow_atk");
+        assert_eq!(to_snake_case("AIFoo"), "a_ifoo");
+        assert_eq!(to_snake_case("spEffectID_0"), "sp_effect_id_0");
+        assert_eq!(to_slug("ATK_PARAM_ST"), "atkparamst");
+        assert_eq!(fix_name("0x81"), "field0x81");
+        assert_eq!(fix_name("type"), "ty");
+    }
+
+    #[test]
+    fn test_param_data() {
+        assert_eq!(codegen_param_data(&layouts()), golden("param_data.rs"));
+    }
+
+    #[test]
+    fn test_enums_and_refs() {
+        let layouts = layouts();
+        let enums = read_enums(&fixtures().join("Paramdex")).unwrap();
+        let param_names = layouts.iter().map(|l| l.name.as_str()).collect::<HashSet<_>>();
+
+        assert_eq!(
+            codegen_param_json(&layouts, |layout| layout.enums(&enums)),
+            golden("param_enums.json")
+        );
+        assert_eq!(
+            codegen_param_json(&layouts, |layout| layout.refs(&param_names)),
+            golden("param_refs.json")
+        );
+    }
+
+    #[test]
+    fn test_unfilled_bitfields() {
+        let layout = |fields: &str| {
+            let def = format!("<PARAMDEF><Fields>{fields}</Fields></PARAMDEF>");
+            ParamLayout::new("TestParam", &def, None).map(|_| ()).map_err(|e| e.to_string())
+        };
+
+        assert_eq!(layout(r#"<Field Def="u8 a:4" /><Field Def="dummy8 b:4" />"#), Ok(()));
+        assert_eq!(
+            layout(r#"<Field Def="u8 a:4" /><Field Def="u8 b:5" />"#),
+            Err("TestParam: bitfield b overflows its u8".to_string())
+        );
+        assert_eq!(
+            layout(r#"<Field Def="u8 a:4" /><Field Def="s32 b" />"#),
+            Err("TestParam: bitfield a is not filled up before b".to_string())
+        );
+        assert_eq!(
+            layout(r#"<Field Def="u8 a:4" /><Field Def="u16 b:12" />"#),
+            Err("TestParam: bitfield a is not filled up before b".to_string())
+        );
+        assert_eq!(
+            layout(r#"<Field Def="u8 a:1" />"#),
+            Err("TestParam: bitfield a is not filled up at the end of the param".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unmatched_params() {
+        let e = read_param_layouts(&fixtures().join("Paramdex"), "AtkParam_Pc\nNotAParam\n")
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Params and paramdefs don't match: atkparamnpc, notaparam, speffectparam"
+        );
+    }
+}
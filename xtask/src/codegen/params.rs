@@ -1,40 +1,37 @@
-use std::env;
-use std::fs::File;
-use std::io::Write;
-use std::process::Command;
+use std::collections::HashSet;
+use std::fs;
 
-use anyhow::{bail, Context};
 use practice_tool_tasks::params::{checkout_paramdex, codegen_param_names};
 
+use super::paramdef::{codegen_param_data, codegen_param_json, read_enums, read_param_layouts};
 use crate::{project_root, Result};
 
 pub(crate) fn codegen() -> Result<()> {
     checkout_paramdex()?;
-    run_python_script()?;
+    codegen_params()?;
     codegen_param_names("target/Paramdex/DS3/Names", "lib/libds3/src/params/param_names.json")?;
 
     Ok(())
 }
 
-fn run_python_script() -> Result<()> {
-    let python = env::var("PYTHON").unwrap_or_else(|_| "python".to_string());
-    let cmd = Command::new(python)
-        .current_dir(project_root().join("target"))
-        .args(&[
-            project_root().join("xtask/src/codegen/codegen.py"),
-            project_root().join("target/Paramdex"),
-            project_root().join("xtask"),
-        ])
-        .output()
-        .context("python")?;
-
-    if !cmd.status.success() {
-        eprintln!("{}", std::str::from_utf8(&cmd.stderr).unwrap());
-        bail!("python codegen failed");
-    }
-
-    File::create(project_root().join("lib/libds3/src/params/param_data.rs"))?
-        .write_all(&cmd.stdout)?;
+fn codegen_params() -> Result<()> {
+    let paramdex_path = project_root().join("target/Paramdex");
+    let params_path = project_root().join("lib/libds3/src/params");
+
+    let param_names = fs::read_to_string(project_root().join("xtask/src/codegen/param_names.txt"))?;
+    let layouts = read_param_layouts(&paramdex_path, &param_names)?;
+    let enums = read_enums(&paramdex_path)?;
+    let names = layouts.iter().map(|l| l.name.as_str()).collect::<HashSet<_>>();
+
+    fs::write(params_path.join("param_data.rs"), codegen_param_data(&layouts))?;
+    fs::write(
+        params_path.join("param_enums.json"),
+        codegen_param_json(&layouts, |layout| layout.enums(&enums)),
+    )?;
+    fs::write(
+        params_path.join("param_refs.json"),
+        codegen_param_json(&layouts, |layout| layout.refs(&names)),
+    )?;
 
     Ok(())
 }
@@ -1,9 +1,11 @@
 mod codegen;
 
 use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::{env, fs, iter};
 
 use anyhow::{bail, Context, Result};
+use libds3::params::{validate_patch, Params, PatchConfig};
 use practice_tool_tasks::{
     cargo_command, project_root, steam_command, target_path, Distribution, FileInstall,
 };
@@ -18,6 +20,11 @@ fn main() -> Result<()> {
         Some("dist") => dist()?,
         Some("dist-param-mod") => dist_param_mod()?,
         Some("codegen") => codegen::codegen()?,
+        Some("base-addresses") => codegen::codegen_base_addresses()?,
+        Some("check-config") => check_config(env::args().nth(2))?,
+        Some("check-param-mod") => check_param_mod(env::args().nth(2))?,
+        Some("check-regulation") => check_regulation(env::args().nth(2))?,
+        Some("dry-run-param-mod") => dry_run_param_mod(env::args().nth(2), env::args().nth(3))?,
         Some("inject") => inject(env::args().skip(1).map(String::from))?,
         Some("run") => run()?,
         Some("run-param-tinkerer") => run_param_tinkerer()?,
@@ -37,6 +44,11 @@ Tasks:
 run ............. compile and start the practice tool
 dist ............ build distribution artifacts
 codegen ......... generate Rust code: parameters, base addresses, ...
+base-addresses .. scan $DSIII_PATCHES_PATH and generate base addresses only
+check-config .... validate a practice tool config (default: jdsd_dsiii_practice_tool.toml)
+check-param-mod . validate a param-mod.toml (default: lib/param-mod/param-mod.toml)
+check-regulation  check param struct sizes against a regulation (default: $DSIII_PATH/Data0.bdt)
+dry-run-param-mod list what a param-mod.toml would change in a regulation, .parambnd or .param
 inject <args> ... standalone dll inject
 install ......... install standalone dll to $DSIII_PATH
 uninstall ....... uninstall standalone dll from $DSIII_PATH
@@ -108,6 +120,115 @@ fn dist_param_mod() -> Result<()> {
         .build(&["--locked", "--release", "--workspace", "--exclude", "xtask"])
 }
 
+fn param_mod_path(path: Option<String>) -> PathBuf {
+    path.map(PathBuf::from).unwrap_or_else(|| project_root().join("lib/param-mod/param-mod.toml"))
+}
+
+fn regulation_path(path: Option<String>) -> Result<PathBuf> {
+    match path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => {
+            Ok(PathBuf::from(env::var("DSIII_PATH").context("DSIII_PATH not set")?)
+                .join("Data0.bdt"))
+        },
+    }
+}
+
+/// Reads params from a regulation, a parambnd or a single `.param` file.
+fn read_params(path: &Path) -> Result<Params> {
+    let data = fs::read(path).with_context(|| format!("Couldn't read {path:?}"))?;
+
+    if path.extension() == Some(OsStr::new("param")) {
+        let name = path.file_stem().context("No file name")?.to_string_lossy().into_owned();
+        return Ok(Params::from_param_files([(name, data)]));
+    }
+
+    Params::from_regulation(&data).map_err(anyhow::Error::msg)
+}
+
+fn check_config(path: Option<String>) -> Result<()> {
+    let path = path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| project_root().join("jdsd_dsiii_practice_tool.toml"));
+    let source = fs::read_to_string(&path).with_context(|| format!("Couldn't read {path:?}"))?;
+
+    match practice_tool_config::report_config(&path, &source) {
+        0 => Ok(()),
+        errors => bail!("{errors} errors found in {}", path.display()),
+    }
+}
+
+fn check_param_mod(path: Option<String>) -> Result<()> {
+    let path = param_mod_path(path);
+    let source = fs::read_to_string(&path).with_context(|| format!("Couldn't read {path:?}"))?;
+
+    let diagnostics = validate_patch(&source);
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", path.display());
+    }
+
+    if !diagnostics.is_empty() {
+        bail!("{} problems found in {}", diagnostics.len(), path.display());
+    }
+
+    eprintln!("{}: ok", path.display());
+
+    Ok(())
+}
+
+fn check_regulation(path: Option<String>) -> Result<()> {
+    let path = regulation_path(path)?;
+    let params = read_params(&path)?;
+
+    let errors = params.layout_errors();
+    for error in &errors {
+        eprintln!("{error}");
+    }
+
+    if !errors.is_empty() {
+        bail!("{} params don't match their struct in {}", errors.len(), path.display());
+    }
+
+    eprintln!("{}: {} params ok", path.display(), params.keys().count());
+
+    Ok(())
+}
+
+fn dry_run_param_mod(config: Option<String>, params: Option<String>) -> Result<()> {
+    let config_path = param_mod_path(config);
+    let params_path = regulation_path(params)?;
+
+    let source = fs::read_to_string(&config_path)
+        .with_context(|| format!("Couldn't read {config_path:?}"))?;
+    let diagnostics = validate_patch(&source);
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", config_path.display());
+    }
+
+    let config: PatchConfig = source.parse().map_err(anyhow::Error::msg)?;
+    let report = read_params(&params_path)?.dry_run_patch(&config);
+
+    println!(
+        "{} over {}: {} changes",
+        config_path.display(),
+        params_path.display(),
+        report.changes.len()
+    );
+    for change in &report.changes {
+        println!("  {change}");
+    }
+    for error in &report.errors {
+        eprintln!("  Error: {error}");
+    }
+
+    let problems = diagnostics.len() + report.errors.len();
+    if problems > 0 {
+        bail!("{problems} problems found in {}", config_path.display());
+    }
+
+    Ok(())
+}
+
 fn install() -> Result<()> {
     let status = cargo_command("build")
         .args(["--lib", "--release", "--package", "darksoulsiii-practice-tool"])